@@ -0,0 +1,473 @@
+//! Compares the results of two test runs.
+//!
+//! ## Usage
+//! ```
+//! $ gba_test_diff [--format <human|json>] <old.sav> <new.sav>
+//! ```
+//!
+//! Both files must contain test results serialized to SRAM by the test runner. Tests are matched
+//! by name, so the order in which they were run does not matter. Tests that newly fail, newly
+//! pass, were added, or were removed are printed.
+//!
+//! With `--format json`, the differences are instead printed as a single JSON object with the
+//! fields `newly_failing`, `newly_passing`, `added`, and `removed`, each an array of trials in the
+//! schema documented by `gba_test::TrialOwned`.
+//!
+//! The exit code is `0` if no tests newly fail, `1` if any do (including added tests that fail),
+//! and `2` if the results could not be compared.
+
+use gba_test::{read_trials, OutcomeOwned, TrialOwned, Truncation};
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+use std::{collections::BTreeMap, env, fmt, fs, process::ExitCode};
+
+/// The usage message printed when the arguments are invalid.
+const USAGE: &str = "usage: gba_test_diff [--format <human|json>] <old.sav> <new.sav>";
+
+/// How the differences are printed.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Format {
+    /// A list of tests under a heading for each kind of difference.
+    Human,
+    /// A single JSON object.
+    Json,
+}
+
+/// An error preventing two test runs from being compared.
+#[derive(Debug, Eq, PartialEq)]
+enum Error<'a> {
+    /// Not every test's result could be read.
+    Incomplete(Truncation<'a>),
+    /// Multiple tests within a single run share the same name.
+    DuplicateName(&'a str),
+}
+
+impl fmt::Display for Error<'_> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Incomplete(truncation) => {
+                write!(formatter, "results are incomplete: {}", truncation)
+            }
+            Self::DuplicateName(name) => write!(formatter, "multiple tests are named `{}`", name),
+        }
+    }
+}
+
+/// Deserializes the results of a test run from the contents of a save file.
+///
+/// Partial results are rejected, since the missing tests would otherwise be reported as removed.
+fn parse(bytes: &[u8]) -> Result<Vec<TrialOwned>, Error<'_>> {
+    match read_trials(bytes) {
+        (trials, None) => Ok(trials.into_iter().map(TrialOwned::from).collect()),
+        (_, Some(truncation)) => Err(Error::Incomplete(truncation)),
+    }
+}
+
+/// Indexes trials by name, failing if any names are repeated.
+fn index(trials: &[TrialOwned]) -> Result<BTreeMap<&str, &TrialOwned>, Error<'_>> {
+    let mut index = BTreeMap::new();
+    for trial in trials {
+        if index.insert(trial.name.as_str(), trial).is_some() {
+            return Err(Error::DuplicateName(&trial.name));
+        }
+    }
+    Ok(index)
+}
+
+/// Returns whether the outcome is a failure.
+fn failed(outcome: &OutcomeOwned) -> bool {
+    matches!(outcome, OutcomeOwned::Failed { .. })
+}
+
+/// The differences between two test runs.
+#[derive(Debug, Default, Eq, PartialEq)]
+struct Diff<'a> {
+    /// Tests that did not fail previously, but do now.
+    ///
+    /// This includes added tests that fail.
+    newly_failing: Vec<&'a TrialOwned>,
+    /// Tests that failed previously, but now pass.
+    newly_passing: Vec<&'a TrialOwned>,
+    /// Tests that were not previously run.
+    added: Vec<&'a TrialOwned>,
+    /// Tests that are no longer run.
+    removed: Vec<&'a TrialOwned>,
+}
+
+impl<'a> Diff<'a> {
+    /// Compares the trials of an old run with those of a new run.
+    fn new(old: &'a [TrialOwned], new: &'a [TrialOwned]) -> Result<Self, Error<'a>> {
+        let old = index(old)?;
+        let new = index(new)?;
+        let mut diff = Self::default();
+
+        for (name, trial) in &new {
+            match old.get(name) {
+                Some(previous) => {
+                    if failed(&trial.outcome) && !failed(&previous.outcome) {
+                        diff.newly_failing.push(trial);
+                    } else if trial.outcome == OutcomeOwned::Passed && failed(&previous.outcome) {
+                        diff.newly_passing.push(trial);
+                    }
+                }
+                None => {
+                    if failed(&trial.outcome) {
+                        diff.newly_failing.push(trial);
+                    }
+                    diff.added.push(trial);
+                }
+            }
+        }
+        for (name, trial) in &old {
+            if !new.contains_key(name) {
+                diff.removed.push(trial);
+            }
+        }
+
+        Ok(diff)
+    }
+}
+
+impl fmt::Display for Diff<'_> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        if !self.newly_failing.is_empty() {
+            writeln!(formatter, "newly failing:")?;
+            for trial in &self.newly_failing {
+                if let OutcomeOwned::Failed { message } = &trial.outcome {
+                    writeln!(formatter, "    {}: {}", trial.name, message)?;
+                }
+            }
+        }
+        if !self.newly_passing.is_empty() {
+            writeln!(formatter, "newly passing:")?;
+            for trial in &self.newly_passing {
+                writeln!(formatter, "    {}", trial.name)?;
+            }
+        }
+        if !self.added.is_empty() {
+            writeln!(formatter, "added:")?;
+            for trial in &self.added {
+                let outcome = match trial.outcome {
+                    OutcomeOwned::Passed => "passed",
+                    OutcomeOwned::Failed { .. } => "failed",
+                    OutcomeOwned::Ignored => "ignored",
+                };
+                writeln!(formatter, "    {} ({})", trial.name, outcome)?;
+            }
+        }
+        if !self.removed.is_empty() {
+            writeln!(formatter, "removed:")?;
+            for trial in &self.removed {
+                writeln!(formatter, "    {}", trial.name)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Serialize for Diff<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut diff = serializer.serialize_struct("Diff", 4)?;
+
+        diff.serialize_field("newly_failing", &self.newly_failing)?;
+        diff.serialize_field("newly_passing", &self.newly_passing)?;
+        diff.serialize_field("added", &self.added)?;
+        diff.serialize_field("removed", &self.removed)?;
+
+        diff.end()
+    }
+}
+
+fn main() -> ExitCode {
+    let mut format = Format::Human;
+    let mut paths = Vec::new();
+    let mut arguments = env::args().skip(1);
+    while let Some(argument) = arguments.next() {
+        if argument == "--format" {
+            format = match arguments.next().as_deref() {
+                Some("human") => Format::Human,
+                Some("json") => Format::Json,
+                _ => {
+                    eprintln!("{}", USAGE);
+                    return ExitCode::from(2);
+                }
+            };
+        } else {
+            paths.push(argument);
+        }
+    }
+    let [old_path, new_path] = paths.as_slice() else {
+        eprintln!("{}", USAGE);
+        return ExitCode::from(2);
+    };
+
+    let read = |path: &String| {
+        fs::read(path).map_err(|error| eprintln!("unable to read `{}`: {}", path, error))
+    };
+    let (Ok(old_bytes), Ok(new_bytes)) = (read(old_path), read(new_path)) else {
+        return ExitCode::from(2);
+    };
+    let parse =
+        |path: &String, bytes| parse(bytes).map_err(|error| eprintln!("`{}`: {}", path, error));
+    let (Ok(old), Ok(new)) = (parse(old_path, &old_bytes), parse(new_path, &new_bytes)) else {
+        return ExitCode::from(2);
+    };
+
+    match Diff::new(&old, &new) {
+        Ok(diff) => {
+            match format {
+                Format::Human => print!("{}", diff),
+                Format::Json => println!(
+                    "{}",
+                    serde_json::to_string(&diff).expect("unable to serialize differences")
+                ),
+            }
+            if diff.newly_failing.is_empty() {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            }
+        }
+        Err(error) => {
+            eprintln!("{}", error);
+            ExitCode::from(2)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, Diff, Error};
+    use gba_test::{OutcomeOwned, TrialOwned, Truncation};
+
+    /// The magic and format version that results written to SRAM begin with.
+    const HEADER: &[u8] = b"GBAT\x02\x00";
+
+    /// Serializes trials in the format written to SRAM, with `dropped` trials not fitting.
+    fn save(trials: &[TrialOwned], dropped: usize, seed: Option<u32>) -> Vec<u8> {
+        let mut bytes = HEADER.to_vec();
+        bytes.push(0);
+        bytes.extend(postcard::to_allocvec(&(trials.len() + dropped)).unwrap());
+        // The environment the tests were run in.
+        bytes.push(0);
+        for trial in trials {
+            let record = postcard::to_allocvec(trial).unwrap();
+            bytes.extend((record.len() as u16).to_le_bytes());
+            bytes.extend(record);
+        }
+        bytes.extend([0, 0]);
+        bytes.extend(postcard::to_allocvec(&(dropped, seed)).unwrap());
+        bytes
+    }
+
+    fn passed(name: &str) -> TrialOwned {
+        TrialOwned {
+            name: name.into(),
+            outcome: OutcomeOwned::Passed,
+            output: String::new(),
+            location: None,
+            suite: None,
+        }
+    }
+
+    fn failed(name: &str) -> TrialOwned {
+        TrialOwned {
+            name: name.into(),
+            outcome: OutcomeOwned::Failed {
+                message: "failed".into(),
+            },
+            output: String::new(),
+            location: None,
+            suite: None,
+        }
+    }
+
+    fn ignored(name: &str) -> TrialOwned {
+        TrialOwned {
+            name: name.into(),
+            outcome: OutcomeOwned::Ignored,
+            output: String::new(),
+            location: None,
+            suite: None,
+        }
+    }
+
+    #[test]
+    fn unchanged() {
+        let old = [passed("a"), failed("b"), ignored("c")];
+        let new = [passed("a"), failed("b"), ignored("c")];
+
+        assert_eq!(Diff::new(&old, &new), Ok(Diff::default()));
+    }
+
+    #[test]
+    fn reordered() {
+        let old = [passed("a"), failed("b"), ignored("c")];
+        let new = [ignored("c"), passed("a"), failed("b")];
+
+        assert_eq!(Diff::new(&old, &new), Ok(Diff::default()));
+    }
+
+    #[test]
+    fn newly_failing() {
+        let old = [passed("a"), ignored("b")];
+        let new = [failed("a"), failed("b")];
+
+        assert_eq!(
+            Diff::new(&old, &new),
+            Ok(Diff {
+                newly_failing: vec![&new[0], &new[1]],
+                ..Diff::default()
+            })
+        );
+    }
+
+    #[test]
+    fn newly_passing() {
+        let old = [failed("a"), failed("b")];
+        let new = [passed("a"), ignored("b")];
+
+        assert_eq!(
+            Diff::new(&old, &new),
+            Ok(Diff {
+                newly_passing: vec![&new[0]],
+                ..Diff::default()
+            })
+        );
+    }
+
+    #[test]
+    fn added() {
+        let old = [passed("a")];
+        let new = [passed("a"), passed("b"), failed("c")];
+
+        assert_eq!(
+            Diff::new(&old, &new),
+            Ok(Diff {
+                newly_failing: vec![&new[2]],
+                added: vec![&new[1], &new[2]],
+                ..Diff::default()
+            })
+        );
+    }
+
+    #[test]
+    fn removed() {
+        let old = [passed("a"), failed("b")];
+        let new = [passed("a")];
+
+        assert_eq!(
+            Diff::new(&old, &new),
+            Ok(Diff {
+                removed: vec![&old[1]],
+                ..Diff::default()
+            })
+        );
+    }
+
+    #[test]
+    fn duplicate_name_old() {
+        let old = [passed("a"), failed("a")];
+        let new = [passed("a")];
+
+        assert_eq!(Diff::new(&old, &new), Err(Error::DuplicateName("a")));
+    }
+
+    #[test]
+    fn duplicate_name_new() {
+        let old = [passed("a")];
+        let new = [passed("a"), passed("a")];
+
+        assert_eq!(Diff::new(&old, &new), Err(Error::DuplicateName("a")));
+    }
+
+    #[test]
+    fn display() {
+        let old = [passed("a"), failed("b"), passed("c")];
+        let new = [failed("a"), passed("b"), ignored("d")];
+
+        assert_eq!(
+            Diff::new(&old, &new).unwrap().to_string(),
+            "newly failing:\n    a: failed\nnewly passing:\n    b\nadded:\n    d (ignored)\nremoved:\n    c\n"
+        );
+    }
+
+    #[test]
+    fn json() {
+        let old = [passed("a"), failed("b"), passed("c")];
+        let new = [failed("a"), passed("b"), ignored("d")];
+
+        assert_eq!(
+            serde_json::to_string(&Diff::new(&old, &new).unwrap()).unwrap(),
+            concat!(
+                r#"{"newly_failing":[{"name":"a","outcome":{"Failed":{"message":"failed"}},"output":"","location":null,"suite":null}],"#,
+                r#""newly_passing":[{"name":"b","outcome":"Passed","output":"","location":null,"suite":null}],"#,
+                r#""added":[{"name":"d","outcome":"Ignored","output":"","location":null,"suite":null}],"#,
+                r#""removed":[{"name":"c","outcome":"Passed","output":"","location":null,"suite":null}]}"#,
+            )
+        );
+    }
+
+    #[test]
+    fn parse_results() {
+        let trials = vec![passed("a"), failed("b")];
+        let bytes = save(&trials, 0, None);
+
+        assert_eq!(parse(&bytes), Ok(trials));
+    }
+
+    #[test]
+    fn parse_results_with_seed() {
+        let trials = vec![passed("a")];
+        let bytes = save(&trials, 0, Some(42));
+
+        assert_eq!(parse(&bytes), Ok(trials));
+    }
+
+    #[test]
+    fn parse_incomplete() {
+        let mut bytes = HEADER.to_vec();
+        bytes.extend(postcard::to_allocvec(&Err::<(), _>("out of memory")).unwrap());
+
+        assert_eq!(
+            parse(&bytes),
+            Err(Error::Incomplete(Truncation::Aborted {
+                message: "out of memory"
+            }))
+        );
+    }
+
+    #[test]
+    fn parse_out_of_space() {
+        let bytes = save(&[passed("a")], 2, None);
+
+        assert_eq!(
+            parse(&bytes),
+            Err(Error::Incomplete(Truncation::OutOfSpace { dropped: 2 }))
+        );
+    }
+
+    #[test]
+    fn parse_version_mismatch() {
+        let mut bytes = save(&[passed("a")], 0, None);
+        bytes[4] = 3;
+
+        assert_eq!(
+            parse(&bytes),
+            Err(Error::Incomplete(Truncation::VersionMismatch {
+                expected: 2,
+                found: 3
+            }))
+        );
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert_eq!(
+            parse(&[0xFF]),
+            Err(Error::Incomplete(Truncation::Incomplete))
+        );
+    }
+}
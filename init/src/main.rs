@@ -0,0 +1,377 @@
+//! Sets up a crate for running tests on the Game Boy Advance.
+//!
+//! ## Usage
+//! ```
+//! $ gba_test_init [--force] [<crate directory>]
+//! ```
+//!
+//! This writes the files a crate needs to build and run its tests as a ROM:
+//!
+//! - `.cargo/config.toml`, configuring the target, linker script, test runner, and `build-std`.
+//!   If the file already exists, only the required settings are updated, leaving everything else
+//!   intact.
+//! - `linker_script.ld`, `build.rs`, and `src/rsrt0.s`, which lay out the ROM and assemble its
+//!   entry point.
+//! - `src/lib.rs`, containing the test harness and an example test.
+//!
+//! Existing files other than `.cargo/config.toml` are left alone unless their contents are the
+//! same as would be written, or `--force` is passed. The crate must still depend on `gba_test`
+//! with the `macros` and `runner` features enabled.
+//!
+//! The exit code is `0` if every file was written or already up to date, `1` if any files were
+//! skipped, and `2` if the crate could not be set up.
+
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+/// A setting that must be present in `.cargo/config.toml`.
+struct Setting {
+    /// The table containing the setting, without brackets.
+    table: &'static str,
+    key: &'static str,
+    /// The value, as written in TOML.
+    value: &'static str,
+}
+
+/// The settings written to `.cargo/config.toml`.
+const SETTINGS: &[Setting] = &[
+    Setting {
+        table: "build",
+        key: "target",
+        value: "\"thumbv4t-none-eabi\"",
+    },
+    Setting {
+        table: "target.thumbv4t-none-eabi",
+        key: "runner",
+        value: "\"mgba -C savegamePath=.\"",
+    },
+    Setting {
+        table: "target.thumbv4t-none-eabi",
+        key: "rustflags",
+        value: "[\"-Clink-arg=-Tlinker_script.ld\"]",
+    },
+    Setting {
+        table: "unstable",
+        key: "build-std",
+        value: "[\"core\"]",
+    },
+];
+
+/// Files written verbatim, relative to the crate directory.
+const FILES: &[(&str, &str)] = &[
+    (
+        "linker_script.ld",
+        include_str!("../templates/linker_script.ld"),
+    ),
+    ("build.rs", include_str!("../templates/build.rs")),
+    ("src/rsrt0.s", include_str!("../templates/rsrt0.s")),
+    ("src/lib.rs", include_str!("../templates/lib.rs")),
+];
+
+/// Returns whether `line` is the header of a table.
+fn is_header(line: &str) -> bool {
+    line.trim_start().starts_with('[')
+}
+
+/// Returns whether `line` assigns a value to `key`.
+fn assigns(line: &str, key: &str) -> bool {
+    line.trim_start()
+        .strip_prefix(key)
+        .is_some_and(|rest| rest.trim_start().starts_with('='))
+}
+
+/// Returns the number of lines taken up by the value assigned on `lines[0]`.
+///
+/// Arrays may span multiple lines, in which case the following lines up to the closing bracket are
+/// counted as well.
+fn assignment_len(lines: &[String]) -> usize {
+    let mut depth = 0i32;
+    for (index, line) in lines.iter().enumerate() {
+        // Brackets within strings are rare enough in cargo configuration to not be worth
+        // handling.
+        let line = line.split('#').next().unwrap_or_default();
+        depth += line.matches('[').count() as i32 - line.matches(']').count() as i32;
+        if depth <= 0 {
+            return index + 1;
+        }
+    }
+    lines.len()
+}
+
+/// Sets `key` within `table` to `value`, adding the table if it is not present.
+fn set(lines: &mut Vec<String>, table: &str, key: &str, value: &str) {
+    let assignment = format!("{} = {}", key, value);
+    let header = format!("[{}]", table);
+    let Some(start) = lines.iter().position(|line| line.trim() == header) else {
+        if lines.last().is_some_and(|line| !line.trim().is_empty()) {
+            lines.push(String::new());
+        }
+        lines.push(header);
+        lines.push(assignment);
+        return;
+    };
+    let end = lines[start + 1..]
+        .iter()
+        .position(|line| is_header(line))
+        .map_or(lines.len(), |offset| start + 1 + offset);
+
+    if let Some(offset) = lines[start + 1..end]
+        .iter()
+        .position(|line| assigns(line, key))
+    {
+        let index = start + 1 + offset;
+        let len = assignment_len(&lines[index..end]);
+        lines.splice(index..index + len, [assignment]);
+    } else {
+        // Insert after the table's last non-blank line, keeping any blank lines separating it
+        // from the next table.
+        let index = lines[start + 1..end]
+            .iter()
+            .rposition(|line| !line.trim().is_empty())
+            .map_or(start + 1, |offset| start + 2 + offset);
+        lines.insert(index, assignment);
+    }
+}
+
+/// Returns `config` with the required settings added or updated.
+///
+/// Everything else in `config`, including comments, is preserved.
+fn merge_config(config: &str) -> String {
+    let mut lines: Vec<String> = config.lines().map(String::from).collect();
+    for setting in SETTINGS {
+        set(&mut lines, setting.table, setting.key, setting.value);
+    }
+    let mut merged = lines.join("\n");
+    merged.push('\n');
+    merged
+}
+
+/// What happened to a file.
+#[derive(Debug, Eq, PartialEq)]
+enum Status {
+    Created,
+    Updated,
+    Unchanged,
+    /// The file exists with other contents, and was not overwritten.
+    Skipped,
+}
+
+/// Writes `contents` to `path`, unless the file exists with different contents and `force` is not
+/// set.
+fn write(path: &Path, contents: &str, force: bool) -> io::Result<Status> {
+    let status = match fs::read_to_string(path) {
+        Ok(existing) if existing == contents => return Ok(Status::Unchanged),
+        Ok(_) if !force => return Ok(Status::Skipped),
+        Ok(_) => Status::Updated,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Status::Created,
+        Err(error) => return Err(error),
+    };
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, contents)?;
+    Ok(status)
+}
+
+/// Sets up the crate in `directory`, printing what was done with each file.
+///
+/// Returns whether any files were skipped.
+fn init(directory: &Path, force: bool) -> io::Result<bool> {
+    let mut skipped = false;
+    let mut report = |path: &Path, status| {
+        let status = match status {
+            Status::Created => "created",
+            Status::Updated => "updated",
+            Status::Unchanged => "unchanged",
+            Status::Skipped => {
+                skipped = true;
+                "skipped, as it already exists (use `--force` to overwrite)"
+            }
+        };
+        println!("{}: {}", path.display(), status);
+    };
+
+    let config_path = directory.join(".cargo/config.toml");
+    let config = match fs::read_to_string(&config_path) {
+        Ok(config) => config,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(error) => return Err(error),
+    };
+    // The configuration is always merged rather than overwritten, so it never needs forcing.
+    let status = write(&config_path, &merge_config(&config), true)?;
+    report(&config_path, status);
+
+    for (path, contents) in FILES {
+        let path = directory.join(path);
+        let status = write(&path, contents, force)?;
+        report(&path, status);
+    }
+
+    Ok(skipped)
+}
+
+fn main() -> ExitCode {
+    let mut force = false;
+    let mut directory = None;
+    for argument in env::args().skip(1) {
+        if argument == "--force" {
+            force = true;
+        } else if directory.is_none() && !argument.starts_with('-') {
+            directory = Some(PathBuf::from(argument));
+        } else {
+            eprintln!("usage: gba_test_init [--force] [<crate directory>]");
+            return ExitCode::from(2);
+        }
+    }
+    let directory = directory.unwrap_or_else(|| PathBuf::from("."));
+    if !directory.join("Cargo.toml").is_file() {
+        eprintln!("`{}` does not contain a crate", directory.display());
+        return ExitCode::from(2);
+    }
+
+    match init(&directory, force) {
+        Ok(false) => ExitCode::SUCCESS,
+        Ok(true) => ExitCode::from(1),
+        Err(error) => {
+            eprintln!("unable to set up crate: {}", error);
+            ExitCode::from(2)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::merge_config;
+
+    const GENERATED: &str = "\
+[build]
+target = \"thumbv4t-none-eabi\"
+
+[target.thumbv4t-none-eabi]
+runner = \"mgba -C savegamePath=.\"
+rustflags = [\"-Clink-arg=-Tlinker_script.ld\"]
+
+[unstable]
+build-std = [\"core\"]
+";
+
+    #[test]
+    fn empty() {
+        assert_eq!(merge_config(""), GENERATED);
+    }
+
+    #[test]
+    fn already_configured() {
+        assert_eq!(merge_config(GENERATED), GENERATED);
+    }
+
+    #[test]
+    fn idempotent() {
+        let config = "[alias]\nt = \"test\"\n\n[build]\njobs = 4\n";
+        assert_eq!(merge_config(&merge_config(config)), merge_config(config));
+    }
+
+    #[test]
+    fn preserves_other_tables() {
+        assert_eq!(
+            merge_config("# Aliases.\n[alias]\nt = \"test\"\n"),
+            format!("# Aliases.\n[alias]\nt = \"test\"\n\n{}", GENERATED)
+        );
+    }
+
+    #[test]
+    fn adds_to_existing_table() {
+        assert_eq!(
+            merge_config("[build]\njobs = 4\n\n[alias]\nt = \"test\"\n"),
+            "\
+[build]
+jobs = 4
+target = \"thumbv4t-none-eabi\"
+
+[alias]
+t = \"test\"
+
+[target.thumbv4t-none-eabi]
+runner = \"mgba -C savegamePath=.\"
+rustflags = [\"-Clink-arg=-Tlinker_script.ld\"]
+
+[unstable]
+build-std = [\"core\"]
+"
+        );
+    }
+
+    #[test]
+    fn adds_to_empty_table() {
+        assert_eq!(
+            merge_config("[build]\n\n[alias]\nt = \"test\"\n"),
+            "\
+[build]
+target = \"thumbv4t-none-eabi\"
+
+[alias]
+t = \"test\"
+
+[target.thumbv4t-none-eabi]
+runner = \"mgba -C savegamePath=.\"
+rustflags = [\"-Clink-arg=-Tlinker_script.ld\"]
+
+[unstable]
+build-std = [\"core\"]
+"
+        );
+    }
+
+    #[test]
+    fn replaces_wrong_values() {
+        assert_eq!(
+            merge_config(
+                "[build]\ntarget = \"thumbv4t-none-eabi.json\"\n\n[target.thumbv4t-none-eabi]\nrunner = \"mgba\"\n"
+            ),
+            GENERATED
+        );
+    }
+
+    #[test]
+    fn replaces_multiline_array() {
+        assert_eq!(
+            merge_config(
+                "[target.thumbv4t-none-eabi]\nrustflags = [\n    \"-Ctarget-cpu=arm7tdmi\",\n    \"-Clink-arg=-Tlink.ld\",\n]\n# Runner.\nrunner = \"mgba\"\n"
+            ),
+            "\
+[target.thumbv4t-none-eabi]
+rustflags = [\"-Clink-arg=-Tlinker_script.ld\"]
+# Runner.
+runner = \"mgba -C savegamePath=.\"
+
+[build]
+target = \"thumbv4t-none-eabi\"
+
+[unstable]
+build-std = [\"core\"]
+"
+        );
+    }
+
+    #[test]
+    fn ignores_keys_with_same_prefix() {
+        assert_eq!(
+            merge_config("[unstable]\nbuild-std-features = [\"compiler-builtins-mem\"]\n"),
+            "\
+[unstable]
+build-std-features = [\"compiler-builtins-mem\"]
+build-std = [\"core\"]
+
+[build]
+target = \"thumbv4t-none-eabi\"
+
+[target.thumbv4t-none-eabi]
+runner = \"mgba -C savegamePath=.\"
+rustflags = [\"-Clink-arg=-Tlinker_script.ld\"]
+"
+        );
+    }
+}
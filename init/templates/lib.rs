@@ -0,0 +1,17 @@
+#![no_std]
+#![no_main]
+#![feature(custom_test_frameworks)]
+#![test_runner(gba_test::runner)]
+#![reexport_test_harness_main = "test_harness"]
+
+gba_test::harness_main!();
+
+#[cfg(test)]
+mod tests {
+    use gba_test::test;
+
+    #[test]
+    fn it_works() {
+        assert_eq!(2 + 2, 4);
+    }
+}
@@ -21,7 +21,8 @@
 //! ```
 //!
 //! Note that you should use the `#[test]` attribute provided by this crate, **not** the default
-//! `#[test]` attribute.
+//! `#[test]` attribute. Annotating a test with both, or with another test attribute such as
+//! `#[bench]` or `#[tokio::test]`, is an error.
 //!
 //! Also note that use of this macro currently depends on the
 //! [`custom_test_frameworks`](https://doc.rust-lang.org/beta/unstable-book/language-features/custom-test-frameworks.html)
@@ -29,33 +30,338 @@
 //! this crate.
 
 use proc_macro::TokenStream;
-use proc_macro2::Span;
-use quote::quote;
-use syn::{parse, Attribute, Ident, ItemFn};
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote, quote_spanned};
+use syn::{
+    parenthesized, parse, parse::Parser, punctuated::Punctuated, spanned::Spanned, Attribute, Expr,
+    Ident, ItemFn, LitInt, LitStr, Meta, MetaNameValue, ReturnType, Token, Type,
+};
+
+/// The maximum length of a test's name, in characters, if no other limit is provided.
+const DEFAULT_MAX_NAME_LENGTH: usize = 48;
+
+/// The instruction set a test is run in, as configured by `isa = "<set>"`.
+#[derive(Clone, Copy)]
+enum Isa {
+    Arm,
+    Thumb,
+    /// The test is run once in each instruction set.
+    Both,
+}
+
+impl Isa {
+    /// Parses the value of an `isa` argument.
+    fn parse(value: &LitStr) -> Result<Self, syn::Error> {
+        match value.value().as_str() {
+            "arm" => Ok(Self::Arm),
+            "thumb" => Ok(Self::Thumb),
+            "both" => Ok(Self::Both),
+            _ => Err(syn::Error::new(
+                value.span(),
+                "expected `\"arm\"`, `\"thumb\"`, or `\"both\"`",
+            )),
+        }
+    }
+}
+
+/// The hardware a test requires, as configured by `requires = "<capability>"`.
+#[derive(Clone, Copy)]
+enum Capability {
+    Sram,
+    Rtc,
+    LinkCable,
+}
+
+impl Capability {
+    /// Parses the value of a `requires` argument.
+    fn parse(value: &LitStr) -> Result<Self, syn::Error> {
+        match value.value().as_str() {
+            "sram" => Ok(Self::Sram),
+            "rtc" => Ok(Self::Rtc),
+            "link_cable" => Ok(Self::LinkCable),
+            _ => Err(syn::Error::new(
+                value.span(),
+                "expected `\"sram\"`, `\"rtc\"`, or `\"link_cable\"`",
+            )),
+        }
+    }
+}
+
+/// Structured representation of the arguments provided to the `#[test]` attribute itself.
+struct Arguments {
+    /// The values a parameterized test is run with, if provided using `cases(...)`.
+    cases: Option<Punctuated<Expr, Token![,]>>,
+    /// The instruction set the test is run in, if provided using `isa = "<set>"`.
+    isa: Option<Isa>,
+    /// The number of frames the test may take, if provided using `max_frames = <int>`.
+    max_frames: Option<u32>,
+    /// The maximum length of the test's name, as provided using `max_name_length = <int>`.
+    max_name_length: usize,
+    /// The hardware the test requires, if provided using `requires = "<capability>"`.
+    requires: Option<Capability>,
+    /// Whether a name exceeding `max_name_length` is an error, as set by `strict_names`.
+    strict_names: bool,
+    /// The suite the test belongs to, if provided using `suite = "<name>"`.
+    suite: Option<LitStr>,
+}
+
+impl Arguments {
+    /// Parses the arguments of a `#[test]` attribute.
+    ///
+    /// This accepts `cases(<expr>, ...)`, `isa = "<set>"`, `max_frames = <int>`,
+    /// `max_name_length = <int>`, `requires = "<capability>"`, `strict_names`, and
+    /// `suite = "<name>"`, in any order.
+    fn parse(attr: TokenStream) -> Result<Self, syn::Error> {
+        let mut cases = None;
+        let mut isa = None;
+        let mut max_frames = None;
+        let mut max_name_length = None;
+        let mut requires = None;
+        let mut strict_names = false;
+        let mut suite = None;
+        let parser = syn::meta::parser(|meta| {
+            if meta.path.is_ident("cases") {
+                if cases.is_some() {
+                    return Err(meta.error("duplicate `cases(...)`"));
+                }
+                let content;
+                parenthesized!(content in meta.input);
+                let parsed = Punctuated::parse_terminated(&content)?;
+                if parsed.is_empty() {
+                    return Err(meta.error("expected at least one case"));
+                }
+                cases = Some(parsed);
+                Ok(())
+            } else if meta.path.is_ident("isa") {
+                if isa.is_some() {
+                    return Err(meta.error("duplicate `isa`"));
+                }
+                isa = Some(Isa::parse(&meta.value()?.parse()?)?);
+                Ok(())
+            } else if meta.path.is_ident("max_frames") {
+                if max_frames.is_some() {
+                    return Err(meta.error("duplicate `max_frames`"));
+                }
+                let frames: LitInt = meta.value()?.parse()?;
+                max_frames = Some(frames.base10_parse()?);
+                Ok(())
+            } else if meta.path.is_ident("max_name_length") {
+                if max_name_length.is_some() {
+                    return Err(meta.error("duplicate `max_name_length`"));
+                }
+                let length: LitInt = meta.value()?.parse()?;
+                max_name_length = Some(length.base10_parse()?);
+                Ok(())
+            } else if meta.path.is_ident("requires") {
+                if requires.is_some() {
+                    return Err(meta.error("duplicate `requires`"));
+                }
+                requires = Some(Capability::parse(&meta.value()?.parse()?)?);
+                Ok(())
+            } else if meta.path.is_ident("strict_names") {
+                if strict_names {
+                    return Err(meta.error("duplicate `strict_names`"));
+                }
+                strict_names = true;
+                Ok(())
+            } else if meta.path.is_ident("suite") {
+                if suite.is_some() {
+                    return Err(meta.error("duplicate `suite`"));
+                }
+                suite = Some(meta.value()?.parse()?);
+                Ok(())
+            } else {
+                Err(meta.error(
+                    "expected `cases(...)`, `isa = ...`, `max_frames = ...`, `max_name_length = ...`, `requires = ...`, `strict_names`, or `suite = ...`",
+                ))
+            }
+        });
+        parser.parse(attr)?;
+        Ok(Self {
+            cases,
+            isa,
+            max_frames,
+            max_name_length: max_name_length.unwrap_or(DEFAULT_MAX_NAME_LENGTH),
+            requires,
+            strict_names,
+            suite,
+        })
+    }
+}
+
+/// Whether a test is expected to panic, as configured by the `#[should_panic]` attribute.
+enum ShouldPanic {
+    No,
+    Yes,
+    /// The test must panic with a message containing `expected`, in a file whose path contains
+    /// `location`, if either is provided.
+    YesWith {
+        expected: Option<Box<Expr>>,
+        location: Option<Box<Expr>>,
+    },
+}
+
+impl ShouldPanic {
+    /// Parses the arguments of a `#[should_panic]` attribute.
+    ///
+    /// This accepts `#[should_panic]`, `#[should_panic = <expr>]`, and
+    /// `#[should_panic(expected = <expr>, location = <expr>)]`, where either argument may be
+    /// omitted.
+    fn parse(attribute: &Attribute) -> Result<Self, syn::Error> {
+        match &attribute.meta {
+            Meta::Path(_) => Ok(Self::Yes),
+            Meta::NameValue(name_value) => Ok(Self::YesWith {
+                expected: Some(Box::new(name_value.value.clone())),
+                location: None,
+            }),
+            Meta::List(list) => {
+                let mut expected = None;
+                let mut location = None;
+                list.parse_nested_meta(|meta| {
+                    let (argument, name) = if meta.path.is_ident("expected") {
+                        (&mut expected, "expected")
+                    } else if meta.path.is_ident("location") {
+                        (&mut location, "location")
+                    } else {
+                        return Err(meta
+                            .error("expected `expected = \"message\"` or `location = \"file\"`"));
+                    };
+                    if argument.is_some() {
+                        return Err(meta.error(format!("duplicate `{}`", name)));
+                    }
+                    *argument = Some(Box::new(meta.value()?.parse()?));
+                    Ok(())
+                })?;
+                Ok(Self::YesWith { expected, location })
+            }
+        }
+    }
+}
+
+/// Whether a test is expected to return an `Err`, as configured by the `#[should_err]` attribute.
+enum ShouldErr {
+    No,
+    Yes,
+    /// The test must return an `Err` whose `Debug` representation contains the given message.
+    YesWithMessage(Box<Expr>),
+}
+
+impl ShouldErr {
+    /// Parses the arguments of a `#[should_err]` attribute.
+    ///
+    /// This accepts `#[should_err]`, `#[should_err = <expr>]`, and
+    /// `#[should_err(expected = <expr>)]`.
+    fn parse(attribute: &Attribute) -> Result<Self, syn::Error> {
+        match &attribute.meta {
+            Meta::Path(_) => Ok(Self::Yes),
+            Meta::NameValue(name_value) => {
+                Ok(Self::YesWithMessage(Box::new(name_value.value.clone())))
+            }
+            Meta::List(list) => {
+                let mut expected = None;
+                list.parse_nested_meta(|meta| {
+                    if !meta.path.is_ident("expected") {
+                        return Err(meta.error("expected `expected = \"message\"`"));
+                    }
+                    if expected.is_some() {
+                        return Err(meta.error("duplicate `expected`"));
+                    }
+                    expected = Some(Box::new(meta.value()?.parse()?));
+                    Ok(())
+                })?;
+                Ok(expected.map_or(Self::Yes, Self::YesWithMessage))
+            }
+        }
+    }
+}
 
 /// Structured representation of the configuration attributes provided for a test.
 struct Attributes {
-    ignore: Ident,
+    /// Whether the test is ignored, along with the reason given by `#[ignore = <expr>]`, if any.
+    ignore: Option<Option<Expr>>,
+    should_panic: ShouldPanic,
+    should_err: ShouldErr,
 }
 
 impl Attributes {
     /// Returns the default configuration attributes for a test.
     fn new() -> Self {
         Self {
-            ignore: Ident::new("No", Span::call_site()),
+            ignore: None,
+            should_panic: ShouldPanic::No,
+            should_err: ShouldErr::No,
         }
     }
 }
 
-impl From<&Vec<Attribute>> for Attributes {
-    fn from(attributes: &Vec<Attribute>) -> Self {
+impl TryFrom<&Vec<Attribute>> for Attributes {
+    type Error = syn::Error;
+
+    fn try_from(attributes: &Vec<Attribute>) -> Result<Self, Self::Error> {
         let mut result = Attributes::new();
 
         for attribute in attributes {
-            if let Some(ident) = attribute.path().get_ident() {
+            let path = attribute.path();
+            // Another test attribute would define a second, conflicting test. Depending on how
+            // the attributes are resolved, the test may then never run on the Game Boy Advance.
+            if path.is_ident("bench")
+                || path
+                    .segments
+                    .last()
+                    .is_some_and(|segment| segment.ident == "test")
+            {
+                let mut name = String::new();
+                if path.leading_colon.is_some() {
+                    name.push_str("::");
+                }
+                for (index, segment) in path.segments.iter().enumerate() {
+                    if index > 0 {
+                        name.push_str("::");
+                    }
+                    name.push_str(&segment.ident.to_string());
+                }
+                return Err(syn::Error::new_spanned(
+                    attribute,
+                    format!("`#[{}]` conflicts with `#[gba_test::test]`", name),
+                ));
+            }
+            if let Some(ident) = path.get_ident() {
                 match ident.to_string().as_str() {
                     "ignore" => {
-                        result.ignore = Ident::new("Yes", Span::call_site());
+                        if result.ignore.is_some() {
+                            return Err(syn::Error::new_spanned(
+                                attribute,
+                                "duplicate `#[ignore]` attribute",
+                            ));
+                        }
+                        result.ignore = Some(match &attribute.meta {
+                            Meta::NameValue(name_value) => Some(name_value.value.clone()),
+                            _ => None,
+                        });
+                    }
+                    "should_panic" => {
+                        if !matches!(result.should_err, ShouldErr::No) {
+                            return Err(syn::Error::new_spanned(
+                                attribute,
+                                "`#[should_panic]` and `#[should_err]` cannot be used together",
+                            ));
+                        }
+                        result.should_panic = ShouldPanic::parse(attribute)?;
+                    }
+                    "should_err" => {
+                        if !matches!(result.should_err, ShouldErr::No) {
+                            return Err(syn::Error::new_spanned(
+                                attribute,
+                                "duplicate `#[should_err]` attribute",
+                            ));
+                        }
+                        if !matches!(result.should_panic, ShouldPanic::No) {
+                            return Err(syn::Error::new_spanned(
+                                attribute,
+                                "`#[should_panic]` and `#[should_err]` cannot be used together",
+                            ));
+                        }
+                        result.should_err = ShouldErr::parse(attribute)?;
                     }
                     // Ignore all other attributes.
                     _ => {}
@@ -63,7 +369,7 @@ impl From<&Vec<Attribute>> for Attributes {
             }
         }
 
-        result
+        Ok(result)
     }
 }
 
@@ -78,15 +384,463 @@ impl From<&Vec<Attribute>> for Attributes {
 ///     assert!(true);
 /// }
 /// ```
+///
+/// Tests annotated with `#[ignore]` are not run. A reason can be given using
+/// `#[ignore = "reason"]`, which is available through `Ignore::message()`. Tests that can only
+/// tell whether they apply once running can instead skip themselves using `gba_test::skip!`.
+///
+/// Tests that are expected to panic can be annotated with `#[should_panic]`. An expected message
+/// can be provided as any expression evaluating to a `&'static str`, and the test will only pass
+/// if the panic message contains it:
+///
+/// ```
+/// # #![feature(custom_test_frameworks)]
+/// #
+/// const MESSAGE: &str = "foo";
+///
+/// #[gba_test_macros::test]
+/// #[should_panic(expected = MESSAGE)]
+/// fn foo() {
+///     panic!("foo");
+/// }
+/// #
+/// # fn main() {}
+/// ```
+///
+/// Similarly, `location = <expr>` requires the panic to occur in a file whose path contains the
+/// given string. If both `expected` and `location` are provided, both must match:
+///
+/// ```
+/// # #![feature(custom_test_frameworks)]
+/// #
+/// #[gba_test_macros::test]
+/// #[should_panic(expected = "foo", location = "src/")]
+/// fn foo() {
+///     panic!("foo");
+/// }
+/// #
+/// # fn main() {}
+/// ```
+///
+/// Tests may return any type implementing `gba_test::Termination`, such as `Result` or `Option`,
+/// in which case returning an error or `None` fails the test. Such tests can't be annotated with
+/// `#[should_panic]`.
+///
+/// Tests returning a `Result` can instead be annotated with `#[should_err]`, in which case they
+/// pass only if they return an `Err`. An expected message can be provided in the same way as for
+/// `#[should_panic]`, and the test will only pass if the `Debug` representation of the error
+/// contains it:
+///
+/// ```
+/// # #![feature(custom_test_frameworks)]
+/// #
+/// #[gba_test_macros::test]
+/// #[should_err(expected = "empty")]
+/// fn foo() -> Result<u32, &'static str> {
+///     "".parse::<u32>().map_err(|_| "empty input")
+/// }
+/// #
+/// # fn main() {}
+/// ```
+///
+/// A test can be given a budget of frames using `max_frames = <int>`. If the test takes more than
+/// that many whole frames to complete, it fails even if it would otherwise have passed.
+///
+/// ```
+/// # #![feature(custom_test_frameworks)]
+/// #
+/// #[gba_test_macros::test(max_frames = 5)]
+/// fn fast() {
+///     assert!(true);
+/// }
+/// ```
+///
+/// Tests can be grouped into suites independently of their modules using `suite = "<name>"`,
+/// such as for tests that are slow or that require real hardware. The suite is reported alongside
+/// the test's result.
+///
+/// ```
+/// # #![feature(custom_test_frameworks)]
+/// #
+/// #[gba_test_macros::test(suite = "slow")]
+/// fn thorough() {
+///     assert!(true);
+/// }
+/// ```
+///
+/// Tests that need a peripheral can declare it using `requires = "<capability>"`, where the
+/// capability is one of `"sram"`, `"rtc"`, or `"link_cable"`. If the peripheral isn't detected
+/// when the test is run, the test is reported as ignored instead.
+///
+/// ```
+/// # #![feature(custom_test_frameworks)]
+/// #
+/// #[gba_test_macros::test(requires = "rtc")]
+/// fn reads_clock() {
+///     assert!(true);
+/// }
+/// ```
+///
+/// A test can be run in a specific instruction set using `isa = "arm"` or `isa = "thumb"`, in
+/// which case it is called from a function compiled for that instruction set. This helps to catch
+/// interworking bugs in code that may be called from either. Using `isa = "both"` runs the test
+/// once in each, reported as two tests with `::arm` and `::thumb` appended to the name.
+///
+/// ```
+/// # #![feature(custom_test_frameworks)]
+/// #
+/// #[gba_test_macros::test(isa = "both")]
+/// fn interworking() {
+///     assert!(true);
+/// }
+/// ```
+///
+/// Names longer than 48 characters result in a warning. The limit can be changed using
+/// `max_name_length = <int>`, and exceeding it can be made an error using `strict_names`.
+///
+/// A test taking a single parameter can be run once for each of a list of values using
+/// `cases(...)`. Each case is reported as a separate test, with the value appended to the test's
+/// name, such as `doubles[2]`. `#[ignore]`, `#[should_panic]`, `#[should_err]`, `isa`,
+/// `max_frames`, `requires`, and `suite` apply to every case.
+///
+/// ```
+/// # #![feature(custom_test_frameworks)]
+/// #
+/// #[gba_test_macros::test(cases(1, 2, 3))]
+/// fn doubles(x: u32) {
+///     assert_eq!(x + x, x * 2);
+/// }
+/// ```
 #[proc_macro_attribute]
-pub fn test(_attr: TokenStream, item: TokenStream) -> TokenStream {
-    let function: ItemFn = match parse(item) {
+pub fn test(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let arguments = match Arguments::parse(attr) {
+        Ok(arguments) => arguments,
+        Err(error) => return error.into_compile_error().into(),
+    };
+    let mut function: ItemFn = match parse(item) {
         Ok(function) => function,
         Err(error) => return error.into_compile_error().into(),
     };
     let name = function.sig.ident.clone();
-    let attributes = Attributes::from(&function.attrs);
-    let ignore = attributes.ignore;
+    let attributes = match Attributes::try_from(&function.attrs) {
+        Ok(attributes) => attributes,
+        Err(error) => return error.into_compile_error().into(),
+    };
+
+    // Long names waste ROM space and are hard to read in results, so they are warned about, or
+    // rejected if `strict_names` is set.
+    let name_length = name.to_string().trim_start_matches("r#").chars().count();
+    let name_length_warning = if name_length > arguments.max_name_length {
+        let message = format!(
+            "test name `{}` is {} characters long, exceeding the limit of {}; consider a shorter name",
+            name, name_length, arguments.max_name_length
+        );
+        if arguments.strict_names {
+            return syn::Error::new(name.span(), message)
+                .into_compile_error()
+                .into();
+        }
+        // There is no stable way for a procedural macro to emit a warning, so the use of a
+        // deprecated constant is used to trigger one.
+        let warning = quote_spanned! {name.span()=> test_name_too_long};
+        quote! {
+            const _: () = {
+                #[deprecated(note = #message)]
+                #[allow(non_upper_case_globals)]
+                const test_name_too_long: () = ();
+                #warning
+            };
+        }
+    } else {
+        TokenStream2::new()
+    };
+    let output = function.sig.output.clone();
+    if let (ReturnType::Type(..), ShouldPanic::Yes | ShouldPanic::YesWith { .. }) =
+        (&output, &attributes.should_panic)
+    {
+        return syn::Error::new(
+            output.span(),
+            "functions using `#[should_panic]` must return `()`",
+        )
+        .into_compile_error()
+        .into();
+    }
+    if !matches!(attributes.should_err, ShouldErr::No) {
+        let returns_result = match &output {
+            ReturnType::Type(_, ty) => match &**ty {
+                Type::Path(path) => path
+                    .path
+                    .segments
+                    .last()
+                    .is_some_and(|segment| segment.ident == "Result"),
+                _ => false,
+            },
+            ReturnType::Default => false,
+        };
+        if !returns_result {
+            let span = match &output {
+                ReturnType::Type(..) => output.span(),
+                ReturnType::Default => name.span(),
+            };
+            return syn::Error::new(
+                span,
+                "functions using `#[should_err]` must return a `Result`",
+            )
+            .into_compile_error()
+            .into();
+        }
+    }
+    // `#[should_panic]` and `#[should_err]` have no meaning outside of a test, so they are removed
+    // from the function.
+    function.attrs.retain(|attribute| {
+        !attribute.path().is_ident("should_panic") && !attribute.path().is_ident("should_err")
+    });
+
+    // The expected message and location are checked in their own constants, so that a non-`&str`
+    // expression results in an error pointing at the expression itself.
+    let constant = |name: &str, value: Option<&Expr>| {
+        let name = Ident::new(name, Span::call_site());
+        value.map_or_else(TokenStream2::new, |value| {
+            quote_spanned! {value.span()=>
+                #[allow(dead_code)]
+                const #name: &'static str = #value;
+            }
+        })
+    };
+    let (should_panic, expected_message, location_message, expected) =
+        match &attributes.should_panic {
+            ShouldPanic::No => (false, None, None, TokenStream2::new()),
+            ShouldPanic::Yes => (true, None, None, TokenStream2::new()),
+            ShouldPanic::YesWith { expected, location } => {
+                let expected_constant = constant("SHOULD_PANIC_EXPECTED", expected.as_deref());
+                let location_constant = constant("SHOULD_PANIC_LOCATION", location.as_deref());
+                (
+                    true,
+                    expected
+                        .as_ref()
+                        .map(|_| quote! {::core::option::Option::Some(SHOULD_PANIC_EXPECTED)}),
+                    location
+                        .as_ref()
+                        .map(|_| quote! {::core::option::Option::Some(SHOULD_PANIC_LOCATION)}),
+                    quote! {
+                        #expected_constant
+                        #location_constant
+                    },
+                )
+            }
+        };
+
+    let (should_err, expected_message, expected_err) = match &attributes.should_err {
+        ShouldErr::No => (false, expected_message, TokenStream2::new()),
+        ShouldErr::Yes => (true, expected_message, TokenStream2::new()),
+        ShouldErr::YesWithMessage(expected) => (
+            true,
+            Some(quote! {::core::option::Option::Some(SHOULD_ERR_EXPECTED)}),
+            constant("SHOULD_ERR_EXPECTED", Some(expected)),
+        ),
+    };
+
+    // Tests returning a value are wrapped in a function reporting the value through
+    // `Termination`, or checking that it is an `Err` for tests using `#[should_err]`. Tests run in
+    // a specific instruction set are wrapped in a function compiled for it, which is never inlined
+    // so that the test is called from that instruction set. The wrapper is defined within the test
+    // constant, so that `gba_test` is only referenced when tests are being built.
+    let report = if matches!(attributes.should_err, ShouldErr::No) {
+        quote! {::gba_test::Termination::terminate}
+    } else {
+        quote! {::gba_test::__expect_err}
+    };
+    let test_function = |function_name: &Ident, instruction_set: Option<&TokenStream2>| {
+        let call = match output {
+            ReturnType::Default if instruction_set.is_none() => return quote! {#function_name},
+            ReturnType::Default => quote! {self::#function_name()},
+            ReturnType::Type(..) => quote! {#report(self::#function_name())},
+        };
+        let attributes = instruction_set.map(|instruction_set| {
+            quote! {
+                #[instruction_set(#instruction_set)]
+                #[inline(never)]
+            }
+        });
+        quote! {
+            {
+                #attributes
+                fn run() {
+                    #call
+                }
+                run
+            }
+        }
+    };
+
+    // The location is spanned at the function's name, so that it refers to the test's definition.
+    let location = quote_spanned! {name.span()=>
+        file: ::core::file!(),
+        line: ::core::line!(),
+    };
+
+    // Only the flags are stored with every test. Everything else is rarely set, so it is stored
+    // separately and referenced only by the tests that need it.
+    let mut flags = Vec::new();
+    if attributes.ignore.is_some() {
+        flags.push(quote! {::gba_test::Test::IGNORE});
+    }
+    if should_panic {
+        flags.push(quote! {::gba_test::Test::SHOULD_PANIC});
+    }
+    if should_err {
+        flags.push(quote! {::gba_test::Test::SHOULD_ERR});
+    }
+    let flags = if flags.is_empty() {
+        quote! {0}
+    } else {
+        quote! {#(#flags)|*}
+    };
+    let ignore_message = attributes
+        .ignore
+        .as_ref()
+        .and_then(Option::as_ref)
+        .map(|reason| quote! {::core::option::Option::Some(#reason)});
+    let max_frames = arguments
+        .max_frames
+        .map(|frames| quote! {::core::option::Option::Some(#frames)});
+    let suite = arguments
+        .suite
+        .map(|suite| quote! {::core::option::Option::Some(#suite)});
+    let requires = arguments.requires.map(|capability| {
+        let capability = match capability {
+            Capability::Sram => quote! {Sram},
+            Capability::Rtc => quote! {Rtc},
+            Capability::LinkCable => quote! {LinkCable},
+        };
+        quote! {::core::option::Option::Some(::gba_test::Capability::#capability)}
+    });
+    let details = if ignore_message.is_none()
+        && expected_message.is_none()
+        && location_message.is_none()
+        && max_frames.is_none()
+        && suite.is_none()
+        && requires.is_none()
+    {
+        quote! {::core::option::Option::None}
+    } else {
+        let none = quote! {::core::option::Option::None};
+        let ignore_message = ignore_message.unwrap_or_else(|| none.clone());
+        let expected_message = expected_message.unwrap_or_else(|| none.clone());
+        let location_message = location_message.unwrap_or_else(|| none.clone());
+        let max_frames = max_frames.unwrap_or_else(|| none.clone());
+        let suite = suite.unwrap_or_else(|| none.clone());
+        let requires = requires.unwrap_or(none);
+        // The details are defined as a constant so that the reference to them is promoted to a
+        // `'static` one.
+        quote! {
+            {
+                const DETAILS: ::gba_test::TestDetails = ::gba_test::TestDetails {
+                    ignore_message: #ignore_message,
+                    expected: #expected_message,
+                    file_substring: #location_message,
+                    max_frames: #max_frames,
+                    suite: #suite,
+                    requires: #requires,
+                };
+                ::core::option::Option::Some(&DETAILS)
+            }
+        }
+    };
+
+    // Each instruction set the test is run in, along with the suffix appended to its name, if the
+    // test is run in more than one.
+    let instruction_sets = match arguments.isa {
+        None => vec![(None, None)],
+        Some(Isa::Arm) => vec![(None, Some(quote! {arm::a32}))],
+        Some(Isa::Thumb) => vec![(None, Some(quote! {arm::t32}))],
+        Some(Isa::Both) => vec![
+            (Some("arm"), Some(quote! {arm::a32})),
+            (Some("thumb"), Some(quote! {arm::t32})),
+        ],
+    };
+    let entries = |const_name: &Ident, test_name: &TokenStream2, function_name: &Ident| {
+        instruction_sets
+            .iter()
+            .map(|(suffix, instruction_set)| {
+                let (const_name, test_name) = match suffix {
+                    Some(suffix) => (
+                        format_ident!("{}_{}", const_name, suffix.to_uppercase()),
+                        quote! {concat!(#test_name, "::", #suffix)},
+                    ),
+                    None => (const_name.clone(), test_name.clone()),
+                };
+                let test = test_function(function_name, instruction_set.as_ref());
+                // The record is only read from the built ELF file, so it is kept by the `#[used]`
+                // attribute rather than by being referenced.
+                let record_name = format_ident!("{}_RECORD", const_name);
+                quote! {
+                    #[cfg(test)]
+                    #[used]
+                    #[link_section = ".gba_test_meta"]
+                    static #record_name: ::gba_test::TestRecord = ::gba_test::TestRecord::new(
+                        #test_name,
+                        ::core::concat!(::core::module_path!(), "::", #test_name),
+                        #flags,
+                    );
+
+                    #[test_case]
+                    const #const_name: ::gba_test::Test = ::gba_test::Test {
+                        name: #test_name,
+                        module_path: ::core::module_path!(),
+                        test: #test,
+                        #location
+                        flags: #flags,
+                        details: #details,
+                    };
+                }
+            })
+            .collect::<TokenStream2>()
+    };
+
+    let tests = match arguments.cases {
+        None => entries(
+            &Ident::new("TEST", Span::call_site()),
+            &quote! {stringify!(#name)},
+            &name,
+        ),
+        Some(cases) => {
+            if function.sig.inputs.len() != 1 {
+                return syn::Error::new(
+                    function.sig.paren_token.span.join(),
+                    "tests with `cases(...)` must take exactly one parameter",
+                )
+                .into_compile_error()
+                .into();
+            }
+            // Case indices are padded to the same width, so that the cases are collected in order.
+            let width = (cases.len() - 1).to_string().len();
+            cases
+                .iter()
+                .enumerate()
+                .map(|(index, case)| {
+                    let function_name = format_ident!("{}_case_{:0width$}", name, index);
+                    let const_name = format_ident!("TEST_{:0width$}", index);
+                    // Each case is called from its own function, outside of the test constant,
+                    // so that type errors point at the case even when tests are not being built.
+                    let call = quote_spanned! {case.span()=> self::#name(#case)};
+                    let entries = entries(
+                        &const_name,
+                        &quote! {concat!(stringify!(#name), "[", stringify!(#case), "]")},
+                        &function_name,
+                    );
+                    quote! {
+                        #[allow(dead_code)]
+                        fn #function_name() #output {
+                            #call
+                        }
+
+                        #entries
+                    }
+                })
+                .collect()
+        }
+    };
 
     TokenStream::from(quote! {
         mod #name {
@@ -94,12 +848,110 @@ pub fn test(_attr: TokenStream, item: TokenStream) -> TokenStream {
 
             #function
 
-            #[test_case]
-            const TEST: ::gba_test::Test = ::gba_test::Test {
-                name: stringify!(#name),
-                test: #name,
-                ignore: ::gba_test::Ignore::#ignore,
-            };
+            #expected
+
+            #expected_err
+
+            #name_length_warning
+
+            #tests
         }
     })
 }
+
+/// Defines the `main` function for a crate of tests, which runs the test harness.
+///
+/// The crate must re-export the test harness as `test_harness` using
+/// `#![reexport_test_harness_main = "test_harness"]`. The test runner never returns, so nothing can
+/// be run after the harness; a function to run once before the first test can instead be provided
+/// using `once = <expr>`, which is passed to `gba_test::set_global_setup()`.
+///
+/// # Example
+/// ```
+/// # #![feature(custom_test_frameworks)]
+/// #
+/// fn calibrate() {}
+///
+/// gba_test_macros::harness_main!(once = calibrate);
+/// #
+/// # fn main() {}
+/// ```
+#[proc_macro]
+pub fn harness_main(input: TokenStream) -> TokenStream {
+    let arguments = match Punctuated::<MetaNameValue, Token![,]>::parse_terminated.parse(input) {
+        Ok(arguments) => arguments,
+        Err(error) => return error.into_compile_error().into(),
+    };
+
+    let mut once = None;
+    for argument in arguments {
+        if argument.path.is_ident("once") {
+            if once.is_some() {
+                return syn::Error::new(argument.path.span(), "duplicate `once` argument")
+                    .into_compile_error()
+                    .into();
+            }
+            once = Some(argument.value);
+        } else {
+            return syn::Error::new(argument.path.span(), "expected `once = ...`")
+                .into_compile_error()
+                .into();
+        }
+    }
+    let setup = once.map(|once| quote! {::gba_test::set_global_setup(#once);});
+
+    TokenStream::from(quote! {
+        #[cfg(test)]
+        #[no_mangle]
+        pub fn main() {
+            #setup
+            test_harness();
+            loop {}
+        }
+    })
+}
+
+/// Fails to compile unless the crate is configured to run its tests.
+///
+/// This is meant to be placed in `main()` or at the crate root. It checks that the crate is being
+/// compiled as a test, and that the test harness has been re-exported as `test_harness` using
+/// `#![reexport_test_harness_main = "test_harness"]`. A crate missing that attribute otherwise
+/// builds successfully, but never runs any tests.
+///
+/// Whether `#![test_runner(gba_test::runner)]` is present can't be checked at compile time. If it
+/// is missing, the harness runs no tests, which the runner reports in mGBA's log.
+///
+/// # Example
+/// ```
+/// # #![feature(custom_test_frameworks)]
+/// #![reexport_test_harness_main = "test_harness"]
+///
+/// #[cfg(test)]
+/// #[no_mangle]
+/// pub fn main() {
+///     gba_test_macros::assert_harness_configured!();
+///     test_harness();
+///     loop {}
+/// }
+/// #
+/// # fn main() {}
+/// ```
+#[proc_macro]
+pub fn assert_harness_configured(input: TokenStream) -> TokenStream {
+    let input = TokenStream2::from(input);
+    if let Some(token) = input.into_iter().next() {
+        return syn::Error::new(token.span(), "unexpected argument")
+            .into_compile_error()
+            .into();
+    }
+
+    TokenStream::from(quote! {
+        #[cfg(not(test))]
+        ::core::compile_error!(
+            "`assert_harness_configured!()` must only be used when compiling tests, such as within a `#[cfg(test)]` item"
+        );
+        // Fails to resolve if the harness was not re-exported.
+        #[cfg(test)]
+        const _: fn() = crate::test_harness;
+    })
+}
@@ -2,5 +2,7 @@
 
 #[test]
 fn test() {
-    trybuild::TestCases::new().compile_fail("tests/trybuild/*.rs");
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/trybuild/*.rs");
+    cases.pass("tests/trybuild/pass/*.rs");
 }
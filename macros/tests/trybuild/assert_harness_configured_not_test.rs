@@ -0,0 +1,6 @@
+#![feature(custom_test_frameworks)]
+#![reexport_test_harness_main = "test_harness"]
+
+fn main() {
+    gba_test_macros::assert_harness_configured!();
+}
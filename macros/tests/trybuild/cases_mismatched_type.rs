@@ -0,0 +1,8 @@
+#![feature(custom_test_frameworks)]
+
+use gba_test_macros::test;
+
+#[test(cases(1, "two", 3))]
+fn foo(_x: u32) {}
+
+fn main() {}
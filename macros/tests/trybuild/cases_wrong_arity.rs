@@ -0,0 +1,8 @@
+#![feature(custom_test_frameworks)]
+
+use gba_test_macros::test;
+
+#[test(cases(1, 2))]
+fn foo(_x: u32, _y: u32) {}
+
+fn main() {}
@@ -0,0 +1,9 @@
+#![feature(custom_test_frameworks)]
+
+use gba_test_macros::test;
+
+#[test]
+#[bench]
+fn foo() {}
+
+fn main() {}
@@ -0,0 +1,9 @@
+#![feature(custom_test_frameworks)]
+
+use gba_test_macros::test as gba_test;
+
+#[gba_test]
+#[core::prelude::v1::test]
+fn foo() {}
+
+fn main() {}
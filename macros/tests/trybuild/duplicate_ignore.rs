@@ -0,0 +1,10 @@
+#![feature(custom_test_frameworks)]
+
+use gba_test_macros::test;
+
+#[test]
+#[ignore]
+#[ignore]
+fn foo() {}
+
+fn main() {}
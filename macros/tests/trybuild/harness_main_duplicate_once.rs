@@ -0,0 +1,5 @@
+#![feature(custom_test_frameworks)]
+
+gba_test_macros::harness_main!(once = setup, once = calibrate);
+
+fn main() {}
@@ -0,0 +1,5 @@
+#![feature(custom_test_frameworks)]
+
+gba_test_macros::harness_main!(before_each = setup);
+
+fn main() {}
@@ -0,0 +1,8 @@
+#![feature(custom_test_frameworks)]
+
+use gba_test_macros::test;
+
+#[test(isa = "x86")]
+fn foo() {}
+
+fn main() {}
@@ -0,0 +1,8 @@
+#![feature(custom_test_frameworks)]
+
+use gba_test_macros::test;
+
+#[test(max_frames = "5")]
+fn foo() {}
+
+fn main() {}
@@ -0,0 +1,9 @@
+#![feature(custom_test_frameworks)]
+#![deny(deprecated)]
+
+use gba_test_macros::test;
+
+#[test]
+fn this_test_has_a_name_that_is_far_too_long_to_read_comfortably() {}
+
+fn main() {}
@@ -0,0 +1,8 @@
+#![feature(custom_test_frameworks)]
+
+use gba_test_macros::test;
+
+#[test(strict_names, max_name_length = 8)]
+fn long_name() {}
+
+fn main() {}
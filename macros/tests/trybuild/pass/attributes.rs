@@ -0,0 +1,41 @@
+#![feature(custom_test_frameworks)]
+
+use gba_test_macros::test;
+
+/// Documentation is kept on the test.
+#[test]
+#[allow(unused_variables)]
+fn documented() {
+    let unused = 0;
+}
+
+#[ignore]
+/// Recognized attributes may come before the test attribute.
+#[test]
+fn ignored_first() {}
+
+#[test]
+#[allow(clippy::unit_cmp)]
+#[ignore]
+fn ignored_last() {}
+
+// A disabled test is removed entirely, including the code it calls.
+#[cfg(any())]
+#[test]
+fn disabled() {
+    does_not_exist();
+}
+
+#[cfg(all())]
+#[test]
+fn enabled() {}
+
+#[test]
+#[ignore = "not yet supported"]
+fn ignored_with_reason() {}
+
+#[cfg_attr(all(), ignore)]
+#[test]
+fn conditionally_ignored() {}
+
+fn main() {}
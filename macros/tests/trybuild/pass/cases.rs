@@ -0,0 +1,27 @@
+#![feature(custom_test_frameworks)]
+
+use gba_test_macros::test;
+
+const VALUE: u32 = 4;
+
+#[test(cases(1, 2, 3))]
+fn doubles(x: u32) {
+    assert_eq!(x + x, x * 2);
+}
+
+#[test(cases("foo", "bar",))]
+#[ignore]
+fn strings(s: &str) {
+    assert!(!s.is_empty());
+}
+
+#[test(cases(VALUE, VALUE + 1))]
+#[should_panic(expected = "odd")]
+fn expressions(x: u32) {
+    assert!(x % 2 == 0, "odd");
+}
+
+#[test(cases(0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10))]
+fn many(_x: u8) {}
+
+fn main() {}
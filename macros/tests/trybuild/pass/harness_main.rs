@@ -0,0 +1,17 @@
+#![feature(custom_test_frameworks)]
+
+mod empty {
+    gba_test_macros::harness_main!();
+}
+
+mod once {
+    fn setup() {}
+
+    gba_test_macros::harness_main!(once = setup);
+}
+
+mod once_trailing_comma {
+    gba_test_macros::harness_main!(once = || {},);
+}
+
+fn main() {}
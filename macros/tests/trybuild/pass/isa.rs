@@ -0,0 +1,21 @@
+#![feature(custom_test_frameworks)]
+
+use gba_test_macros::test;
+
+#[test(isa = "arm")]
+fn arm() {}
+
+#[test(isa = "thumb")]
+fn thumb() -> Option<()> {
+    Some(())
+}
+
+#[test(isa = "both")]
+fn both() {}
+
+#[test(cases(1, 2), isa = "both")]
+fn cases(x: u32) {
+    assert!(x > 0);
+}
+
+fn main() {}
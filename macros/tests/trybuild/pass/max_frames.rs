@@ -0,0 +1,17 @@
+#![feature(custom_test_frameworks)]
+
+use gba_test_macros::test;
+
+#[test(max_frames = 5)]
+fn budget() {}
+
+#[test(max_frames = 1, cases(1, 2))]
+fn cases(_x: u32) {}
+
+#[test(cases(1, 2), max_frames = 1)]
+#[should_panic]
+fn cases_should_panic(_x: u32) {
+    panic!();
+}
+
+fn main() {}
@@ -0,0 +1,18 @@
+#![feature(custom_test_frameworks)]
+#![deny(deprecated)]
+
+use gba_test_macros::test;
+
+#[test(strict_names)]
+fn a_name_that_is_exactly_forty_eight_characters_xx() {}
+
+#[test(max_name_length = 64)]
+fn this_test_has_a_name_that_is_far_too_long_to_read_comfortably() {}
+
+#[test(strict_names, max_name_length = 5)]
+fn r#fn() {}
+
+#[test(strict_names, max_name_length = 5)]
+fn émoji() {}
+
+fn main() {}
@@ -0,0 +1,17 @@
+#![feature(custom_test_frameworks)]
+
+use gba_test_macros::test;
+
+#[test(requires = "sram")]
+fn sram() {}
+
+#[test(requires = "rtc", suite = "hardware")]
+#[ignore]
+fn rtc() {}
+
+#[test(cases(1, 2), requires = "link_cable")]
+fn link_cable(x: u32) {
+    assert!(x > 0);
+}
+
+fn main() {}
@@ -0,0 +1,20 @@
+#![feature(custom_test_frameworks)]
+
+use gba_test_macros::test;
+
+#[test]
+fn result() -> Result<(), &'static str> {
+    Ok(())
+}
+
+#[test]
+fn option() -> Option<()> {
+    Some(())
+}
+
+#[test(cases(1, 2))]
+fn cases(x: u32) -> Option<()> {
+    (x > 0).then_some(())
+}
+
+fn main() {}
@@ -0,0 +1,29 @@
+#![feature(custom_test_frameworks)]
+
+use gba_test_macros::test;
+
+#[test]
+#[should_err(expected = "foo")]
+fn foo() -> Result<(), &'static str> {
+    Err("foo")
+}
+
+#[test]
+#[should_err = "bar"]
+fn bar() -> Result<u32, &'static str> {
+    Err("bar")
+}
+
+#[test]
+#[should_err]
+fn baz() -> core::result::Result<(), ()> {
+    Err(())
+}
+
+#[test(cases(1, 2))]
+#[should_err]
+fn cases(x: u32) -> Result<(), u32> {
+    Err(x)
+}
+
+fn main() {}
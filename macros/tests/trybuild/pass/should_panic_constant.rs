@@ -0,0 +1,19 @@
+#![feature(custom_test_frameworks)]
+
+use gba_test_macros::test;
+
+mod messages {
+    pub const FOO: &str = "foo";
+}
+
+const BAR: &str = "bar";
+
+#[test]
+#[should_panic(expected = messages::FOO)]
+fn foo() {}
+
+#[test]
+#[should_panic(expected = BAR)]
+fn bar() {}
+
+fn main() {}
@@ -0,0 +1,17 @@
+#![feature(custom_test_frameworks)]
+
+use gba_test_macros::test;
+
+#[test]
+#[should_panic(expected = "foo")]
+fn foo() {}
+
+#[test]
+#[should_panic = "bar"]
+fn bar() {}
+
+#[test]
+#[should_panic]
+fn baz() {}
+
+fn main() {}
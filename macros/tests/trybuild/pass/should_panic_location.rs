@@ -0,0 +1,19 @@
+#![feature(custom_test_frameworks)]
+
+use gba_test_macros::test;
+
+const FILE: &str = "src/lib.rs";
+
+#[test]
+#[should_panic(location = "src/lib.rs")]
+fn location() {}
+
+#[test]
+#[should_panic(location = FILE, expected = "foo")]
+fn location_and_expected() {}
+
+#[test]
+#[should_panic(expected = "foo", location = FILE,)]
+fn expected_and_location() {}
+
+fn main() {}
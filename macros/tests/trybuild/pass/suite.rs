@@ -0,0 +1,16 @@
+#![feature(custom_test_frameworks)]
+
+use gba_test_macros::test;
+
+#[test(suite = "slow")]
+fn slow() {}
+
+#[test(suite = "hardware-only", max_frames = 10)]
+fn hardware_only() {}
+
+#[test(cases(1, 2), suite = "slow")]
+fn cases(x: u32) {
+    assert_eq!(x, x);
+}
+
+fn main() {}
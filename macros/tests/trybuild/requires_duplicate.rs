@@ -0,0 +1,8 @@
+#![feature(custom_test_frameworks)]
+
+use gba_test_macros::test;
+
+#[test(requires = "sram", requires = "rtc")]
+fn foo() {}
+
+fn main() {}
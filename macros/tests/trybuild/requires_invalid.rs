@@ -0,0 +1,8 @@
+#![feature(custom_test_frameworks)]
+
+use gba_test_macros::test;
+
+#[test(requires = "rumble")]
+fn foo() {}
+
+fn main() {}
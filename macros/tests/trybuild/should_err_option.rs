@@ -0,0 +1,11 @@
+#![feature(custom_test_frameworks)]
+
+use gba_test_macros::test;
+
+#[test]
+#[should_err]
+fn foo() -> Option<()> {
+    None
+}
+
+fn main() {}
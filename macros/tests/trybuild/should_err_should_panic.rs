@@ -0,0 +1,12 @@
+#![feature(custom_test_frameworks)]
+
+use gba_test_macros::test;
+
+#[test]
+#[should_panic]
+#[should_err]
+fn foo() -> Result<(), ()> {
+    Err(())
+}
+
+fn main() {}
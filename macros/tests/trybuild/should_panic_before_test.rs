@@ -0,0 +1,13 @@
+#![feature(custom_test_frameworks)]
+
+use gba_test_macros::test;
+
+/// Documentation.
+#[should_panic]
+#[allow(unused)]
+#[test]
+fn foo() -> Result<(), ()> {
+    Ok(())
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+#![feature(custom_test_frameworks)]
+
+use gba_test_macros::test;
+
+#[test]
+#[should_panic(location = "src/lib.rs", location = "src/dma.rs")]
+fn foo() {}
+
+fn main() {}
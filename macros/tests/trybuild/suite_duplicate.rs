@@ -0,0 +1,8 @@
+#![feature(custom_test_frameworks)]
+
+use gba_test_macros::test;
+
+#[test(suite = "slow", suite = "fast")]
+fn foo() {}
+
+fn main() {}
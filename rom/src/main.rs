@@ -0,0 +1,103 @@
+//! Converts a built test executable into a ROM that can be run directly.
+//!
+//! ## Usage
+//! ```
+//! $ gba_test_rom <executable> [<command> [<argument>...]]
+//! ```
+//!
+//! The ELF file is converted using `gba_test::rom::from_elf()`, which extracts the data loaded
+//! from cartridge ROM, pads it to a power-of-two size, and fixes the cartridge header. The ROM is
+//! written next to the executable with the `.gba` extension, and its path is printed.
+//!
+//! If a command is given, it is then run with the path of the ROM appended to its arguments. This
+//! allows the tool to be used as the runner in `.cargo/config.toml`, such as with:
+//!
+//! ```toml
+//! [target.thumbv4t-none-eabi]
+//! runner = "gba_test_rom mgba -C savegamePath=."
+//! ```
+//!
+//! The exit code is that of the command if one is run, `0` if the ROM was written and no command
+//! was given, and `2` if the ROM could not be written or the command could not be run.
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::{Command, ExitCode},
+};
+
+/// The usage message printed when the arguments are invalid.
+const USAGE: &str = "usage: gba_test_rom <executable> [<command> [<argument>...]]";
+
+/// Returns the path the ROM converted from `executable` is written to.
+fn rom_path(executable: &Path) -> PathBuf {
+    executable.with_extension("gba")
+}
+
+fn main() -> ExitCode {
+    let mut arguments = env::args_os().skip(1);
+    let Some(executable) = arguments.next().map(PathBuf::from) else {
+        eprintln!("{}", USAGE);
+        return ExitCode::from(2);
+    };
+
+    let elf = match fs::read(&executable) {
+        Ok(elf) => elf,
+        Err(error) => {
+            eprintln!("unable to read `{}`: {}", executable.display(), error);
+            return ExitCode::from(2);
+        }
+    };
+    let rom = match gba_test::rom::from_elf(&elf) {
+        Ok(rom) => rom,
+        Err(error) => {
+            eprintln!("`{}`: {}", executable.display(), error);
+            return ExitCode::from(2);
+        }
+    };
+    let path = rom_path(&executable);
+    if let Err(error) = fs::write(&path, &rom) {
+        eprintln!("unable to write `{}`: {}", path.display(), error);
+        return ExitCode::from(2);
+    }
+    println!("{}", path.display());
+
+    let Some(program) = arguments.next() else {
+        return ExitCode::SUCCESS;
+    };
+    match Command::new(&program).args(arguments).arg(&path).status() {
+        Ok(status) => status
+            .code()
+            .and_then(|code| u8::try_from(code).ok())
+            .map(ExitCode::from)
+            .unwrap_or(ExitCode::FAILURE),
+        Err(error) => {
+            eprintln!("unable to run `{}`: {}", program.to_string_lossy(), error);
+            ExitCode::from(2)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::rom_path;
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn rom_path_next_to_executable() {
+        assert_eq!(
+            rom_path(Path::new(
+                "target/thumbv4t-none-eabi/debug/deps/pass-0123456789abcdef"
+            )),
+            PathBuf::from("target/thumbv4t-none-eabi/debug/deps/pass-0123456789abcdef.gba")
+        );
+    }
+
+    #[test]
+    fn rom_path_replaces_extension() {
+        assert_eq!(
+            rom_path(Path::new("target/pass.elf")),
+            PathBuf::from("target/pass.gba")
+        );
+    }
+}
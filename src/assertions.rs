@@ -0,0 +1,384 @@
+//! Assertions for comparing numeric values within a tolerance.
+//!
+//! Game Boy Advance code often uses fixed-point arithmetic, where results may differ from the
+//! expected value by a few units in the last place. These assertions allow for that difference,
+//! and can display fixed-point values as decimals to make failures easier to read.
+
+use core::{
+    fmt,
+    fmt::{Display, Formatter},
+};
+
+/// A fixed-point value, displayed as a decimal followed by its raw bits.
+///
+/// This is an implementation detail of [`assert_fixed_eq!`].
+///
+/// [`assert_fixed_eq!`]: crate::assert_fixed_eq!
+#[doc(hidden)]
+#[derive(Clone, Copy, Debug)]
+pub struct Fixed {
+    /// The raw bits of the value.
+    pub bits: i32,
+    /// The number of bits used for the fractional part.
+    pub frac_bits: u32,
+}
+
+impl Fixed {
+    /// The number of decimal places displayed if no precision is specified.
+    const DEFAULT_PRECISION: usize = 3;
+    /// The largest number of decimal places that can be displayed.
+    ///
+    /// This ensures the intermediate values used for rounding fit within a `u64`.
+    const MAX_PRECISION: usize = 9;
+}
+
+impl Display for Fixed {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        let precision = formatter
+            .precision()
+            .unwrap_or(Self::DEFAULT_PRECISION)
+            .min(Self::MAX_PRECISION);
+        let frac_bits = self.frac_bits.min(31);
+        let magnitude = u64::from(self.bits.unsigned_abs());
+
+        let mut integer = magnitude >> frac_bits;
+        let fraction = magnitude & ((1 << frac_bits) - 1);
+        // Round half away from zero.
+        let scale = 10u64.pow(precision as u32);
+        let mut decimal = ((fraction * scale) + (1 << frac_bits >> 1)) >> frac_bits;
+        if decimal == scale {
+            integer += 1;
+            decimal = 0;
+        }
+
+        if self.bits < 0 {
+            formatter.write_str("-")?;
+        }
+        write!(formatter, "{}", integer)?;
+        if precision > 0 {
+            write!(formatter, ".{:0precision$}", decimal, precision = precision)?;
+        }
+        let bits = self.bits as u32;
+        write!(formatter, " (0x{:04X}_{:04X})", bits >> 16, bits & 0xFFFF)
+    }
+}
+
+/// Asserts that two values are equal to within a tolerance.
+///
+/// The assertion passes if the difference between the two values is less than or equal to the
+/// tolerance. This works with any values that can be compared and subtracted, such as integers or
+/// the raw bits of fixed-point values. A custom message can be provided after the tolerance, in
+/// the same way as with [`assert_eq!`].
+///
+/// ```
+/// use gba_test::assert_approx_eq;
+///
+/// assert_approx_eq!(16777216, 16777215, 1);
+/// ```
+#[macro_export]
+macro_rules! assert_approx_eq {
+    ($left:expr, $right:expr, $tolerance:expr $(,)?) => {
+        match (&$left, &$right, &$tolerance) {
+            (left, right, tolerance) => {
+                let difference = if *left > *right {
+                    *left - *right
+                } else {
+                    *right - *left
+                };
+                if difference > *tolerance {
+                    ::core::panic!(
+                        "assertion failed: `(left ~= right)` (tolerance: `{:?}`)\n  left: `{:?}`,\n right: `{:?}`",
+                        tolerance,
+                        left,
+                        right,
+                    );
+                }
+            }
+        }
+    };
+    ($left:expr, $right:expr, $tolerance:expr, $($arg:tt)+) => {
+        match (&$left, &$right, &$tolerance) {
+            (left, right, tolerance) => {
+                let difference = if *left > *right {
+                    *left - *right
+                } else {
+                    *right - *left
+                };
+                if difference > *tolerance {
+                    ::core::panic!(
+                        "assertion failed: `(left ~= right)` (tolerance: `{:?}`)\n  left: `{:?}`,\n right: `{:?}`: {}",
+                        tolerance,
+                        left,
+                        right,
+                        ::core::format_args!($($arg)+),
+                    );
+                }
+            }
+        }
+    };
+}
+
+/// Asserts that two fixed-point values are equal to within a tolerance.
+///
+/// Both values are the raw bits of fixed-point numbers with `frac_bits` fractional bits, and are
+/// converted to `i32` using `as`. The assertion passes if they differ by at most `tolerance_lsb`
+/// units in the last place. On failure, the values are displayed both as decimals and as raw bits,
+/// such as `1.500 (0x0001_8000)`.
+///
+/// ```
+/// use gba_test::assert_fixed_eq;
+///
+/// // 1.5 and 1.5 + 2^-16, in 16.16 fixed-point.
+/// assert_fixed_eq!(0x0001_8000, 0x0001_8001, 16, 1);
+/// ```
+#[macro_export]
+macro_rules! assert_fixed_eq {
+    ($left:expr, $right:expr, $frac_bits:expr, $tolerance_lsb:expr $(,)?) => {
+        match ($left as i32, $right as i32, $frac_bits as u32, $tolerance_lsb as u32) {
+            (left, right, frac_bits, tolerance_lsb) => {
+                if left.abs_diff(right) > tolerance_lsb {
+                    ::core::panic!(
+                        "assertion failed: `(left == right)` (tolerance: {} lsb)\n  left: `{}`,\n right: `{}`",
+                        tolerance_lsb,
+                        $crate::__Fixed {
+                            bits: left,
+                            frac_bits,
+                        },
+                        $crate::__Fixed {
+                            bits: right,
+                            frac_bits,
+                        },
+                    );
+                }
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Fixed;
+    use alloc::format;
+
+    #[test]
+    fn integer() {
+        assert_eq!(
+            format!(
+                "{}",
+                Fixed {
+                    bits: 0x0002_0000,
+                    frac_bits: 16
+                }
+            ),
+            "2.000 (0x0002_0000)"
+        );
+    }
+
+    #[test]
+    fn fraction() {
+        assert_eq!(
+            format!(
+                "{}",
+                Fixed {
+                    bits: 0x0001_8000,
+                    frac_bits: 16
+                }
+            ),
+            "1.500 (0x0001_8000)"
+        );
+    }
+
+    #[test]
+    fn negative() {
+        assert_eq!(
+            format!(
+                "{}",
+                Fixed {
+                    bits: -0x0001_8000,
+                    frac_bits: 16
+                }
+            ),
+            "-1.500 (0xFFFE_8000)"
+        );
+    }
+
+    #[test]
+    fn negative_fraction() {
+        assert_eq!(
+            format!(
+                "{}",
+                Fixed {
+                    bits: -0x40,
+                    frac_bits: 8
+                }
+            ),
+            "-0.250 (0xFFFF_FFC0)"
+        );
+    }
+
+    #[test]
+    fn round_down() {
+        // 1/256 = 0.00390625.
+        assert_eq!(
+            format!(
+                "{}",
+                Fixed {
+                    bits: 1,
+                    frac_bits: 8
+                }
+            ),
+            "0.004 (0x0000_0001)"
+        );
+        // 1/1024 = 0.0009765625.
+        assert_eq!(
+            format!(
+                "{}",
+                Fixed {
+                    bits: 1,
+                    frac_bits: 10
+                }
+            ),
+            "0.001 (0x0000_0001)"
+        );
+        // 1/4096 = 0.000244140625.
+        assert_eq!(
+            format!(
+                "{}",
+                Fixed {
+                    bits: 1,
+                    frac_bits: 12
+                }
+            ),
+            "0.000 (0x0000_0001)"
+        );
+    }
+
+    #[test]
+    fn round_half_away_from_zero() {
+        // 1/16 = 0.0625.
+        assert_eq!(
+            format!(
+                "{:.3}",
+                Fixed {
+                    bits: 1,
+                    frac_bits: 4
+                }
+            ),
+            "0.063 (0x0000_0001)"
+        );
+        assert_eq!(
+            format!(
+                "{:.3}",
+                Fixed {
+                    bits: -1,
+                    frac_bits: 4
+                }
+            ),
+            "-0.063 (0xFFFF_FFFF)"
+        );
+    }
+
+    #[test]
+    fn round_into_integer() {
+        // 255/256 = 0.99609375.
+        assert_eq!(
+            format!(
+                "{:.2}",
+                Fixed {
+                    bits: 0xFF,
+                    frac_bits: 8
+                }
+            ),
+            "1.00 (0x0000_00FF)"
+        );
+    }
+
+    #[test]
+    fn precision() {
+        assert_eq!(
+            format!(
+                "{:.0}",
+                Fixed {
+                    bits: 0x0001_8000,
+                    frac_bits: 16
+                }
+            ),
+            "2 (0x0001_8000)"
+        );
+        assert_eq!(
+            format!(
+                "{:.5}",
+                Fixed {
+                    bits: 0x0001_0001,
+                    frac_bits: 16
+                }
+            ),
+            "1.00002 (0x0001_0001)"
+        );
+    }
+
+    #[test]
+    fn no_fractional_bits() {
+        assert_eq!(
+            format!(
+                "{}",
+                Fixed {
+                    bits: -5,
+                    frac_bits: 0
+                }
+            ),
+            "-5.000 (0xFFFF_FFFB)"
+        );
+    }
+
+    #[test]
+    fn minimum() {
+        assert_eq!(
+            format!(
+                "{}",
+                Fixed {
+                    bits: i32::MIN,
+                    frac_bits: 31
+                }
+            ),
+            "-1.000 (0x8000_0000)"
+        );
+    }
+
+    #[test]
+    fn approx_eq_within_tolerance() {
+        assert_approx_eq!(16777216, 16777215, 1);
+        assert_approx_eq!(5u8, 5u8, 0);
+        assert_approx_eq!(-3i32, 3i32, 6, "custom {}", "message");
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "assertion failed: `(left ~= right)` (tolerance: `1`)\n  left: `16777216`,\n right: `16777214`"
+    )]
+    fn approx_eq_outside_tolerance() {
+        assert_approx_eq!(16777216, 16777214, 1);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "assertion failed: `(left ~= right)` (tolerance: `0`)\n  left: `1`,\n right: `2`: custom message"
+    )]
+    fn approx_eq_custom_message() {
+        assert_approx_eq!(1u32, 2u32, 0, "custom {}", "message");
+    }
+
+    #[test]
+    fn fixed_eq_within_tolerance() {
+        assert_fixed_eq!(0x0001_8000, 0x0001_8001, 16, 1);
+        assert_fixed_eq!(i32::MIN, i32::MAX, 16, u32::MAX);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "assertion failed: `(left == right)` (tolerance: 1 lsb)\n  left: `1.500 (0x0001_8000)`,\n right: `1.500 (0x0001_8002)`"
+    )]
+    fn fixed_eq_outside_tolerance() {
+        assert_fixed_eq!(0x0001_8000, 0x0001_8002, 16, 1);
+    }
+}
@@ -0,0 +1,152 @@
+//! Wrappers around Game Boy Advance BIOS functions.
+
+use crate::{failed_names, order};
+use core::{ops::Range, ptr};
+
+/// The start of external work RAM.
+const EWRAM_START: usize = 0x0200_0000;
+/// The end of external work RAM, exclusive.
+const EWRAM_END: usize = 0x0204_0000;
+/// The `RegisterRamReset` flag for clearing external work RAM.
+const EWRAM_FLAG: u8 = 0b0000_0001;
+
+/// Memory regions and registers to be cleared by the BIOS `RegisterRamReset` function.
+///
+/// This is a builder, starting from a value that clears nothing. The runner executes this reset
+/// before each test to prevent state left over from previous tests from leaking into the next
+/// one. It can be configured using [`set_register_ram_reset()`].
+///
+/// Note that clearing internal work RAM is not supported, as that is where the test runner stores
+/// its own state. Clearing external work RAM leaves the runner's state there, such as the list of
+/// failed test names, intact.
+///
+/// [`set_register_ram_reset()`]: crate::set_register_ram_reset()
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(all(feature = "runner", target = "thumbv4t-none-eabi")))
+)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct RegisterRamReset(u8);
+
+impl RegisterRamReset {
+    /// The reset performed by default.
+    ///
+    /// This clears everything except for the work RAM. If the `no-vram-clear` feature is
+    /// enabled, VRAM is also not cleared. If the `preserve-audio` feature is enabled, the sound
+    /// registers and other registers, which include the timers, DMA, and interrupt control
+    /// driving audio playback, are also not cleared.
+    pub(crate) const DEFAULT: Self = {
+        let reset = Self::new().palette().oam().sio_registers();
+        let reset = if cfg!(feature = "preserve-audio") {
+            reset
+        } else {
+            reset.sound_registers().other_registers()
+        };
+        if cfg!(feature = "no-vram-clear") {
+            reset
+        } else {
+            reset.vram()
+        }
+    };
+
+    /// Returns a reset that clears nothing.
+    pub const fn new() -> Self {
+        Self(0)
+    }
+
+    /// Clear the 256 KiB of external work RAM.
+    ///
+    /// The state the runner keeps in external work RAM, such as the list of failed test names, is
+    /// not cleared, so it stays valid for the whole run.
+    pub const fn ewram(self) -> Self {
+        Self(self.0 | EWRAM_FLAG)
+    }
+
+    /// Clear palette RAM.
+    pub const fn palette(self) -> Self {
+        Self(self.0 | 0b0000_0100)
+    }
+
+    /// Clear video RAM.
+    pub const fn vram(self) -> Self {
+        Self(self.0 | 0b0000_1000)
+    }
+
+    /// Clear object attribute memory.
+    pub const fn oam(self) -> Self {
+        Self(self.0 | 0b0001_0000)
+    }
+
+    /// Reset the serial I/O registers.
+    pub const fn sio_registers(self) -> Self {
+        Self(self.0 | 0b0010_0000)
+    }
+
+    /// Reset the sound registers.
+    pub const fn sound_registers(self) -> Self {
+        Self(self.0 | 0b0100_0000)
+    }
+
+    /// Reset all other I/O registers.
+    ///
+    /// Note that this includes the cartridge wait state, which the runner reconfigures after each
+    /// reset.
+    pub const fn other_registers(self) -> Self {
+        Self(self.0 | 0b1000_0000)
+    }
+
+    /// Executes the reset.
+    pub(crate) fn execute(self) {
+        // SAFETY: Internal work RAM, which holds the stack and all runner state, is never cleared.
+        // External work RAM is cleared below instead of by the BIOS.
+        unsafe {
+            core::arch::asm!(
+                "swi #0x01",
+                inlateout("r0") (self.0 & !EWRAM_FLAG) as u32 => _,
+                lateout("r1") _,
+                lateout("r2") _,
+                lateout("r3") _,
+                lateout("r12") _,
+            );
+        }
+        // The BIOS would also clear the runner's state, so external work RAM is cleared around it
+        // instead.
+        if self.0 & EWRAM_FLAG != 0 {
+            let mut preserved = [failed_names::region(), order::region()];
+            preserved.sort_unstable_by_key(|region| region.start);
+            let mut start = EWRAM_START;
+            for region in preserved {
+                // SAFETY: External work RAM is valid for writes, and the runner's state in it is
+                // word-aligned.
+                unsafe {
+                    clear_words(start..region.start);
+                }
+                start = region.end;
+            }
+            // SAFETY: External work RAM is valid for writes, and the runner's state in it is
+            // word-aligned.
+            unsafe {
+                clear_words(start..EWRAM_END);
+            }
+        }
+    }
+}
+
+/// Zeroes the memory within `region`, a word at a time.
+///
+/// # Safety
+/// `region` must be valid for writes, and both of its bounds must be 4-byte aligned.
+unsafe fn clear_words(region: Range<usize>) {
+    for address in region.step_by(4) {
+        // SAFETY: The caller guarantees `address` is valid for writes and aligned.
+        unsafe {
+            ptr::write_volatile(address as *mut u32, 0);
+        }
+    }
+}
+
+impl Default for RegisterRamReset {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
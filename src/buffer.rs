@@ -0,0 +1,89 @@
+//! A fixed-capacity string buffer.
+
+use core::{fmt, fmt::Write, str};
+
+/// A string stored inline with a fixed capacity.
+///
+/// Writing past the capacity silently truncates the written string at a character boundary, so
+/// the contents are always valid UTF-8.
+pub(crate) struct Buffer<const CAPACITY: usize> {
+    bytes: [u8; CAPACITY],
+    /// The number of bytes written to `bytes`.
+    len: usize,
+}
+
+impl<const CAPACITY: usize> Buffer<CAPACITY> {
+    /// Creates a new, empty buffer.
+    pub(crate) const fn new() -> Self {
+        Self {
+            bytes: [0; CAPACITY],
+            len: 0,
+        }
+    }
+
+    /// Removes all contents from the buffer.
+    pub(crate) fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// Returns the contents of the buffer.
+    pub(crate) fn as_str(&self) -> &str {
+        // SAFETY: Only complete characters are ever written to the buffer.
+        unsafe { str::from_utf8_unchecked(&self.bytes[..self.len]) }
+    }
+}
+
+impl<const CAPACITY: usize> Write for Buffer<CAPACITY> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut len = s.len().min(CAPACITY - self.len);
+        // Only write complete characters, so that the buffer remains valid UTF-8.
+        while !s.is_char_boundary(len) {
+            len -= 1;
+        }
+        self.bytes[self.len..self.len + len].copy_from_slice(&s.as_bytes()[..len]);
+        self.len += len;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Buffer;
+    use core::fmt::Write;
+
+    #[test]
+    fn empty() {
+        assert_eq!(Buffer::<4>::new().as_str(), "");
+    }
+
+    #[test]
+    fn write() {
+        let mut buffer = Buffer::<16>::new();
+        write!(buffer, "foo {}", 42).unwrap();
+        assert_eq!(buffer.as_str(), "foo 42");
+    }
+
+    #[test]
+    fn write_truncated() {
+        let mut buffer = Buffer::<4>::new();
+        buffer.write_str("foo").unwrap();
+        buffer.write_str("bar").unwrap();
+        assert_eq!(buffer.as_str(), "foob");
+    }
+
+    #[test]
+    fn write_truncated_char_boundary() {
+        let mut buffer = Buffer::<4>::new();
+        buffer.write_str("ab\u{e9}\u{e9}").unwrap();
+        assert_eq!(buffer.as_str(), "ab\u{e9}");
+    }
+
+    #[test]
+    fn clear() {
+        let mut buffer = Buffer::<4>::new();
+        buffer.write_str("foo").unwrap();
+        buffer.clear();
+        buffer.write_str("bar").unwrap();
+        assert_eq!(buffer.as_str(), "bar");
+    }
+}
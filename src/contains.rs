@@ -0,0 +1,127 @@
+//! Substring searching within formatted output.
+//!
+//! Panic messages can't be stored without an allocator, so they are instead searched as they are
+//! formatted.
+
+use core::{
+    fmt,
+    fmt::{Display, Write},
+};
+
+/// Searches for a pattern within a stream of written strings.
+struct Matcher<'a> {
+    pattern: &'a [u8],
+    /// The length of the longest prefix of `pattern` that is a suffix of the data written so far.
+    matched: usize,
+    /// Whether the full pattern has been found.
+    found: bool,
+}
+
+impl<'a> Matcher<'a> {
+    fn new(pattern: &'a str) -> Self {
+        Self {
+            pattern: pattern.as_bytes(),
+            matched: 0,
+            found: pattern.is_empty(),
+        }
+    }
+
+    /// Advances the match state by a single byte.
+    fn push(&mut self, byte: u8) {
+        // The last `matched` bytes written are exactly `pattern[..matched]`, so the longest prefix
+        // of the pattern that is still matched after this byte can be found using only the
+        // pattern itself.
+        let matched = self.matched;
+        self.matched = (0..=(matched + 1).min(self.pattern.len()))
+            .rev()
+            .find(|&len| {
+                len == 0
+                    || (self.pattern[len - 1] == byte
+                        && self.pattern[..len - 1] == self.pattern[matched + 1 - len..matched])
+            })
+            .unwrap_or(0);
+        if self.matched == self.pattern.len() {
+            self.found = true;
+        }
+    }
+}
+
+impl Write for Matcher<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for &byte in s.as_bytes() {
+            if self.found {
+                // Stop formatting early.
+                return Err(fmt::Error);
+            }
+            self.push(byte);
+        }
+        Ok(())
+    }
+}
+
+/// Returns whether the formatted `value` contains `pattern`.
+pub(crate) fn contains<T>(value: T, pattern: &str) -> bool
+where
+    T: Display,
+{
+    let mut matcher = Matcher::new(pattern);
+    // An error is returned when formatting is stopped early, which only happens once the pattern
+    // is found.
+    let _ = write!(matcher, "{}", value);
+    matcher.found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::contains;
+
+    #[test]
+    fn empty_pattern() {
+        assert!(contains("foo", ""));
+        assert!(contains("", ""));
+    }
+
+    #[test]
+    fn empty_value() {
+        assert!(!contains("", "foo"));
+    }
+
+    #[test]
+    fn equal() {
+        assert!(contains("foo", "foo"));
+    }
+
+    #[test]
+    fn prefix() {
+        assert!(contains("foobar", "foo"));
+    }
+
+    #[test]
+    fn suffix() {
+        assert!(contains("foobar", "bar"));
+    }
+
+    #[test]
+    fn missing() {
+        assert!(!contains("foobar", "baz"));
+        assert!(!contains("foo", "foobar"));
+    }
+
+    #[test]
+    fn overlapping_partial_match() {
+        assert!(contains("aaab", "aab"));
+        assert!(contains("abababc", "ababc"));
+        assert!(!contains("abababd", "ababc"));
+    }
+
+    #[test]
+    fn across_arguments() {
+        assert!(contains(format_args!("{}{}", "fo", "obar"), "oob"));
+    }
+
+    #[test]
+    fn multibyte() {
+        assert!(contains("h\u{e9}llo", "\u{e9}l"));
+        assert!(!contains("h\u{e9}llo", "el"));
+    }
+}
@@ -0,0 +1,394 @@
+//! Listing of the tests in a built ROM.
+//!
+//! The `#[test]` attribute places a record describing each test in the `.gba_test_meta` section.
+//! The section is not loaded onto the Game Boy Advance, but it is kept in the ELF file, so that
+//! host tools can list the tests using [`list_tests()`] without running the ROM. This only works if
+//! the linker script keeps the section, such as with:
+//!
+//! ```text
+//! .gba_test_meta (INFO) : {
+//!   KEEP(*(.gba_test_meta .gba_test_meta.*));
+//! }
+//! ```
+
+use crate::Test;
+use alloc::{borrow::ToOwned, string::String, vec::Vec};
+use core::{fmt, fmt::Display, str};
+
+/// The name of the section containing the test records.
+const SECTION: &[u8] = b".gba_test_meta";
+/// The size of a single test record, as laid out by `TestRecord` on the Game Boy Advance.
+const RECORD_SIZE: usize = 20;
+
+/// The ELF identification bytes of a 32-bit little-endian ELF file.
+pub(crate) const IDENT: [u8; 6] = [0x7F, b'E', b'L', b'F', 1, 1];
+/// The size of an ELF section header.
+const SECTION_HEADER_SIZE: usize = 40;
+/// The type of a section that takes up no space in the file.
+const SHT_NOBITS: u32 = 8;
+/// The flag of a section that is loaded into memory.
+const SHF_ALLOC: u32 = 0x2;
+
+/// A test found in a built ROM.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TestMeta {
+    /// The name of the test, as reported in its results.
+    pub name: String,
+    /// The path of the module the test is defined in, followed by the name of the test.
+    pub path: String,
+    /// Whether the test is annotated with `#[ignore]`.
+    pub ignore: bool,
+    /// Whether the test is annotated with `#[should_panic]`.
+    pub should_panic: bool,
+    /// Whether the test is annotated with `#[should_err]`.
+    pub should_err: bool,
+}
+
+/// An error encountered while listing the tests in an ELF file.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum Error {
+    /// The data is not a 32-bit little-endian ELF file.
+    NotElf,
+    /// The ELF file's headers or test records refer to data outside of the file.
+    Malformed,
+}
+
+impl Display for Error {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NotElf => formatter.write_str("not a 32-bit little-endian ELF file"),
+            Self::Malformed => formatter.write_str("malformed ELF file"),
+        }
+    }
+}
+
+/// Reads the `u16` at `offset`.
+pub(crate) fn read_u16(bytes: &[u8], offset: usize) -> Result<u16, Error> {
+    bytes
+        .get(offset..offset + 2)
+        .map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]))
+        .ok_or(Error::Malformed)
+}
+
+/// Reads the `u32` at `offset`.
+pub(crate) fn read_u32(bytes: &[u8], offset: usize) -> Result<u32, Error> {
+    bytes
+        .get(offset..offset + 4)
+        .map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+        .ok_or(Error::Malformed)
+}
+
+/// The parts of an ELF section header needed to find the test records and their strings.
+struct SectionHeader {
+    name: u32,
+    kind: u32,
+    flags: u32,
+    address: u32,
+    offset: u32,
+    size: u32,
+}
+
+impl SectionHeader {
+    /// Reads the section header at `offset`.
+    fn read(bytes: &[u8], offset: usize) -> Result<Self, Error> {
+        Ok(Self {
+            name: read_u32(bytes, offset)?,
+            kind: read_u32(bytes, offset + 4)?,
+            flags: read_u32(bytes, offset + 8)?,
+            address: read_u32(bytes, offset + 12)?,
+            offset: read_u32(bytes, offset + 16)?,
+            size: read_u32(bytes, offset + 20)?,
+        })
+    }
+
+    /// Returns the contents of the section.
+    fn contents<'a>(&self, bytes: &'a [u8]) -> Result<&'a [u8], Error> {
+        bytes
+            .get(self.offset as usize..)
+            .and_then(|contents| contents.get(..self.size as usize))
+            .ok_or(Error::Malformed)
+    }
+}
+
+/// Reads the string at `address`, looking it up in the sections loaded into memory.
+fn read_string(
+    bytes: &[u8],
+    sections: &[SectionHeader],
+    address: u32,
+    len: u32,
+) -> Result<String, Error> {
+    let section = sections
+        .iter()
+        .find(|section| {
+            section.flags & SHF_ALLOC != 0
+                && section.kind != SHT_NOBITS
+                && address >= section.address
+                && u64::from(address) + u64::from(len)
+                    <= u64::from(section.address) + u64::from(section.size)
+        })
+        .ok_or(Error::Malformed)?;
+    let start = (address - section.address) as usize;
+    let string = &section.contents(bytes)?[start..start + len as usize];
+    str::from_utf8(string)
+        .map(ToOwned::to_owned)
+        .map_err(|_| Error::Malformed)
+}
+
+/// Lists the tests defined in an ELF file built for the Game Boy Advance.
+///
+/// The tests are returned in the order their records were placed by the linker, which is not
+/// necessarily the order they are run in. If the ELF file has no `.gba_test_meta` section, such as
+/// when the linker script discards it, no tests are returned.
+///
+/// # Example
+/// ```no_run
+/// use std::fs;
+///
+/// let elf = fs::read("target/thumbv4t-none-eabi/debug/deps/tests-0123456789abcdef").unwrap();
+/// for test in gba_test::elf::list_tests(&elf).unwrap() {
+///     println!("{} ({})", test.name, test.path);
+/// }
+/// ```
+pub fn list_tests(bytes: &[u8]) -> Result<Vec<TestMeta>, Error> {
+    if bytes.get(..IDENT.len()) != Some(&IDENT) {
+        return Err(Error::NotElf);
+    }
+    let section_headers = read_u32(bytes, 0x20)? as usize;
+    let section_header_size = read_u16(bytes, 0x2E)? as usize;
+    let section_count = read_u16(bytes, 0x30)? as usize;
+    let names_index = read_u16(bytes, 0x32)? as usize;
+    if section_header_size < SECTION_HEADER_SIZE {
+        return Err(Error::Malformed);
+    }
+    let sections = (0..section_count)
+        .map(|index| SectionHeader::read(bytes, section_headers + index * section_header_size))
+        .collect::<Result<Vec<_>, _>>()?;
+    let names = sections
+        .get(names_index)
+        .ok_or(Error::Malformed)?
+        .contents(bytes)?;
+
+    let mut tests = Vec::new();
+    for section in &sections {
+        let name = names
+            .get(section.name as usize..)
+            .and_then(|name| name.split(|&byte| byte == 0).next())
+            .ok_or(Error::Malformed)?;
+        if name != SECTION {
+            continue;
+        }
+        let records = section.contents(bytes)?;
+        if records.len() % RECORD_SIZE != 0 {
+            return Err(Error::Malformed);
+        }
+        for record in records.chunks_exact(RECORD_SIZE) {
+            let flags = record[16];
+            tests.push(TestMeta {
+                name: read_string(bytes, &sections, read_u32(record, 0)?, read_u32(record, 4)?)?,
+                path: read_string(
+                    bytes,
+                    &sections,
+                    read_u32(record, 8)?,
+                    read_u32(record, 12)?,
+                )?,
+                ignore: flags & Test::IGNORE != 0,
+                should_panic: flags & Test::SHOULD_PANIC != 0,
+                should_err: flags & Test::SHOULD_ERR != 0,
+            });
+        }
+    }
+    Ok(tests)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{list_tests, Error, TestMeta, SHF_ALLOC, SHT_NOBITS};
+    use crate::Test;
+    use alloc::{vec, vec::Vec};
+
+    /// The type of a section containing program data.
+    const SHT_PROGBITS: u32 = 1;
+    /// The type of a section containing strings.
+    const SHT_STRTAB: u32 = 3;
+    /// The address of the ROM.
+    const ROM: u32 = 0x0800_0000;
+
+    /// A section to be written into an ELF file.
+    struct Section<'a> {
+        name: &'a str,
+        kind: u32,
+        flags: u32,
+        address: u32,
+        contents: Vec<u8>,
+    }
+
+    /// Writes a minimal 32-bit little-endian ELF file containing the given sections.
+    fn elf(sections: Vec<Section>) -> Vec<u8> {
+        let mut names = vec![0];
+        let mut sections = sections;
+        sections.push(Section {
+            name: ".shstrtab",
+            kind: SHT_STRTAB,
+            flags: 0,
+            address: 0,
+            contents: Vec::new(),
+        });
+        let mut name_offsets = Vec::new();
+        for section in &sections {
+            name_offsets.push(names.len() as u32);
+            names.extend(section.name.as_bytes());
+            names.push(0);
+        }
+        let last = sections.len() - 1;
+        sections[last].contents = names;
+
+        let mut bytes = vec![0; 52];
+        bytes[..6].copy_from_slice(&[0x7F, b'E', b'L', b'F', 1, 1]);
+        let mut offsets = Vec::new();
+        for section in &sections {
+            offsets.push(bytes.len() as u32);
+            bytes.extend(&section.contents);
+        }
+        let section_headers = bytes.len() as u32;
+        bytes.extend([0; 40]);
+        for ((section, name), offset) in sections.iter().zip(name_offsets).zip(offsets) {
+            for value in [
+                name,
+                section.kind,
+                section.flags,
+                section.address,
+                offset,
+                section.contents.len() as u32,
+            ] {
+                bytes.extend(value.to_le_bytes());
+            }
+            bytes.extend([0; 16]);
+        }
+        bytes[0x20..0x24].copy_from_slice(&section_headers.to_le_bytes());
+        bytes[0x2E..0x30].copy_from_slice(&40u16.to_le_bytes());
+        bytes[0x30..0x32].copy_from_slice(&(sections.len() as u16 + 1).to_le_bytes());
+        bytes[0x32..0x34].copy_from_slice(&(sections.len() as u16).to_le_bytes());
+        bytes
+    }
+
+    /// Returns a record for the strings at the given offsets into the ROM.
+    fn record(name: (u32, u32), path: (u32, u32), flags: u8) -> Vec<u8> {
+        let mut record = Vec::new();
+        for value in [ROM + name.0, name.1, ROM + path.0, path.1] {
+            record.extend(value.to_le_bytes());
+        }
+        record.extend([flags, 0, 0, 0]);
+        record
+    }
+
+    fn rodata(contents: &[u8]) -> Section<'static> {
+        Section {
+            name: ".rodata",
+            kind: SHT_PROGBITS,
+            flags: SHF_ALLOC,
+            address: ROM,
+            contents: contents.to_vec(),
+        }
+    }
+
+    fn meta(contents: Vec<u8>) -> Section<'static> {
+        Section {
+            name: ".gba_test_meta",
+            kind: SHT_PROGBITS,
+            flags: 0,
+            address: 0,
+            contents,
+        }
+    }
+
+    #[test]
+    fn lists_tests() {
+        let mut records = record((0, 3), (3, 17), 0);
+        records.extend(record((20, 3), (23, 17), Test::IGNORE | Test::SHOULD_PANIC));
+        let bytes = elf(vec![
+            rodata(b"foocrate::tests::foobarcrate::tests::bar"),
+            meta(records),
+        ]);
+
+        assert_eq!(
+            list_tests(&bytes),
+            Ok(vec![
+                TestMeta {
+                    name: "foo".into(),
+                    path: "crate::tests::foo".into(),
+                    ignore: false,
+                    should_panic: false,
+                    should_err: false,
+                },
+                TestMeta {
+                    name: "bar".into(),
+                    path: "crate::tests::bar".into(),
+                    ignore: true,
+                    should_panic: true,
+                    should_err: false,
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn no_section() {
+        let bytes = elf(vec![rodata(b"foo")]);
+
+        assert_eq!(list_tests(&bytes), Ok(vec![]));
+    }
+
+    #[test]
+    fn not_elf() {
+        assert_eq!(list_tests(b"not an elf file"), Err(Error::NotElf));
+        assert_eq!(list_tests(&[]), Err(Error::NotElf));
+    }
+
+    #[test]
+    fn not_32_bit() {
+        let mut bytes = elf(vec![rodata(b"foo")]);
+        bytes[4] = 2;
+
+        assert_eq!(list_tests(&bytes), Err(Error::NotElf));
+    }
+
+    #[test]
+    fn string_outside_of_sections() {
+        let bytes = elf(vec![rodata(b"foo"), meta(record((0, 3), (3, 17), 0))]);
+
+        assert_eq!(list_tests(&bytes), Err(Error::Malformed));
+    }
+
+    #[test]
+    fn string_in_unloaded_section() {
+        let bytes = elf(vec![
+            Section {
+                name: ".bss",
+                kind: SHT_NOBITS,
+                flags: SHF_ALLOC,
+                address: ROM,
+                contents: b"foocrate::tests::foo".to_vec(),
+            },
+            meta(record((0, 3), (3, 17), 0)),
+        ]);
+
+        assert_eq!(list_tests(&bytes), Err(Error::Malformed));
+    }
+
+    #[test]
+    fn partial_record() {
+        let mut records = record((0, 3), (3, 17), 0);
+        records.pop();
+        let bytes = elf(vec![rodata(b"foocrate::tests::foo"), meta(records)]);
+
+        assert_eq!(list_tests(&bytes), Err(Error::Malformed));
+    }
+
+    #[test]
+    fn truncated() {
+        let bytes = elf(vec![rodata(b"foo")]);
+
+        assert_eq!(list_tests(&bytes[..40]), Err(Error::Malformed));
+    }
+}
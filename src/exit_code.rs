@@ -0,0 +1,113 @@
+//! The exit code reported once all tests have been run.
+
+use crate::Summary;
+
+/// The exit code reported by the test runner once all tests have been run.
+///
+/// By default, the runner places this value in `r0` before halting with `swi #0x03`. When using
+/// `mgba-rom-test`, it can be used as the emulator's exit code by passing `-S 0x03 -R r0`. Host
+/// tooling can compare the emulator's exit code against these values by casting them. Other
+/// reporting methods can be configured using `set_report_method()`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(usize)]
+pub enum ExitCode {
+    /// All tests passed or were ignored.
+    Passed = 0,
+    /// At least one test failed.
+    Failed = 1,
+    /// The test framework itself failed, so the results are incomplete.
+    FrameworkPanic = 2,
+    /// No tests were found.
+    NoTests = 3,
+    /// At least one test was aborted after running for too long.
+    TimedOut = 4,
+}
+
+impl ExitCode {
+    /// Determines the exit code for a completed test run.
+    ///
+    /// Timeouts take precedence over other failures, as they likely indicate a test that hangs
+    /// entirely.
+    pub fn from_summary(summary: &Summary) -> Self {
+        if summary.timed_out > 0 {
+            Self::TimedOut
+        } else if summary.failed > 0 {
+            Self::Failed
+        } else if summary.passed == 0 && summary.ignored == 0 {
+            Self::NoTests
+        } else {
+            Self::Passed
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ExitCode;
+    use crate::Summary;
+
+    #[test]
+    fn passed() {
+        assert_eq!(
+            ExitCode::from_summary(&Summary {
+                passed: 2,
+                ignored: 1,
+                ..Summary::default()
+            }),
+            ExitCode::Passed
+        );
+    }
+
+    #[test]
+    fn all_ignored() {
+        assert_eq!(
+            ExitCode::from_summary(&Summary {
+                ignored: 1,
+                ..Summary::default()
+            }),
+            ExitCode::Passed
+        );
+    }
+
+    #[test]
+    fn failed() {
+        assert_eq!(
+            ExitCode::from_summary(&Summary {
+                passed: 2,
+                failed: 1,
+                ..Summary::default()
+            }),
+            ExitCode::Failed
+        );
+    }
+
+    #[test]
+    fn no_tests() {
+        assert_eq!(
+            ExitCode::from_summary(&Summary::default()),
+            ExitCode::NoTests
+        );
+    }
+
+    #[test]
+    fn timed_out() {
+        assert_eq!(
+            ExitCode::from_summary(&Summary {
+                passed: 1,
+                failed: 2,
+                timed_out: 1,
+                ..Summary::default()
+            }),
+            ExitCode::TimedOut
+        );
+    }
+
+    #[test]
+    fn values() {
+        assert_eq!(ExitCode::Passed as usize, 0);
+        assert_eq!(ExitCode::Failed as usize, 1);
+        assert_eq!(ExitCode::FrameworkPanic as usize, 2);
+        assert_eq!(ExitCode::NoTests as usize, 3);
+        assert_eq!(ExitCode::TimedOut as usize, 4);
+    }
+}
@@ -0,0 +1,197 @@
+//! A list of the names of failed tests, kept at a known symbol.
+//!
+//! CI scripts often only need to know which tests failed. Rather than decoding the full results,
+//! they can read this list directly from a memory dump or savestate, by looking up the
+//! `GBA_TEST_FAILED_NAMES` symbol in the test executable. Its layout is:
+//!
+//! | Offset | Size | Contents                                       |
+//! |--------|------|------------------------------------------------|
+//! | 0      | 4    | The magic bytes `GBTF`                         |
+//! | 4      | 2    | The length of the list in bytes, little-endian |
+//! | 6      | 2042 | The list, one name per line                    |
+//!
+//! If the names of all failed tests do not fit, the list ends with a line containing `...`.
+//!
+//! The list is kept in EWRAM, in the `.gba_test_ewram` section, so that it does not take up space
+//! in the much smaller IWRAM. The section is never loaded or zeroed, since the runner initializes
+//! the list before running any tests. The linker script must place it in EWRAM, such as with:
+//!
+//! ```text
+//! .gba_test_ewram (NOLOAD) : {
+//!   KEEP(*(.gba_test_ewram .gba_test_ewram.*));
+//!   . = ALIGN(4);
+//! } >ewram
+//! ```
+
+/// The magic bytes at the start of the list, identifying it in a memory dump.
+const MAGIC: [u8; 4] = *b"GBTF";
+
+/// The line ending the list if not all names fit.
+const TRUNCATED_MARKER: &[u8] = b"...\n";
+
+/// The number of bytes available for names.
+#[cfg(not(test))]
+const CAPACITY: usize = 2042;
+
+/// A newline-separated list of test names, stored inline with a fixed capacity.
+///
+/// The list is word-aligned, so that the memory around it can be cleared a word at a time.
+#[repr(C, align(4))]
+pub(crate) struct FailedNames<const CAPACITY: usize> {
+    magic: [u8; 4],
+    /// The number of bytes written to `names`.
+    len: u16,
+    names: [u8; CAPACITY],
+}
+
+impl<const CAPACITY: usize> FailedNames<CAPACITY> {
+    /// Creates a new, empty list.
+    ///
+    /// The magic bytes are not written until the list is cleared.
+    pub(crate) const fn new() -> Self {
+        Self {
+            magic: [0; 4],
+            len: 0,
+            names: [0; CAPACITY],
+        }
+    }
+
+    /// Removes all names from the list, marking it as valid.
+    pub(crate) fn clear(&mut self) {
+        self.magic = MAGIC;
+        self.len = 0;
+    }
+
+    /// Returns the names in the list, each followed by a newline.
+    #[cfg(test)]
+    fn as_bytes(&self) -> &[u8] {
+        &self.names[..self.len as usize]
+    }
+
+    /// Appends a name to the list.
+    ///
+    /// If the name does not fit, the list is ended with a `...` line instead, and no further names
+    /// are appended.
+    pub(crate) fn push(&mut self, name: &str) {
+        let len = self.len as usize;
+        if self.names[..len].ends_with(TRUNCATED_MARKER) {
+            return;
+        }
+        let remaining = CAPACITY - len;
+        if name.len() + 1 + TRUNCATED_MARKER.len() <= remaining {
+            self.names[len..len + name.len()].copy_from_slice(name.as_bytes());
+            self.names[len + name.len()] = b'\n';
+            self.len += name.len() as u16 + 1;
+        } else if TRUNCATED_MARKER.len() <= remaining {
+            self.names[len..len + TRUNCATED_MARKER.len()].copy_from_slice(TRUNCATED_MARKER);
+            self.len += TRUNCATED_MARKER.len() as u16;
+        }
+    }
+}
+
+/// The names of the tests that have failed so far.
+///
+/// This is not loaded, so it is only valid after [`clear()`] has been called.
+#[cfg(not(test))]
+#[no_mangle]
+#[used]
+#[link_section = ".gba_test_ewram"]
+static mut GBA_TEST_FAILED_NAMES: FailedNames<CAPACITY> = FailedNames::new();
+
+/// Removes all names from the list.
+#[cfg(not(test))]
+pub(crate) fn clear() {
+    // SAFETY: `GBA_TEST_FAILED_NAMES` is only ever accessed on the main thread.
+    unsafe {
+        (*core::ptr::addr_of_mut!(GBA_TEST_FAILED_NAMES)).clear();
+    }
+}
+
+/// Returns the addresses of the memory holding the list.
+#[cfg(not(test))]
+pub(crate) fn region() -> core::ops::Range<usize> {
+    let start = core::ptr::addr_of!(GBA_TEST_FAILED_NAMES) as usize;
+    start..start + core::mem::size_of::<FailedNames<CAPACITY>>()
+}
+
+/// Records the name of a failed test.
+#[cfg(not(test))]
+pub(crate) fn record(name: &str) {
+    // SAFETY: `GBA_TEST_FAILED_NAMES` is only ever accessed on the main thread.
+    unsafe {
+        (*core::ptr::addr_of_mut!(GBA_TEST_FAILED_NAMES)).push(name);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FailedNames;
+    use core::mem;
+
+    #[test]
+    fn layout() {
+        let mut names = FailedNames::<2042>::new();
+        names.clear();
+        names.push("foo");
+
+        assert_eq!(mem::size_of::<FailedNames<2042>>(), 2048);
+        // SAFETY: `FailedNames` is `repr(C)` and contains no padding.
+        let bytes: [u8; 2048] = unsafe { mem::transmute(names) };
+        assert_eq!(&bytes[..10], b"GBTF\x04\x00foo\n");
+    }
+
+    #[test]
+    fn empty() {
+        let mut names = FailedNames::<16>::new();
+        names.clear();
+        assert_eq!(names.as_bytes(), b"");
+    }
+
+    #[test]
+    fn push() {
+        let mut names = FailedNames::<16>::new();
+        names.clear();
+        names.push("foo");
+        names.push("bar");
+        assert_eq!(names.as_bytes(), b"foo\nbar\n");
+    }
+
+    #[test]
+    fn clear() {
+        let mut names = FailedNames::<16>::new();
+        names.clear();
+        names.push("foo");
+        names.clear();
+        names.push("bar");
+        assert_eq!(names.as_bytes(), b"bar\n");
+    }
+
+    #[test]
+    fn truncated() {
+        let mut names = FailedNames::<16>::new();
+        names.clear();
+        names.push("foo");
+        names.push("barbaz");
+        names.push("quux");
+        assert_eq!(names.as_bytes(), b"foo\nbarbaz\n...\n");
+    }
+
+    #[test]
+    fn truncated_stays_truncated() {
+        let mut names = FailedNames::<16>::new();
+        names.clear();
+        names.push("foobarbazquux");
+        names.push("a");
+        assert_eq!(names.as_bytes(), b"...\n");
+    }
+
+    #[test]
+    fn exactly_full() {
+        let mut names = FailedNames::<12>::new();
+        names.clear();
+        names.push("foo");
+        names.push("bar");
+        names.push("baz");
+        assert_eq!(names.as_bytes(), b"foo\nbar\n...\n");
+    }
+}
@@ -5,9 +5,6 @@
 use core::ptr;
 use postcard::ser_flavors::Flavor;
 
-/// The end of the SRAM.
-const SRAM_END: *mut u8 = 0x0E00_FFFF as *mut u8;
-
 /// Storage within SRAM.
 ///
 /// This struct manages writing serialized data directly to SRAM. It is a `postcard` flavor and can
@@ -15,17 +12,21 @@ const SRAM_END: *mut u8 = 0x0E00_FFFF as *mut u8;
 pub(crate) struct Sram {
     /// The current position in SRAM.
     cursor: *mut u8,
+    /// The position at which writing stops.
+    end: *mut u8,
 }
 
 impl Sram {
     /// Create a new SRAM writer.
     ///
-    /// This creates a writer to SRAM at the given pointer location.
+    /// This creates a writer to SRAM at the given pointer location, which fails with
+    /// `SerializeBufferFull` once it reaches `end`.
     ///
     /// # Safety
-    /// The pointer location must be a valid location within SRAM (0x0E00_0000 to 0x0E00_FFFF).
-    pub(crate) unsafe fn new(ptr: *mut u8) -> Self {
-        Self { cursor: ptr }
+    /// Every location from `ptr` up to, but not including, `end` must be valid for writes, such as
+    /// locations within SRAM (0x0E00_0000 to 0x0E00_FFFF).
+    pub(crate) unsafe fn new(ptr: *mut u8, end: *mut u8) -> Self {
+        Self { cursor: ptr, end }
     }
 }
 
@@ -34,8 +35,7 @@ impl Flavor for Sram {
     type Output = *mut u8;
 
     fn try_push(&mut self, data: u8) -> postcard::Result<()> {
-        // We can write up to and including SRAM_END.
-        if self.cursor >= SRAM_END {
+        if self.cursor >= self.end {
             return Err(postcard::Error::SerializeBufferFull);
         }
         // SAFETY: These writes will always be to a valid location.
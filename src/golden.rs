@@ -0,0 +1,290 @@
+//! Comparison of memory against golden data.
+//!
+//! Golden tests check rendering code by comparing the contents of video memory against a known
+//! good snapshot embedded in the ROM. On mismatch, the failure message summarizes how much of the
+//! region differs and where.
+//!
+//! ```no_run
+//! use gba_test::assert_matches_golden;
+//!
+//! # fn render_scene() {}
+//! render_scene();
+//! // SAFETY: VRAM is valid for reads.
+//! unsafe {
+//!     assert_matches_golden!(0x0600_0000 as *const u8, "golden/scene.bin");
+//! }
+//! ```
+
+use core::{
+    fmt,
+    fmt::{Display, Formatter},
+    mem, ptr,
+};
+
+/// The number of sections in each row of the summary of where differences are.
+///
+/// The region is split into `GRID * GRID` sections, shown as `GRID` rows of `GRID` sections each.
+const GRID: usize = 4;
+
+/// A mismatch between memory and golden data.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Mismatch {
+    /// The number of bytes that differ.
+    pub differing_bytes: usize,
+    /// The offset of the first differing byte.
+    pub first_offset: usize,
+    /// The total number of bytes compared.
+    pub len: usize,
+    /// The number of differing bytes within each of 16 equally-sized, consecutive byte ranges of
+    /// the region.
+    ///
+    /// Range `row * 4 + column` is at `sections[row][column]`, so each row covers a consecutive
+    /// quarter of the region. The ranges are split by offset only, without regard to the width of
+    /// an image, so for image data each range covers a horizontal band of the image rather than a
+    /// rectangular area of it.
+    pub sections: [[u32; GRID]; GRID],
+}
+
+impl Mismatch {
+    /// Records a differing byte at `offset`.
+    fn record(&mut self, offset: usize) {
+        if self.differing_bytes == 0 {
+            self.first_offset = offset;
+        }
+        self.differing_bytes += 1;
+        let section = offset * GRID * GRID / self.len;
+        self.sections[section / GRID][section % GRID] += 1;
+    }
+}
+
+impl Display for Mismatch {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "{} of {} bytes differ from golden, first at offset {:#x}\ndifferences by section:",
+            self.differing_bytes, self.len, self.first_offset
+        )?;
+        for row in &self.sections {
+            formatter.write_str("\n")?;
+            for count in row {
+                write!(formatter, " {:>6}", count)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Compares the memory starting at `actual` against `golden`.
+///
+/// Memory is read using volatile reads, a word at a time when both `actual` and `golden` are
+/// 4-byte aligned.
+///
+/// # Safety
+/// `actual` must be valid for volatile reads of `golden.len()` bytes.
+pub unsafe fn compare(actual: *const u8, golden: &[u8]) -> Result<(), Mismatch> {
+    let mut mismatch = Mismatch {
+        differing_bytes: 0,
+        first_offset: 0,
+        len: golden.len(),
+        sections: [[0; GRID]; GRID],
+    };
+    let word_size = mem::size_of::<u32>();
+
+    let mut offset = 0;
+    let actual_words = actual.cast::<u32>();
+    let golden_words = golden.as_ptr().cast::<u32>();
+    if actual_words.is_aligned() && golden_words.is_aligned() {
+        for index in 0..golden.len() / word_size {
+            // SAFETY: Both pointers are aligned, and `actual` is guaranteed by the caller to be
+            // valid for reads within `golden`'s length.
+            let (actual_word, golden_word) = unsafe {
+                (
+                    ptr::read_volatile(actual_words.add(index)),
+                    ptr::read(golden_words.add(index)),
+                )
+            };
+            if actual_word != golden_word {
+                let actual_bytes = actual_word.to_ne_bytes();
+                let golden_bytes = golden_word.to_ne_bytes();
+                for byte in 0..word_size {
+                    if actual_bytes[byte] != golden_bytes[byte] {
+                        mismatch.record(offset + byte);
+                    }
+                }
+            }
+            offset += word_size;
+        }
+    }
+    for (offset, golden_byte) in golden.iter().enumerate().skip(offset) {
+        // SAFETY: `actual` is guaranteed by the caller to be valid for reads within `golden`'s
+        // length.
+        if unsafe { ptr::read_volatile(actual.add(offset)) } != *golden_byte {
+            mismatch.record(offset);
+        }
+    }
+
+    if mismatch.differing_bytes == 0 {
+        Ok(())
+    } else {
+        Err(mismatch)
+    }
+}
+
+/// Includes a file as a reference to a 4-byte aligned byte slice.
+///
+/// This works like [`include_bytes!`], but guarantees alignment, allowing the data to be compared
+/// or copied a word at a time.
+#[macro_export]
+macro_rules! include_aligned_bytes {
+    ($path:literal $(,)?) => {{
+        #[repr(C, align(4))]
+        struct Aligned<T: ?Sized>(T);
+
+        static ALIGNED: &Aligned<[u8]> = &Aligned(*::core::include_bytes!($path));
+        &ALIGNED.0
+    }};
+}
+
+/// Asserts that the memory starting at a pointer matches the contents of a golden file.
+///
+/// The file is embedded in the ROM using [`include_aligned_bytes!`], with a path relative to the
+/// current file, and its length determines how much memory is compared. On mismatch, this panics
+/// with the number of differing bytes, the offset of the first, and the number of differences in
+/// each of 16 equally-sized, consecutive byte ranges of the region.
+///
+/// # Safety
+/// This must be invoked within an `unsafe` block. The pointer must be valid for volatile reads of
+/// the golden file's length.
+///
+/// [`include_aligned_bytes!`]: crate::include_aligned_bytes!
+#[macro_export]
+macro_rules! assert_matches_golden {
+    ($actual:expr, $path:literal $(,)?) => {
+        if let ::core::result::Result::Err(mismatch) =
+            $crate::golden::compare($actual, $crate::include_aligned_bytes!($path))
+        {
+            ::core::panic!(
+                "memory does not match golden file {}: {}",
+                ::core::stringify!($path),
+                mismatch
+            );
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compare, Mismatch};
+    use alloc::format;
+
+    #[repr(C, align(4))]
+    struct Aligned<const N: usize>([u8; N]);
+
+    #[test]
+    fn matches() {
+        let golden = Aligned([1; 64]);
+        let actual = Aligned([1; 64]);
+
+        assert_eq!(unsafe { compare(actual.0.as_ptr(), &golden.0) }, Ok(()));
+    }
+
+    #[test]
+    fn matches_unaligned() {
+        let golden = Aligned([1; 65]);
+        let actual = Aligned([1; 65]);
+
+        assert_eq!(
+            unsafe { compare(actual.0[1..].as_ptr(), &golden.0[1..]) },
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!(unsafe { compare([].as_ptr(), &[]) }, Ok(()));
+    }
+
+    #[test]
+    fn single_difference() {
+        let golden = Aligned([0; 64]);
+        let mut actual = Aligned([0; 64]);
+        actual.0[21] = 1;
+
+        assert_eq!(
+            unsafe { compare(actual.0.as_ptr(), &golden.0) },
+            Err(Mismatch {
+                differing_bytes: 1,
+                first_offset: 21,
+                len: 64,
+                sections: [[0, 0, 0, 0], [0, 1, 0, 0], [0, 0, 0, 0], [0, 0, 0, 0]],
+            })
+        );
+    }
+
+    #[test]
+    fn multiple_differences_in_word() {
+        let golden = Aligned([0; 64]);
+        let mut actual = Aligned([0; 64]);
+        actual.0[61] = 1;
+        actual.0[63] = 1;
+
+        assert_eq!(
+            unsafe { compare(actual.0.as_ptr(), &golden.0) },
+            Err(Mismatch {
+                differing_bytes: 2,
+                first_offset: 61,
+                len: 64,
+                sections: [[0, 0, 0, 0], [0, 0, 0, 0], [0, 0, 0, 0], [0, 0, 0, 2]],
+            })
+        );
+    }
+
+    #[test]
+    fn differences_in_tail() {
+        let golden = Aligned([0; 18]);
+        let mut actual = Aligned([0; 18]);
+        actual.0[2] = 1;
+        actual.0[17] = 1;
+
+        assert_eq!(
+            unsafe { compare(actual.0.as_ptr(), &golden.0) },
+            Err(Mismatch {
+                differing_bytes: 2,
+                first_offset: 2,
+                len: 18,
+                sections: [[0, 1, 0, 0], [0, 0, 0, 0], [0, 0, 0, 0], [0, 0, 0, 1]],
+            })
+        );
+    }
+
+    #[test]
+    fn differences_unaligned() {
+        let golden = Aligned([0; 33]);
+        let mut actual = Aligned([0; 33]);
+        actual.0[5] = 1;
+
+        assert_eq!(
+            unsafe { compare(actual.0[1..].as_ptr(), &golden.0[1..]) },
+            Err(Mismatch {
+                differing_bytes: 1,
+                first_offset: 4,
+                len: 32,
+                sections: [[0, 0, 1, 0], [0, 0, 0, 0], [0, 0, 0, 0], [0, 0, 0, 0]],
+            })
+        );
+    }
+
+    #[test]
+    fn display() {
+        let golden = Aligned([0; 64]);
+        let mut actual = Aligned([0; 64]);
+        actual.0[21] = 1;
+        actual.0[22] = 1;
+        actual.0[63] = 1;
+
+        assert_eq!(
+            format!("{}", unsafe { compare(actual.0.as_ptr(), &golden.0) }.unwrap_err()),
+            "3 of 64 bytes differ from golden, first at offset 0x15\ndifferences by section:\n      0      0      0      0\n      0      2      0      0\n      0      0      0      0\n      0      0      0      1"
+        );
+    }
+}
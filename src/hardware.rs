@@ -0,0 +1,160 @@
+//! Detection of the hardware required by tests.
+//!
+//! Each [`Capability`] is probed the first time a test requires it, and the result is reused for
+//! the rest of the test run. Probes are conservative: anything they change is restored
+//! afterward, and hardware that can't be positively identified is treated as missing.
+//!
+//! The [`Environment`] the tests run in is detected the same way when the runner starts.
+
+use crate::{mgba_log, Capability, Environment};
+use core::ptr;
+use voladdress::{Safe, VolAddress};
+
+/// The byte of SRAM used to probe for it.
+///
+/// This is the last byte of SRAM, as results are written starting from the first.
+const SRAM_PROBE: *mut u8 = 0x0E00_FFFF as *mut u8;
+
+/// The data pins of the cartridge's GPIO port.
+const GPIO_DATA: VolAddress<u16, Safe, Safe> = unsafe { VolAddress::new(0x0800_00C4) };
+/// Which of the GPIO port's pins are outputs.
+const GPIO_DIRECTION: VolAddress<u16, Safe, Safe> = unsafe { VolAddress::new(0x0800_00C6) };
+/// Whether the GPIO port can be read.
+const GPIO_CONTROL: VolAddress<u16, Safe, Safe> = unsafe { VolAddress::new(0x0800_00C8) };
+
+/// The real-time clock's serial clock pin.
+const RTC_SCK: u16 = 0b0001;
+/// The real-time clock's serial data pin.
+const RTC_SIO: u16 = 0b0010;
+/// The real-time clock's chip select pin.
+const RTC_CS: u16 = 0b0100;
+/// The real-time clock command reading the date and time.
+const RTC_READ_DATE_TIME: u8 = 0x65;
+
+/// Serial I/O control.
+const SIOCNT: VolAddress<u16, Safe, Safe> = unsafe { VolAddress::new(0x0400_0128) };
+/// Serial I/O mode select.
+const RCNT: VolAddress<u16, Safe, Safe> = unsafe { VolAddress::new(0x0400_0134) };
+
+/// Serial control value selecting multi-player mode.
+const SIOCNT_MULTIPLAYER: u16 = 0b0010_0000_0000_0000;
+/// Serial control flag for the SI terminal, which is low for the parent.
+const SIOCNT_SI: u16 = 0b0000_0000_0000_0100;
+/// Serial control flag for the SD terminal, which is high once all connected units are ready.
+const SIOCNT_SD: u16 = 0b0000_0000_0000_1000;
+
+/// The capabilities that have been probed, as a bit for each.
+static mut PROBED: u8 = 0;
+/// The capabilities that have been detected, as a bit for each.
+static mut DETECTED: u8 = 0;
+
+/// Returns the environment the tests are running in.
+///
+/// mGBA is identified by enabling its debug output, which is safe to attempt on hardware. No other
+/// emulators or flashcarts are identified, as they expose no signature that can be read without
+/// side effects on the hardware that lacks it.
+pub(crate) fn environment() -> Environment {
+    if mgba_log::enable() {
+        Environment::Mgba
+    } else {
+        Environment::HardwareOrUnknown
+    }
+}
+
+/// Returns whether the given capability is present, probing for it if it hasn't been already.
+pub(crate) fn is_available(capability: Capability) -> bool {
+    let bit = 1 << capability as u8;
+    // SAFETY: `PROBED` and `DETECTED` are only ever accessed on the main thread.
+    unsafe {
+        if PROBED & bit == 0 {
+            let detected = match capability {
+                Capability::Sram => sram(),
+                Capability::Rtc => rtc(),
+                Capability::LinkCable => link_cable(),
+            };
+            PROBED |= bit;
+            if detected {
+                DETECTED |= bit;
+            }
+        }
+        DETECTED & bit != 0
+    }
+}
+
+/// Probes for SRAM by checking that a byte written to it can be read back.
+fn sram() -> bool {
+    // SAFETY: SRAM is valid for byte reads and writes, and anything there is restored.
+    unsafe {
+        let original = ptr::read_volatile(SRAM_PROBE);
+        ptr::write_volatile(SRAM_PROBE, !original);
+        let written = ptr::read_volatile(SRAM_PROBE);
+        ptr::write_volatile(SRAM_PROBE, original);
+        written == !original
+    }
+}
+
+/// Probes for a real-time clock by reading the date from it.
+///
+/// Without a clock, the GPIO port reads back the ROM's contents, which are the same for every bit
+/// and therefore never form a valid month and day.
+fn rtc() -> bool {
+    GPIO_CONTROL.write(1);
+    GPIO_DATA.write(RTC_SCK);
+    GPIO_DATA.write(RTC_SCK | RTC_CS);
+    GPIO_DIRECTION.write(RTC_SCK | RTC_SIO | RTC_CS);
+    rtc_write(RTC_READ_DATE_TIME);
+    GPIO_DIRECTION.write(RTC_SCK | RTC_CS);
+    let _year = rtc_read();
+    let month = rtc_read();
+    let day = rtc_read();
+    // The rest of the date and time is still read, so that the command is completed.
+    for _ in 0..4 {
+        rtc_read();
+    }
+    GPIO_DATA.write(RTC_SCK);
+    GPIO_DIRECTION.write(0);
+    GPIO_CONTROL.write(0);
+
+    is_bcd(month) && (0x01..=0x12).contains(&month) && is_bcd(day) && (0x01..=0x31).contains(&day)
+}
+
+/// Writes a byte to the real-time clock, most significant bit first.
+fn rtc_write(value: u8) {
+    for bit in (0..8).rev() {
+        let sio = u16::from((value >> bit) & 1) << 1;
+        GPIO_DATA.write(sio | RTC_CS);
+        GPIO_DATA.write(sio | RTC_SCK | RTC_CS);
+    }
+}
+
+/// Reads a byte from the real-time clock, least significant bit first.
+fn rtc_read() -> u8 {
+    let mut value = 0;
+    for _ in 0..8 {
+        GPIO_DATA.write(RTC_CS);
+        GPIO_DATA.write(RTC_SCK | RTC_CS);
+        let bit = ((GPIO_DATA.read() & RTC_SIO) >> 1) as u8;
+        value = (value >> 1) | (bit << 7);
+    }
+    value
+}
+
+/// Returns whether both digits of the byte are valid binary-coded decimal.
+fn is_bcd(value: u8) -> bool {
+    value & 0x0F <= 9 && value >> 4 <= 9
+}
+
+/// Probes for a link cable by checking whether this unit is a ready multi-player parent.
+///
+/// Without a cable, the SI terminal is pulled high, which is indistinguishable from being a child.
+fn link_cable() -> bool {
+    let rcnt = RCNT.read();
+    let siocnt = SIOCNT.read();
+    RCNT.write(0);
+    SIOCNT.write(SIOCNT_MULTIPLAYER);
+    let status = SIOCNT.read();
+    SIOCNT.write(siocnt);
+    RCNT.write(rcnt);
+
+    status & SIOCNT_SI == 0 && status & SIOCNT_SD != 0
+}
@@ -0,0 +1,220 @@
+//! Reporting of IO registers changed by a failing test.
+//!
+//! When the `io-diff` feature is enabled, the runner records the values of a fixed set of IO
+//! registers just before each test is run. If the test fails, the registers whose values have
+//! changed are appended to its failure message, such as `DISPCNT 0400→0000, IE 0001→0041`. This
+//! helps to track down tests that only fail when run after certain other tests.
+//!
+//! Timer 1 and its interrupt are used by the watchdog, so they are not compared.
+
+use core::{fmt, fmt::Display};
+#[cfg(not(test))]
+use {
+    core::ptr,
+    voladdress::{Safe, VolAddress},
+};
+
+/// An IO register compared before and after a test.
+struct Register {
+    name: &'static str,
+    address: usize,
+    /// The bits of the register that are compared.
+    mask: u16,
+}
+
+impl Register {
+    const fn new(name: &'static str, address: usize) -> Self {
+        Self {
+            name,
+            address,
+            mask: 0xFFFF,
+        }
+    }
+
+    /// Reads the register's current value.
+    #[cfg(not(test))]
+    fn read(&self) -> u16 {
+        // SAFETY: Every address in `REGISTERS` is a readable 16-bit IO register.
+        unsafe { VolAddress::<u16, Safe, ()>::new(self.address).read() }
+    }
+}
+
+/// The registers compared before and after each test.
+const REGISTERS: [Register; 17] = [
+    Register::new("DISPCNT", 0x0400_0000),
+    Register::new("BG0CNT", 0x0400_0008),
+    Register::new("BG1CNT", 0x0400_000A),
+    Register::new("BG2CNT", 0x0400_000C),
+    Register::new("BG3CNT", 0x0400_000E),
+    Register::new("SOUNDCNT_L", 0x0400_0080),
+    Register::new("SOUNDCNT_H", 0x0400_0082),
+    Register::new("SOUNDCNT_X", 0x0400_0084),
+    Register::new("DMA0CNT_H", 0x0400_00BA),
+    Register::new("DMA1CNT_H", 0x0400_00C6),
+    Register::new("DMA2CNT_H", 0x0400_00D2),
+    Register::new("DMA3CNT_H", 0x0400_00DE),
+    Register::new("TM0CNT_H", 0x0400_0102),
+    Register::new("TM2CNT_H", 0x0400_010A),
+    Register::new("TM3CNT_H", 0x0400_010E),
+    Register {
+        name: "IE",
+        address: 0x0400_0200,
+        // The timer 1 interrupt is enabled and disabled by the watchdog.
+        mask: !0b0000_0000_0001_0000,
+    },
+    Register::new("IME", 0x0400_0208),
+];
+
+/// The registers that changed between two sets of values.
+///
+/// This is displayed as a comma-separated list of each changed register's name followed by its
+/// old and new values. Its length is bounded by the number of registers compared.
+pub(crate) struct Diff<'a> {
+    registers: &'a [Register],
+    before: &'a [u16],
+    after: &'a [u16],
+}
+
+impl Diff<'_> {
+    /// Returns whether no registers changed.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.changes().next().is_none()
+    }
+
+    /// Returns the changed registers, along with their old and new values.
+    ///
+    /// Bits that are not compared are included in the values, and are cleared when displayed.
+    fn changes(&self) -> impl Iterator<Item = (&Register, u16, u16)> {
+        self.registers
+            .iter()
+            .zip(self.before.iter().zip(self.after))
+            .filter(|(register, (before, after))| (*before ^ *after) & register.mask != 0)
+            .map(|(register, (&before, &after))| (register, before, after))
+    }
+}
+
+impl Display for Diff<'_> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        for (index, (register, before, after)) in self.changes().enumerate() {
+            if index > 0 {
+                formatter.write_str(", ")?;
+            }
+            write!(
+                formatter,
+                "{} {:04x}→{:04x}",
+                register.name,
+                before & register.mask,
+                after & register.mask
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// The values of [`REGISTERS`] recorded before the current test.
+#[cfg(not(test))]
+static mut BEFORE: [u16; REGISTERS.len()] = [0; REGISTERS.len()];
+/// The values of [`REGISTERS`] read when the current test's diff was last requested.
+#[cfg(not(test))]
+static mut AFTER: [u16; REGISTERS.len()] = [0; REGISTERS.len()];
+
+/// Records the current values of [`REGISTERS`].
+#[cfg(not(test))]
+pub(crate) fn snapshot() {
+    // SAFETY: `BEFORE` is only ever accessed on the main thread.
+    let before = unsafe { &mut *ptr::addr_of_mut!(BEFORE) };
+    for (value, register) in before.iter_mut().zip(&REGISTERS) {
+        *value = register.read();
+    }
+}
+
+/// Returns the registers that changed since the last call to [`snapshot()`].
+#[cfg(not(test))]
+pub(crate) fn diff() -> Diff<'static> {
+    // SAFETY: `BEFORE` and `AFTER` are only ever accessed on the main thread.
+    let (before, after) = unsafe { (&*ptr::addr_of!(BEFORE), &mut *ptr::addr_of_mut!(AFTER)) };
+    for (value, register) in after.iter_mut().zip(&REGISTERS) {
+        *value = register.read();
+    }
+    Diff {
+        registers: &REGISTERS,
+        before,
+        after,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Diff, Register, REGISTERS};
+    use alloc::format;
+
+    const REGISTERS_ABC: [Register; 3] = [
+        Register::new("A", 0),
+        Register::new("B", 2),
+        Register {
+            name: "C",
+            address: 4,
+            mask: 0x00FF,
+        },
+    ];
+
+    #[test]
+    fn unchanged() {
+        let diff = Diff {
+            registers: &REGISTERS_ABC,
+            before: &[1, 2, 3],
+            after: &[1, 2, 3],
+        };
+        assert!(diff.is_empty());
+        assert_eq!(format!("{}", diff), "");
+    }
+
+    #[test]
+    fn single_change() {
+        let diff = Diff {
+            registers: &REGISTERS_ABC,
+            before: &[0x0400, 2, 3],
+            after: &[0x0000, 2, 3],
+        };
+        assert!(!diff.is_empty());
+        assert_eq!(format!("{}", diff), "A 0400→0000");
+    }
+
+    #[test]
+    fn multiple_changes() {
+        let diff = Diff {
+            registers: &REGISTERS_ABC,
+            before: &[0x0400, 2, 0x0001],
+            after: &[0x0000, 2, 0x0041],
+        };
+        assert_eq!(format!("{}", diff), "A 0400→0000, C 0001→0041");
+    }
+
+    #[test]
+    fn masked_bits_ignored() {
+        let diff = Diff {
+            registers: &REGISTERS_ABC,
+            before: &[1, 2, 0x0003],
+            after: &[1, 2, 0xFF03],
+        };
+        assert!(diff.is_empty());
+    }
+
+    #[test]
+    fn masked_bits_not_displayed() {
+        let diff = Diff {
+            registers: &REGISTERS_ABC,
+            before: &[1, 2, 0x1003],
+            after: &[1, 2, 0x0004],
+        };
+        assert_eq!(format!("{}", diff), "C 0003→0004");
+    }
+
+    #[test]
+    fn registers_are_halfword_aligned_io() {
+        for register in &REGISTERS {
+            assert_eq!(register.address % 2, 0, "{}", register.name);
+            assert!((0x0400_0000..0x0400_0400).contains(&register.address));
+        }
+    }
+}
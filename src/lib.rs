@@ -9,19 +9,129 @@
 extern crate alloc;
 
 #[cfg(all(feature = "runner", any(target = "thumbv4t-none-eabi", doc)))]
+mod bios;
+#[cfg(any(all(feature = "runner", any(target = "thumbv4t-none-eabi", doc)), test))]
 pub(crate) mod flavors;
 
+mod assertions;
+#[cfg(any(all(feature = "runner", any(target = "thumbv4t-none-eabi", doc)), test))]
+mod buffer;
+#[cfg(any(all(feature = "runner", any(target = "thumbv4t-none-eabi", doc)), test))]
+mod contains;
 #[cfg(feature = "serde")]
 mod display;
+#[cfg(any(feature = "std", test))]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "std")))]
+pub mod elf;
+mod exit_code;
+#[cfg(any(all(feature = "runner", any(target = "thumbv4t-none-eabi", doc)), test))]
+mod failed_names;
+pub mod golden;
+#[cfg(all(feature = "runner", any(target = "thumbv4t-none-eabi", doc)))]
+mod hardware;
+#[cfg(any(
+    all(feature = "io-diff", any(target = "thumbv4t-none-eabi", doc)),
+    test
+))]
+mod io_diff;
+#[cfg(any(
+    all(feature = "log-capture", any(target = "thumbv4t-none-eabi", doc)),
+    test
+))]
+mod log_capture;
+#[cfg(all(feature = "runner", any(target = "thumbv4t-none-eabi", doc)))]
+mod mgba_log;
+#[cfg(any(all(feature = "runner", any(target = "thumbv4t-none-eabi", doc)), test))]
+mod order;
+#[cfg(all(feature = "runner", any(target = "thumbv4t-none-eabi", doc)))]
+mod output;
+#[cfg(any(feature = "std", test))]
+mod owned;
+#[cfg(all(feature = "runner", any(target = "thumbv4t-none-eabi", doc)))]
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(all(feature = "runner", target = "thumbv4t-none-eabi")))
+)]
+pub mod perf;
+pub mod quick;
+#[cfg(any(
+    all(feature = "runner", any(target = "thumbv4t-none-eabi", doc)),
+    feature = "std",
+    test
+))]
+mod records;
+#[cfg(all(feature = "runner", any(target = "thumbv4t-none-eabi", doc)))]
+mod report_method;
+#[cfg(all(feature = "runner", any(target = "thumbv4t-none-eabi", doc)))]
+mod reporter;
+#[cfg(any(feature = "std", test))]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "std")))]
+pub mod rom;
 #[cfg(all(feature = "runner", any(target = "thumbv4t-none-eabi", doc)))]
 mod runner;
+#[cfg(any(
+    all(feature = "shuffle", any(target = "thumbv4t-none-eabi", doc)),
+    test
+))]
+mod shuffle;
+#[cfg(all(feature = "runner", any(target = "thumbv4t-none-eabi", doc)))]
+mod skip;
+#[cfg(all(feature = "runner", any(target = "thumbv4t-none-eabi", doc)))]
+mod termination;
 mod test_case;
 mod trial;
+#[cfg(any(all(feature = "runner", any(target = "thumbv4t-none-eabi", doc)), test))]
+mod truncate;
+#[cfg(all(feature = "runner", any(target = "thumbv4t-none-eabi", doc)))]
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(all(feature = "runner", target = "thumbv4t-none-eabi")))
+)]
+pub mod vram_guard;
+#[cfg(all(feature = "runner", any(target = "thumbv4t-none-eabi", doc)))]
+mod watchdog;
 
+#[doc(hidden)]
+pub use assertions::Fixed as __Fixed;
+#[cfg(all(feature = "runner", any(target = "thumbv4t-none-eabi", doc)))]
+pub use bios::RegisterRamReset;
+pub use exit_code::ExitCode;
 #[cfg(feature = "gba_test_macros")]
 #[cfg_attr(doc_cfg, doc(cfg(feature = "macros")))]
-pub use gba_test_macros::test;
+pub use gba_test_macros::{assert_harness_configured, harness_main, test};
+#[cfg(all(feature = "log-capture", any(target = "thumbv4t-none-eabi", doc)))]
+#[doc(hidden)]
+pub use log_capture::assert_logged as __assert_logged;
+#[cfg(all(feature = "log-capture", any(target = "thumbv4t-none-eabi", doc)))]
+pub use log_capture::{logs, LogRecord, Logs};
+#[cfg(all(feature = "runner", any(target = "thumbv4t-none-eabi", doc)))]
+#[doc(hidden)]
+pub use output::append as __output;
+#[cfg(feature = "std")]
+pub use owned::{LocationOwned, OutcomeOwned, TrialOwned};
+#[cfg(feature = "std")]
+pub use records::{read_environment, read_seed, read_trials, Truncation};
+#[cfg(all(feature = "runner", any(target = "thumbv4t-none-eabi", doc)))]
+pub use report_method::ReportMethod;
+#[cfg(all(feature = "runner", any(target = "thumbv4t-none-eabi", doc)))]
+pub use reporter::Reporter;
+#[cfg(all(feature = "runner", any(target = "thumbv4t-none-eabi", doc)))]
+pub use runner::{
+    progress, runner, runner_with_reporter, set_default_timeout, set_failure_formatter,
+    set_global_setup, set_list_only, set_message_limit, set_register_ram_reset, set_repeat_count,
+    set_report_method, FailureFormatter, Progress,
+};
+#[cfg(all(feature = "runner", any(target = "thumbv4t-none-eabi", doc)))]
+#[doc(hidden)]
+pub use skip::skip as __skip;
+#[cfg(all(feature = "runner", any(target = "thumbv4t-none-eabi", doc)))]
+#[doc(hidden)]
+pub use termination::expect_err as __expect_err;
+#[cfg(all(feature = "runner", any(target = "thumbv4t-none-eabi", doc)))]
+pub use termination::{Failure, Termination};
+pub use test_case::{
+    Capability, Ignore, Location, ShouldErr, ShouldPanic, Test, TestCase, TestDetails, TestRecord,
+};
+pub use trial::{Environment, Outcome, Summary, Trial};
 #[cfg(all(feature = "runner", any(target = "thumbv4t-none-eabi", doc)))]
-pub use runner::runner;
-pub use test_case::{Ignore, Test, TestCase};
-pub use trial::{Outcome, Trial};
+pub use watchdog::{frames, wait_frames};
@@ -0,0 +1,639 @@
+//! Capturing of log messages for failure reports.
+//!
+//! When the `log-capture` feature is enabled, the runner installs a [`log`] logger that records
+//! the most recent lines logged by the current test into a fixed-size ring buffer, and forwards
+//! each message to mGBA's debug output. When a test fails, the captured lines are appended to its
+//! failure message, giving context that would otherwise only be available in the emulator's log.
+//!
+//! The most recent records are also kept individually, so that tests can inspect them using
+//! [`logs()`] and [`assert_logged!`].
+//!
+//! [`assert_logged!`]: crate::assert_logged!
+
+use core::{fmt, fmt::Write, str};
+#[cfg(not(test))]
+use {
+    crate::mgba_log,
+    core::{
+        fmt::{Debug, Display, Formatter},
+        ptr,
+    },
+    log::{Level, LevelFilter, Log, Metadata, Record},
+};
+
+/// The number of bytes of log lines captured for a single test.
+#[cfg(not(test))]
+const CAPACITY: usize = 1024;
+/// The number of records kept individually for a single test.
+#[cfg(not(test))]
+const RECORD_COUNT: usize = 16;
+/// The number of bytes of a record's target that are kept.
+#[cfg(not(test))]
+const TARGET_LEN: usize = 32;
+/// The number of bytes of a record's message that are kept.
+#[cfg(not(test))]
+const MESSAGE_LEN: usize = 64;
+
+/// A buffer of complete lines with a fixed capacity.
+///
+/// When a new line does not fit, the oldest lines are discarded to make room for it. A single
+/// line longer than the whole buffer is cut off at a character boundary.
+pub(crate) struct Ring<const CAPACITY: usize> {
+    bytes: [u8; CAPACITY],
+    /// The number of bytes written to `bytes`.
+    len: usize,
+    /// The start of the line currently being written.
+    line_start: usize,
+}
+
+impl<const CAPACITY: usize> Ring<CAPACITY> {
+    /// Creates a new, empty buffer.
+    pub(crate) const fn new() -> Self {
+        Self {
+            bytes: [0; CAPACITY],
+            len: 0,
+            line_start: 0,
+        }
+    }
+
+    /// Removes all lines from the buffer.
+    pub(crate) fn clear(&mut self) {
+        self.len = 0;
+        self.line_start = 0;
+    }
+
+    /// Returns the lines in the buffer, oldest first, each followed by a newline.
+    pub(crate) fn as_str(&self) -> &str {
+        // SAFETY: Only complete characters are ever written to the buffer, and only complete lines
+        // are ever removed.
+        unsafe { str::from_utf8_unchecked(&self.bytes[..self.len]) }
+    }
+
+    /// Appends a line of formatted text.
+    pub(crate) fn push(&mut self, args: fmt::Arguments) {
+        if CAPACITY == 0 {
+            return;
+        }
+        self.line_start = self.len;
+        // A full buffer has no room for even the newline of an empty line.
+        if self.len == CAPACITY {
+            self.discard_oldest();
+        }
+        // Writing to the buffer never fails; text that does not fit is discarded.
+        let _ = self.write_fmt(args);
+        // Space for the newline is always kept free by `write_str()` and the check above.
+        self.bytes[self.len] = b'\n';
+        self.len += 1;
+        self.line_start = self.len;
+    }
+
+    /// Returns the number of bytes that can be written to the current line.
+    ///
+    /// One byte is always kept free for the newline ending the line.
+    fn available(&self) -> usize {
+        CAPACITY.saturating_sub(self.len + 1)
+    }
+
+    /// Discards the oldest complete line, returning whether there was one to discard.
+    fn discard_oldest(&mut self) -> bool {
+        let Some(end) = self.bytes[..self.line_start]
+            .iter()
+            .position(|&byte| byte == b'\n')
+        else {
+            return false;
+        };
+        self.bytes.copy_within(end + 1..self.len, 0);
+        self.len -= end + 1;
+        self.line_start -= end + 1;
+        true
+    }
+}
+
+impl<const CAPACITY: usize> Write for Ring<CAPACITY> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        while self.available() < s.len() && self.discard_oldest() {}
+
+        let mut len = s.len().min(self.available());
+        // Only write complete characters, so that the buffer remains valid UTF-8.
+        while !s.is_char_boundary(len) {
+            len -= 1;
+        }
+        self.bytes[self.len..self.len + len].copy_from_slice(&s.as_bytes()[..len]);
+        self.len += len;
+        Ok(())
+    }
+}
+
+/// A string with a fixed capacity.
+///
+/// Text that does not fit is cut off at a character boundary.
+#[derive(Clone, Copy)]
+pub(crate) struct Text<const LEN: usize> {
+    bytes: [u8; LEN],
+    len: usize,
+}
+
+impl<const LEN: usize> Text<LEN> {
+    /// Creates a new, empty string.
+    pub(crate) const fn new() -> Self {
+        Self {
+            bytes: [0; LEN],
+            len: 0,
+        }
+    }
+
+    /// Returns the text written so far.
+    pub(crate) fn as_str(&self) -> &str {
+        // SAFETY: Only complete characters are ever written.
+        unsafe { str::from_utf8_unchecked(&self.bytes[..self.len]) }
+    }
+}
+
+impl<const LEN: usize> Write for Text<LEN> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut len = s.len().min(LEN - self.len);
+        // Only write complete characters, so that the text remains valid UTF-8.
+        while !s.is_char_boundary(len) {
+            len -= 1;
+        }
+        self.bytes[self.len..self.len + len].copy_from_slice(&s.as_bytes()[..len]);
+        self.len += len;
+        Ok(())
+    }
+}
+
+/// A buffer of the most recent values pushed to it.
+///
+/// When the buffer is full, pushing a new value discards the oldest one.
+pub(crate) struct Recent<T, const CAPACITY: usize> {
+    values: [Option<T>; CAPACITY],
+    /// The index the next value will be written to.
+    next: usize,
+    /// The number of values in the buffer.
+    len: usize,
+}
+
+impl<T, const CAPACITY: usize> Recent<T, CAPACITY>
+where
+    T: Copy,
+{
+    /// Creates a new, empty buffer.
+    pub(crate) const fn new() -> Self {
+        Self {
+            values: [None; CAPACITY],
+            next: 0,
+            len: 0,
+        }
+    }
+
+    /// Removes all values from the buffer.
+    pub(crate) fn clear(&mut self) {
+        self.next = 0;
+        self.len = 0;
+    }
+
+    /// Appends a value, discarding the oldest value if the buffer is full.
+    pub(crate) fn push(&mut self, value: T) {
+        if CAPACITY == 0 {
+            return;
+        }
+        self.values[self.next] = Some(value);
+        self.next = (self.next + 1) % CAPACITY;
+        self.len = (self.len + 1).min(CAPACITY);
+    }
+
+    /// Returns the value at `index`, counting from the oldest value in the buffer.
+    pub(crate) fn get(&self, index: usize) -> Option<T> {
+        if index >= self.len {
+            return None;
+        }
+        self.values[(self.next + CAPACITY - self.len + index) % CAPACITY]
+    }
+}
+
+/// A message logged by the current test, as returned by [`logs()`].
+///
+/// Only the first 32 bytes of the target and the first 64 bytes of the message are kept.
+#[cfg(not(test))]
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(all(feature = "log-capture", target = "thumbv4t-none-eabi")))
+)]
+#[derive(Clone, Copy)]
+pub struct LogRecord {
+    level: Level,
+    target: Text<TARGET_LEN>,
+    message: Text<MESSAGE_LEN>,
+}
+
+#[cfg(not(test))]
+impl LogRecord {
+    /// Returns the level the message was logged at.
+    pub fn level(&self) -> Level {
+        self.level
+    }
+
+    /// Returns the target the message was logged to, usually the path of the module logging it.
+    pub fn target(&self) -> &str {
+        self.target.as_str()
+    }
+
+    /// Returns the message.
+    pub fn message(&self) -> &str {
+        self.message.as_str()
+    }
+}
+
+#[cfg(not(test))]
+impl Debug for LogRecord {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        formatter
+            .debug_struct("LogRecord")
+            .field("level", &self.level)
+            .field("target", &self.target())
+            .field("message", &self.message())
+            .finish()
+    }
+}
+
+#[cfg(not(test))]
+impl Display for LogRecord {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "{} {}: {}",
+            self.level,
+            self.target(),
+            self.message()
+        )
+    }
+}
+
+/// Records captured for the current test.
+#[cfg(not(test))]
+static mut RECORDS: Recent<LogRecord, RECORD_COUNT> = Recent::new();
+
+/// An iterator over the records logged by the current test, returned by [`logs()`].
+#[cfg(not(test))]
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(all(feature = "log-capture", target = "thumbv4t-none-eabi")))
+)]
+#[derive(Debug)]
+pub struct Logs {
+    index: usize,
+}
+
+#[cfg(not(test))]
+impl Iterator for Logs {
+    type Item = LogRecord;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // SAFETY: `RECORDS` is only ever accessed on the main thread.
+        let record = unsafe { (*ptr::addr_of!(RECORDS)).get(self.index) }?;
+        self.index += 1;
+        Some(record)
+    }
+}
+
+/// Returns the records logged by the current test, oldest first.
+///
+/// Only the 16 most recent records are kept. Records are cleared by the runner before each test
+/// is run.
+///
+/// ```no_run
+/// use log::Level;
+///
+/// log::warn!("low battery");
+/// let warnings = gba_test::logs()
+///     .filter(|record| record.level() == Level::Warn)
+///     .count();
+/// assert_eq!(warnings, 1);
+/// ```
+#[cfg(not(test))]
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(all(feature = "log-capture", target = "thumbv4t-none-eabi")))
+)]
+pub fn logs() -> Logs {
+    Logs { index: 0 }
+}
+
+/// Lists the captured records in a failure message.
+#[cfg(not(test))]
+struct Captured;
+
+#[cfg(not(test))]
+impl Display for Captured {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        let mut records = logs().peekable();
+        if records.peek().is_none() {
+            return formatter.write_str("no records were captured");
+        }
+        formatter.write_str("captured records:")?;
+        for record in records {
+            write!(formatter, "\n{}", record)?;
+        }
+        Ok(())
+    }
+}
+
+/// Panics if no record captured for the current test was logged at `level` with a message
+/// containing `substring`.
+///
+/// This is an implementation detail of [`assert_logged!`].
+///
+/// [`assert_logged!`]: crate::assert_logged!
+#[cfg(not(test))]
+#[doc(hidden)]
+#[track_caller]
+pub fn assert_logged(level: Level, substring: &str) {
+    if !logs().any(|record| record.level() == level && record.message().contains(substring)) {
+        panic!(
+            "no {} record containing {:?} was logged; {}",
+            level, substring, Captured
+        );
+    }
+}
+
+/// Asserts that the current test logged a message at the given level containing the given
+/// substring.
+///
+/// Only the records returned by [`logs()`] are searched. On failure, the captured records are
+/// listed in the panic message.
+///
+/// ```no_run
+/// use gba_test::assert_logged;
+/// use log::Level;
+///
+/// log::warn!("low battery");
+/// assert_logged!(Level::Warn, "battery");
+/// ```
+#[cfg(not(test))]
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(all(feature = "log-capture", target = "thumbv4t-none-eabi")))
+)]
+#[macro_export]
+macro_rules! assert_logged {
+    ($level:expr, $substring:expr $(,)?) => {
+        $crate::__assert_logged($level, $substring)
+    };
+}
+
+/// Log lines captured for the current test.
+#[cfg(not(test))]
+static mut LINES: Ring<CAPACITY> = Ring::new();
+
+/// Records log messages into [`LINES`].
+#[cfg(not(test))]
+struct Capture;
+
+#[cfg(not(test))]
+impl Log for Capture {
+    fn enabled(&self, _metadata: &Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record) {
+        // SAFETY: `LINES` is only ever accessed on the main thread.
+        unsafe {
+            (*ptr::addr_of_mut!(LINES)).push(format_args!("{} {}", record.level(), record.args()));
+            let mut captured = LogRecord {
+                level: record.level(),
+                target: Text::new(),
+                message: Text::new(),
+            };
+            // Writing never fails; text that does not fit is discarded.
+            let _ = captured.target.write_str(record.target());
+            let _ = captured.message.write_fmt(*record.args());
+            (*ptr::addr_of_mut!(RECORDS)).push(captured);
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// Forwards log messages to mGBA's debug output.
+#[cfg(not(test))]
+struct Mgba;
+
+#[cfg(not(test))]
+impl Log for Mgba {
+    fn enabled(&self, _metadata: &Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record) {
+        mgba_log::log(record.level().into(), *record.args());
+    }
+
+    fn flush(&self) {}
+}
+
+/// Passes each log message on to two loggers.
+#[cfg(not(test))]
+struct Fanout<A, B>(A, B);
+
+#[cfg(not(test))]
+impl<A, B> Log for Fanout<A, B>
+where
+    A: Log,
+    B: Log,
+{
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.0.enabled(metadata) || self.1.enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if self.0.enabled(record.metadata()) {
+            self.0.log(record);
+        }
+        if self.1.enabled(record.metadata()) {
+            self.1.log(record);
+        }
+    }
+
+    fn flush(&self) {
+        self.0.flush();
+        self.1.flush();
+    }
+}
+
+/// The logger installed by [`install()`].
+#[cfg(not(test))]
+static LOGGER: Fanout<Capture, Mgba> = Fanout(Capture, Mgba);
+
+/// Installs the capturing logger, enabling all log levels.
+///
+/// If another logger has already been installed, it is left in place and nothing is captured.
+#[cfg(not(test))]
+pub(crate) fn install() {
+    // SAFETY: The logger is only ever installed on the main thread, before any tests are run.
+    unsafe {
+        if log::set_logger_racy(&LOGGER).is_ok() {
+            log::set_max_level_racy(LevelFilter::Trace);
+        }
+    }
+}
+
+/// Discards all captured log lines and records.
+#[cfg(not(test))]
+pub(crate) fn clear() {
+    // SAFETY: `LINES` and `RECORDS` are only ever accessed on the main thread.
+    unsafe {
+        (*ptr::addr_of_mut!(LINES)).clear();
+        (*ptr::addr_of_mut!(RECORDS)).clear();
+    }
+}
+
+/// Returns the log lines captured since the last call to [`clear()`].
+#[cfg(not(test))]
+pub(crate) fn captured() -> &'static str {
+    // SAFETY: `LINES` is only ever accessed on the main thread.
+    unsafe { (*ptr::addr_of!(LINES)).as_str() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Recent, Ring, Text};
+    use core::fmt::Write;
+
+    #[test]
+    fn empty() {
+        assert_eq!(Ring::<16>::new().as_str(), "");
+    }
+
+    #[test]
+    fn push() {
+        let mut ring = Ring::<16>::new();
+        ring.push(format_args!("foo {}", 1));
+        ring.push(format_args!("bar"));
+        assert_eq!(ring.as_str(), "foo 1\nbar\n");
+    }
+
+    #[test]
+    fn clear() {
+        let mut ring = Ring::<16>::new();
+        ring.push(format_args!("foo"));
+        ring.clear();
+        ring.push(format_args!("bar"));
+        assert_eq!(ring.as_str(), "bar\n");
+    }
+
+    #[test]
+    fn discards_oldest_lines() {
+        let mut ring = Ring::<13>::new();
+        ring.push(format_args!("foo"));
+        ring.push(format_args!("bar"));
+        ring.push(format_args!("baz"));
+        ring.push(format_args!("quux"));
+        assert_eq!(ring.as_str(), "bar\nbaz\nquux\n");
+    }
+
+    #[test]
+    fn empty_line_when_full() {
+        let mut ring = Ring::<8>::new();
+        ring.push(format_args!("foo"));
+        ring.push(format_args!("bar"));
+        ring.push(format_args!(""));
+        assert_eq!(ring.as_str(), "bar\n\n");
+    }
+
+    #[test]
+    fn discards_only_as_needed() {
+        let mut ring = Ring::<12>::new();
+        ring.push(format_args!("foo"));
+        ring.push(format_args!("bar"));
+        ring.push(format_args!("{}{}", "b", "az"));
+        assert_eq!(ring.as_str(), "foo\nbar\nbaz\n");
+    }
+
+    #[test]
+    fn long_line_truncated() {
+        let mut ring = Ring::<8>::new();
+        ring.push(format_args!("foo"));
+        ring.push(format_args!("foobarbaz"));
+        assert_eq!(ring.as_str(), "foobarb\n");
+    }
+
+    #[test]
+    fn long_line_truncated_char_boundary() {
+        let mut ring = Ring::<5>::new();
+        ring.push(format_args!("abc\u{e9}\u{e9}"));
+        assert_eq!(ring.as_str(), "abc\n");
+    }
+
+    #[test]
+    fn zero_capacity() {
+        let mut ring = Ring::<0>::new();
+        ring.push(format_args!("foo"));
+        assert_eq!(ring.as_str(), "");
+    }
+
+    #[test]
+    fn text() {
+        let mut text = Text::<8>::new();
+        write!(text, "foo {}", 1).unwrap();
+        assert_eq!(text.as_str(), "foo 1");
+    }
+
+    #[test]
+    fn text_truncated() {
+        let mut text = Text::<8>::new();
+        write!(text, "foo{}", 123456).unwrap();
+        assert_eq!(text.as_str(), "foo12345");
+    }
+
+    #[test]
+    fn text_truncated_char_boundary() {
+        let mut text = Text::<4>::new();
+        text.write_str("ab\u{e9}\u{e9}").unwrap();
+        assert_eq!(text.as_str(), "ab\u{e9}");
+    }
+
+    #[test]
+    fn recent_empty() {
+        assert_eq!(Recent::<u8, 4>::new().get(0), None);
+    }
+
+    #[test]
+    fn recent_push() {
+        let mut recent = Recent::<u8, 4>::new();
+        recent.push(1);
+        recent.push(2);
+        assert_eq!(recent.get(0), Some(1));
+        assert_eq!(recent.get(1), Some(2));
+        assert_eq!(recent.get(2), None);
+    }
+
+    #[test]
+    fn recent_discards_oldest() {
+        let mut recent = Recent::<u8, 3>::new();
+        for value in 1..=5 {
+            recent.push(value);
+        }
+        assert_eq!(recent.get(0), Some(3));
+        assert_eq!(recent.get(1), Some(4));
+        assert_eq!(recent.get(2), Some(5));
+        assert_eq!(recent.get(3), None);
+    }
+
+    #[test]
+    fn recent_clear() {
+        let mut recent = Recent::<u8, 3>::new();
+        recent.push(1);
+        recent.push(2);
+        recent.clear();
+        recent.push(3);
+        assert_eq!(recent.get(0), Some(3));
+        assert_eq!(recent.get(1), None);
+    }
+
+    #[test]
+    fn recent_zero_capacity() {
+        let mut recent = Recent::<u8, 0>::new();
+        recent.push(1);
+        assert_eq!(recent.get(0), None);
+    }
+}
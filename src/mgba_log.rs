@@ -0,0 +1,103 @@
+//! Logging through mGBA's debug output.
+//!
+//! mGBA exposes a set of registers that allow a ROM to write messages to the emulator's log. On
+//! other emulators and on hardware, the registers are not present and messages are discarded.
+
+use core::{
+    fmt,
+    fmt::{Arguments, Write},
+};
+use voladdress::{Safe, VolAddress, VolBlock};
+
+/// Enables debug output when written `0xC0DE`, after which it reads as `0x1DEA`.
+const DEBUG_ENABLE: VolAddress<u16, Safe, Safe> = unsafe { VolAddress::new(0x04FF_F780) };
+/// Controls the level of, and sends, the message in `DEBUG_STRING`.
+const DEBUG_FLAGS: VolAddress<u16, Safe, Safe> = unsafe { VolAddress::new(0x04FF_F700) };
+/// The buffer the message is written to.
+const DEBUG_STRING: VolBlock<u8, Safe, Safe, 256> = unsafe { VolBlock::new(0x04FF_F600) };
+
+/// The value written to `DEBUG_ENABLE` to enable debug output.
+const ENABLE_REQUEST: u16 = 0xC0DE;
+/// The value read from `DEBUG_ENABLE` when debug output is enabled.
+const ENABLE_RESPONSE: u16 = 0x1DEA;
+/// Flag for sending the message in `DEBUG_STRING`.
+const SEND: u16 = 0b0000_0001_0000_0000;
+
+/// The level of a message written to mGBA's log.
+///
+/// The runner itself only writes informational messages and warnings. The other levels are used
+/// when forwarding messages from the `log` crate.
+#[cfg_attr(not(feature = "log-capture"), allow(dead_code))]
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum Level {
+    Error = 1,
+    Warn = 2,
+    Info = 3,
+    Debug = 4,
+}
+
+#[cfg(feature = "log-capture")]
+impl From<log::Level> for Level {
+    fn from(level: log::Level) -> Self {
+        match level {
+            log::Level::Error => Self::Error,
+            log::Level::Warn => Self::Warn,
+            log::Level::Info => Self::Info,
+            // mGBA has no level below debug.
+            log::Level::Debug | log::Level::Trace => Self::Debug,
+        }
+    }
+}
+
+/// Writes into `DEBUG_STRING`, discarding anything past its end.
+struct DebugString {
+    len: usize,
+}
+
+impl Write for DebugString {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for byte in s.bytes() {
+            let Some(address) = DEBUG_STRING.get(self.len) else {
+                break;
+            };
+            address.write(byte);
+            self.len += 1;
+        }
+        Ok(())
+    }
+}
+
+/// Enables debug output, returning whether mGBA's debug registers are present.
+///
+/// This is safe to call on hardware and other emulators. The registers are in unused IO memory,
+/// where writes are ignored and reads return open bus rather than faulting, as the Game Boy
+/// Advance raises no aborts for unmapped addresses. Open bus reads return the most recently
+/// prefetched instruction, so the registers could only be misdetected if that instruction happened
+/// to encode the expected response.
+pub(crate) fn enable() -> bool {
+    DEBUG_ENABLE.write(ENABLE_REQUEST);
+    DEBUG_ENABLE.read() == ENABLE_RESPONSE
+}
+
+/// Writes a single informational line to mGBA's log.
+///
+/// Messages longer than 256 bytes are cut off.
+pub(crate) fn info(message: Arguments) {
+    log(Level::Info, message);
+}
+
+/// Writes a single line to mGBA's log at the given level.
+///
+/// Messages longer than 256 bytes are cut off.
+pub(crate) fn log(level: Level, message: Arguments) {
+    if !enable() {
+        return;
+    }
+    let mut debug_string = DebugString { len: 0 };
+    // Writing to `DebugString` never fails.
+    let _ = debug_string.write_fmt(message);
+    if let Some(address) = DEBUG_STRING.get(debug_string.len) {
+        address.write(0);
+    }
+    DEBUG_FLAGS.write(level as u16 | SEND);
+}
@@ -0,0 +1,183 @@
+//! The order tests are run in.
+//!
+//! The order the compiler collects tests in is unspecified, and has changed between toolchain
+//! versions. So that results can be compared between builds, the tests are sorted by module path
+//! and then by name before any are run. The cases of a parameterized test, whose names differ only
+//! after a `[`, are not sorted by name, and neither are tests with the same module path and name;
+//! these keep the order they were collected in.
+//!
+//! The sorted order is stored as a list of `u16` indices in the `.gba_test_ewram` section, which is
+//! sorted in place without allocating.
+
+use crate::TestCase;
+use core::cmp::Ordering;
+
+/// The largest number of tests that can be sorted.
+///
+/// If more tests than this are run, they are run in the order they were collected in.
+pub(crate) const MAX_TESTS: usize = 8192;
+
+/// Test indices, word-aligned so that the memory around them can be cleared a word at a time.
+#[repr(C, align(4))]
+struct Indices([u16; MAX_TESTS]);
+
+/// The indices of the tests, in sorted order.
+///
+/// This is not loaded, so only the first `LEN` indices are valid after [`sort()`] has been called.
+#[cfg(not(test))]
+#[link_section = ".gba_test_ewram"]
+static mut SORTED: Indices = Indices([0; MAX_TESTS]);
+/// The number of valid indices in `SORTED`.
+///
+/// This is `0` if the tests could not be sorted.
+#[cfg(not(test))]
+static mut LEN: usize = 0;
+
+/// Returns the part of `name` before the case arguments, if it has any.
+fn base_name(name: &str) -> &str {
+    name.split('[').next().unwrap_or(name)
+}
+
+/// Compares two tests by module path, and then by name.
+fn compare(a: &dyn TestCase, b: &dyn TestCase) -> Ordering {
+    a.module_path()
+        .cmp(b.module_path())
+        .then_with(|| base_name(a.name()).cmp(base_name(b.name())))
+}
+
+/// Fills `indices` with the indices of `tests` in sorted order.
+///
+/// `indices` must be the same length as `tests`.
+fn sort_indices(tests: &[&dyn TestCase], indices: &mut [u16]) {
+    for (index, slot) in indices.iter_mut().enumerate() {
+        *slot = index as u16;
+    }
+    indices
+        .sort_unstable_by(|&a, &b| compare(tests[a as usize], tests[b as usize]).then(a.cmp(&b)));
+}
+
+/// Sorts `tests`, returning whether they could be sorted.
+///
+/// If there are more than [`MAX_TESTS`] tests, they are left in the order they were collected in.
+#[cfg(not(test))]
+pub(crate) fn sort(tests: &[&dyn TestCase]) -> bool {
+    // SAFETY: `SORTED` and `LEN` are only ever accessed on the main thread.
+    unsafe {
+        if tests.len() > MAX_TESTS {
+            LEN = 0;
+            return false;
+        }
+        let sorted = &mut (*core::ptr::addr_of_mut!(SORTED)).0;
+        sort_indices(tests, &mut sorted[..tests.len()]);
+        LEN = tests.len();
+    }
+    true
+}
+
+/// Returns the index of the test at `position` in the sorted order.
+#[cfg(not(test))]
+pub(crate) fn index(position: usize) -> usize {
+    // SAFETY: `SORTED` and `LEN` are only ever accessed on the main thread.
+    unsafe {
+        if position < LEN {
+            SORTED.0[position] as usize
+        } else {
+            position
+        }
+    }
+}
+
+/// Returns the addresses of the memory holding the sorted order.
+#[cfg(not(test))]
+pub(crate) fn region() -> core::ops::Range<usize> {
+    let start = core::ptr::addr_of!(SORTED) as usize;
+    start..start + core::mem::size_of::<Indices>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::sort_indices;
+    use crate::{Ignore, TestCase};
+
+    struct Named {
+        module_path: &'static str,
+        name: &'static str,
+    }
+
+    impl TestCase for Named {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn module_path(&self) -> &str {
+            self.module_path
+        }
+
+        fn run(&self) {}
+
+        fn ignore(&self) -> Ignore {
+            Ignore::No
+        }
+    }
+
+    const fn named(module_path: &'static str, name: &'static str) -> Named {
+        Named { module_path, name }
+    }
+
+    fn sorted(tests: &[&dyn TestCase]) -> [u16; 5] {
+        let mut indices = [0; 5];
+        sort_indices(tests, &mut indices[..tests.len()]);
+        indices
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!(sorted(&[]), [0; 5]);
+    }
+
+    #[test]
+    fn by_name() {
+        let (a, b, c) = (named("foo", "c"), named("foo", "a"), named("foo", "b"));
+        assert_eq!(sorted(&[&a, &b, &c]), [1, 2, 0, 0, 0]);
+    }
+
+    #[test]
+    fn by_module_path_first() {
+        let (a, b, c) = (
+            named("foo::b", "a"),
+            named("foo::a", "z"),
+            named("foo", "m"),
+        );
+        assert_eq!(sorted(&[&a, &b, &c]), [2, 1, 0, 0, 0]);
+    }
+
+    #[test]
+    fn module_path_compared_whole() {
+        // Sorting by the full path would put `foo0::a` before `foo::b`.
+        let (a, b) = (named("foo0", "a"), named("foo", "b"));
+        assert_eq!(sorted(&[&a, &b]), [1, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn cases_keep_collection_order() {
+        let (a, b, c, d) = (
+            named("foo", "b[2]"),
+            named("foo", "b[10]"),
+            named("foo", "b[1]"),
+            named("foo", "a"),
+        );
+        assert_eq!(sorted(&[&a, &b, &c, &d]), [3, 0, 1, 2, 0]);
+    }
+
+    #[test]
+    fn duplicates_keep_collection_order() {
+        let (a, b, c, d, e) = (
+            named("foo", "x"),
+            named("foo", "a"),
+            named("foo", "x"),
+            named("foo", "a"),
+            named("foo", "x"),
+        );
+        assert_eq!(sorted(&[&a, &b, &c, &d, &e]), [1, 3, 0, 2, 4]);
+    }
+}
@@ -0,0 +1,71 @@
+//! Capturing of output written by tests.
+//!
+//! Output written using [`output!`] is stored in a fixed-size buffer that is cleared before each
+//! test is run. When the test completes, the captured output is attached to its [`Trial`].
+//!
+//! [`output!`]: crate::output!
+//! [`Trial`]: crate::Trial
+
+use crate::buffer::Buffer;
+use core::{fmt, fmt::Write, ptr};
+
+/// The maximum number of bytes of output captured for a single test.
+///
+/// Output beyond this limit is discarded.
+const CAPACITY: usize = 512;
+
+/// Output captured for the current test.
+static mut OUTPUT: Buffer<CAPACITY> = Buffer::new();
+
+/// Discards all captured output.
+pub(crate) fn clear() {
+    // SAFETY: `OUTPUT` is only ever accessed on the main thread.
+    unsafe {
+        (*ptr::addr_of_mut!(OUTPUT)).clear();
+    }
+}
+
+/// Returns the output captured since the last call to [`clear()`].
+pub(crate) fn captured() -> &'static str {
+    // SAFETY: `OUTPUT` is only ever accessed on the main thread.
+    unsafe { (*ptr::addr_of!(OUTPUT)).as_str() }
+}
+
+/// Appends a line of formatted output to the captured output.
+///
+/// This is an implementation detail of [`output!`].
+///
+/// [`output!`]: crate::output!
+#[doc(hidden)]
+pub fn append(args: fmt::Arguments) {
+    // SAFETY: `OUTPUT` is only ever accessed on the main thread.
+    let output = unsafe { &mut *ptr::addr_of_mut!(OUTPUT) };
+    // Writing to the buffer never fails; output that does not fit is discarded.
+    let _ = output.write_fmt(args);
+    let _ = output.write_str("\n");
+}
+
+/// Writes a line of output, attaching it to the result of the current test.
+///
+/// This accepts the same arguments as [`format!`]. Each invocation is followed by a newline. The
+/// output is reported along with the test's outcome, whether the test passes or fails. Up to 512
+/// bytes are captured for each test; anything beyond that is discarded.
+///
+/// ```no_run
+/// use gba_test::output;
+///
+/// let frames = 60;
+/// output!("rendered {} frames", frames);
+/// ```
+///
+/// [`format!`]: https://doc.rust-lang.org/alloc/macro.format.html
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(all(feature = "runner", target = "thumbv4t-none-eabi")))
+)]
+#[macro_export]
+macro_rules! output {
+    ($($arg:tt)*) => {
+        $crate::__output(::core::format_args!($($arg)*))
+    };
+}
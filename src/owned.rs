@@ -0,0 +1,831 @@
+//! Owned versions of the test result types, for use on the host.
+//!
+//! [`Trial`], [`Outcome`], and [`Location`] borrow their strings, which lets the runner report
+//! results without allocating and lets results be read directly from the bytes of a save file.
+//! Host-side tools often need to keep results around longer than those bytes, or to read them from
+//! formats like JSON where strings can't always be borrowed. [`TrialOwned`], [`OutcomeOwned`], and
+//! [`LocationOwned`] own their strings instead, and are serialized identically to their borrowed
+//! counterparts, so either can be used to read what the other wrote.
+//!
+//! ## JSON
+//! When serialized with `serde_json`, a trial has the following schema:
+//!
+//! ```json
+//! {
+//!     "name": "tests::it_works",
+//!     "outcome": "Passed",
+//!     "output": "",
+//!     "location": {"file": "src/lib.rs", "line": 12},
+//!     "suite": null
+//! }
+//! ```
+//!
+//! - `name` and `output` are strings.
+//! - `outcome` is either the string `"Passed"`, the string `"Ignored"`, or an object of the form
+//!   `{"Failed": {"message": "..."}}`.
+//! - `location` is either `null` or an object with a string `file` and an integer `line`.
+//! - `suite` is either `null` or a string.
+//!
+//! When deserializing, `location` and `suite` may be omitted, in which case they are `null`.
+//!
+//! [`Trial`]: crate::Trial
+//! [`Outcome`]: crate::Outcome
+//! [`Location`]: crate::Location
+
+use crate::{Location, Outcome, Trial};
+use alloc::string::{String, ToString};
+use core::{fmt, fmt::Display, str};
+use serde::{
+    de,
+    de::{
+        Deserialize, Deserializer, EnumAccess, Error as _, MapAccess, SeqAccess, Unexpected,
+        VariantAccess, Visitor,
+    },
+    ser::{Serialize, Serializer},
+};
+
+/// An owned [`Outcome`].
+#[cfg_attr(doc_cfg, doc(cfg(feature = "std")))]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum OutcomeOwned {
+    /// The test passed.
+    Passed,
+    /// The test failed.
+    Failed { message: String },
+    /// The test was excluded from the test run.
+    Ignored,
+}
+
+impl OutcomeOwned {
+    /// Returns an [`Outcome`] borrowing from this one.
+    pub fn as_borrowed(&self) -> Outcome<&str> {
+        match self {
+            Self::Passed => Outcome::Passed,
+            Self::Failed { message } => Outcome::Failed { message },
+            Self::Ignored => Outcome::Ignored,
+        }
+    }
+}
+
+impl<FailedMessage> From<&Outcome<FailedMessage>> for OutcomeOwned
+where
+    FailedMessage: Display,
+{
+    fn from(outcome: &Outcome<FailedMessage>) -> Self {
+        match outcome {
+            Outcome::Passed => Self::Passed,
+            Outcome::Failed { message } => Self::Failed {
+                message: message.to_string(),
+            },
+            Outcome::Ignored => Self::Ignored,
+        }
+    }
+}
+
+impl PartialEq<Outcome<&str>> for OutcomeOwned {
+    fn eq(&self, other: &Outcome<&str>) -> bool {
+        self.as_borrowed() == *other
+    }
+}
+
+impl Serialize for OutcomeOwned {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.as_borrowed().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for OutcomeOwned {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        enum Variant {
+            Passed,
+            Failed,
+            Ignored,
+        }
+
+        impl<'de> Deserialize<'de> for Variant {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                struct VariantVisitor;
+
+                impl<'de> Visitor<'de> for VariantVisitor {
+                    type Value = Variant;
+
+                    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                        formatter.write_str("`Passed`, `Failed`, or `Ignored`")
+                    }
+
+                    fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
+                    where
+                        E: de::Error,
+                    {
+                        match value {
+                            0 => Ok(Variant::Passed),
+                            1 => Ok(Variant::Failed),
+                            2 => Ok(Variant::Ignored),
+                            _ => Err(E::invalid_value(Unexpected::Unsigned(value), &self)),
+                        }
+                    }
+
+                    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+                    where
+                        E: de::Error,
+                    {
+                        match value {
+                            "Passed" => Ok(Variant::Passed),
+                            "Failed" => Ok(Variant::Failed),
+                            "Ignored" => Ok(Variant::Ignored),
+                            _ => Err(E::unknown_variant(value, VARIANTS)),
+                        }
+                    }
+
+                    fn visit_bytes<E>(self, value: &[u8]) -> Result<Self::Value, E>
+                    where
+                        E: de::Error,
+                    {
+                        match value {
+                            b"Passed" => Ok(Variant::Passed),
+                            b"Failed" => Ok(Variant::Failed),
+                            b"Ignored" => Ok(Variant::Ignored),
+                            _ => {
+                                if let Ok(value) = str::from_utf8(value) {
+                                    Err(E::unknown_variant(value, VARIANTS))
+                                } else {
+                                    Err(E::invalid_value(Unexpected::Bytes(value), &self))
+                                }
+                            }
+                        }
+                    }
+                }
+
+                deserializer.deserialize_identifier(VariantVisitor)
+            }
+        }
+
+        enum FailedField {
+            Message,
+        }
+
+        const FAILED_FIELDS: &[&str] = &["message"];
+
+        impl<'de> Deserialize<'de> for FailedField {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                struct FailedFieldVisitor;
+
+                impl<'de> Visitor<'de> for FailedFieldVisitor {
+                    type Value = FailedField;
+
+                    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                        formatter.write_str("`message`")
+                    }
+
+                    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+                    where
+                        E: de::Error,
+                    {
+                        match value {
+                            "message" => Ok(FailedField::Message),
+                            _ => Err(E::unknown_field(value, FAILED_FIELDS)),
+                        }
+                    }
+                }
+
+                deserializer.deserialize_identifier(FailedFieldVisitor)
+            }
+        }
+
+        struct FailedVisitor;
+
+        impl<'de> Visitor<'de> for FailedVisitor {
+            type Value = OutcomeOwned;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("variant Outcome::Failed")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                Ok(OutcomeOwned::Failed {
+                    message: seq
+                        .next_element()?
+                        .ok_or_else(|| de::Error::invalid_length(0, &self))?,
+                })
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let mut message = None;
+
+                while let Some(key) = map.next_key()? {
+                    match key {
+                        FailedField::Message => {
+                            if message.is_some() {
+                                return Err(A::Error::duplicate_field("message"));
+                            }
+                            message = Some(map.next_value()?);
+                        }
+                    }
+                }
+
+                Ok(OutcomeOwned::Failed {
+                    message: message.ok_or_else(|| A::Error::missing_field("message"))?,
+                })
+            }
+        }
+
+        struct OutcomeVisitor;
+
+        impl<'de> Visitor<'de> for OutcomeVisitor {
+            type Value = OutcomeOwned;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("enum Outcome")
+            }
+
+            fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
+            where
+                A: EnumAccess<'de>,
+            {
+                match data.variant()? {
+                    (Variant::Passed, variant) => {
+                        variant.unit_variant().and(Ok(OutcomeOwned::Passed))
+                    }
+                    (Variant::Failed, variant) => {
+                        variant.struct_variant(FAILED_FIELDS, FailedVisitor)
+                    }
+                    (Variant::Ignored, variant) => {
+                        variant.unit_variant().and(Ok(OutcomeOwned::Ignored))
+                    }
+                }
+            }
+        }
+
+        const VARIANTS: &[&str] = &["Passed", "Failed", "Ignored"];
+
+        deserializer.deserialize_enum("Outcome", VARIANTS, OutcomeVisitor)
+    }
+}
+
+/// An owned [`Location`].
+#[cfg_attr(doc_cfg, doc(cfg(feature = "std")))]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LocationOwned {
+    /// The path of the file, as given by [`file!()`].
+    pub file: String,
+    /// The line number, starting at `1`.
+    pub line: u32,
+}
+
+impl LocationOwned {
+    /// Returns a [`Location`] borrowing from this one.
+    pub fn as_borrowed(&self) -> Location<'_> {
+        Location {
+            file: &self.file,
+            line: self.line,
+        }
+    }
+}
+
+impl From<Location<'_>> for LocationOwned {
+    fn from(location: Location) -> Self {
+        Self {
+            file: location.file.to_string(),
+            line: location.line,
+        }
+    }
+}
+
+impl Display for LocationOwned {
+    /// Writes the location in the form `file:line`.
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        self.as_borrowed().fmt(formatter)
+    }
+}
+
+impl Serialize for LocationOwned {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.as_borrowed().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for LocationOwned {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        enum Field {
+            File,
+            Line,
+        }
+
+        impl<'de> Deserialize<'de> for Field {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                struct FieldVisitor;
+
+                impl<'de> Visitor<'de> for FieldVisitor {
+                    type Value = Field;
+
+                    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                        formatter.write_str("`file` or `line`")
+                    }
+
+                    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+                    where
+                        E: de::Error,
+                    {
+                        match v {
+                            "file" => Ok(Field::File),
+                            "line" => Ok(Field::Line),
+                            _ => Err(E::unknown_field(v, FIELDS)),
+                        }
+                    }
+                }
+
+                deserializer.deserialize_identifier(FieldVisitor)
+            }
+        }
+
+        struct LocationVisitor;
+
+        impl<'de> Visitor<'de> for LocationVisitor {
+            type Value = LocationOwned;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("struct Location")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let file = seq
+                    .next_element()?
+                    .ok_or(de::Error::missing_field("file"))?;
+                let line = seq
+                    .next_element()?
+                    .ok_or(de::Error::missing_field("line"))?;
+
+                Ok(LocationOwned { file, line })
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let mut file = None;
+                let mut line = None;
+
+                while let Some(key) = map.next_key()? {
+                    match key {
+                        Field::File => {
+                            if file.is_some() {
+                                return Err(A::Error::duplicate_field("file"));
+                            }
+                            file = Some(map.next_value()?);
+                        }
+                        Field::Line => {
+                            if line.is_some() {
+                                return Err(A::Error::duplicate_field("line"));
+                            }
+                            line = Some(map.next_value()?);
+                        }
+                    }
+                }
+
+                Ok(LocationOwned {
+                    file: file.ok_or_else(|| A::Error::missing_field("file"))?,
+                    line: line.ok_or_else(|| A::Error::missing_field("line"))?,
+                })
+            }
+        }
+
+        const FIELDS: &[&str] = &["file", "line"];
+
+        deserializer.deserialize_struct("Location", FIELDS, LocationVisitor)
+    }
+}
+
+/// An owned [`Trial`].
+///
+/// This can be compared directly against a borrowed [`Trial`].
+#[cfg_attr(doc_cfg, doc(cfg(feature = "std")))]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TrialOwned {
+    /// The name of the test.
+    pub name: String,
+    /// The test's outcome.
+    pub outcome: OutcomeOwned,
+    /// Output captured while the test was running.
+    pub output: String,
+    /// Where the test is defined, if known.
+    pub location: Option<LocationOwned>,
+    /// The suite the test belongs to, if any.
+    pub suite: Option<String>,
+}
+
+impl TrialOwned {
+    /// Returns a [`Trial`] borrowing from this one.
+    pub fn as_borrowed(&self) -> Trial<'_, &str> {
+        Trial {
+            name: &self.name,
+            outcome: self.outcome.as_borrowed(),
+            output: &self.output,
+            location: self.location.as_ref().map(LocationOwned::as_borrowed),
+            suite: self.suite.as_deref(),
+        }
+    }
+}
+
+impl<FailedMessage> From<&Trial<'_, FailedMessage>> for TrialOwned
+where
+    FailedMessage: Display,
+{
+    fn from(trial: &Trial<FailedMessage>) -> Self {
+        Self {
+            name: trial.name.to_string(),
+            outcome: OutcomeOwned::from(&trial.outcome),
+            output: trial.output.to_string(),
+            location: trial.location.map(LocationOwned::from),
+            suite: trial.suite.map(ToString::to_string),
+        }
+    }
+}
+
+impl<FailedMessage> From<Trial<'_, FailedMessage>> for TrialOwned
+where
+    FailedMessage: Display,
+{
+    fn from(trial: Trial<FailedMessage>) -> Self {
+        Self::from(&trial)
+    }
+}
+
+impl PartialEq<Trial<'_, &str>> for TrialOwned {
+    fn eq(&self, other: &Trial<&str>) -> bool {
+        self.as_borrowed() == *other
+    }
+}
+
+impl Serialize for TrialOwned {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.as_borrowed().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for TrialOwned {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        enum Field {
+            Name,
+            Outcome,
+            Output,
+            Location,
+            Suite,
+        }
+
+        impl<'de> Deserialize<'de> for Field {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                struct FieldVisitor;
+
+                impl<'de> Visitor<'de> for FieldVisitor {
+                    type Value = Field;
+
+                    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                        formatter.write_str("`name`, `outcome`, `output`, `location`, or `suite`")
+                    }
+
+                    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+                    where
+                        E: de::Error,
+                    {
+                        match v {
+                            "name" => Ok(Field::Name),
+                            "outcome" => Ok(Field::Outcome),
+                            "output" => Ok(Field::Output),
+                            "location" => Ok(Field::Location),
+                            "suite" => Ok(Field::Suite),
+                            _ => Err(E::unknown_field(v, FIELDS)),
+                        }
+                    }
+                }
+
+                deserializer.deserialize_identifier(FieldVisitor)
+            }
+        }
+
+        struct TrialVisitor;
+
+        impl<'de> Visitor<'de> for TrialVisitor {
+            type Value = TrialOwned;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("struct Trial")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let name = seq
+                    .next_element()?
+                    .ok_or(de::Error::missing_field("name"))?;
+                let outcome = seq
+                    .next_element()?
+                    .ok_or(de::Error::missing_field("outcome"))?;
+                let output = seq
+                    .next_element()?
+                    .ok_or(de::Error::missing_field("output"))?;
+                // The location and suite are optional, so they may be omitted.
+                let location = seq.next_element()?.unwrap_or(None);
+                let suite = seq.next_element()?.unwrap_or(None);
+
+                Ok(TrialOwned {
+                    name,
+                    outcome,
+                    output,
+                    location,
+                    suite,
+                })
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let mut name = None;
+                let mut outcome = None;
+                let mut output = None;
+                let mut location = None;
+                let mut suite = None;
+
+                while let Some(key) = map.next_key()? {
+                    match key {
+                        Field::Name => {
+                            if name.is_some() {
+                                return Err(A::Error::duplicate_field("name"));
+                            }
+                            name = Some(map.next_value()?);
+                        }
+                        Field::Outcome => {
+                            if outcome.is_some() {
+                                return Err(A::Error::duplicate_field("outcome"));
+                            }
+                            outcome = Some(map.next_value()?);
+                        }
+                        Field::Output => {
+                            if output.is_some() {
+                                return Err(A::Error::duplicate_field("output"));
+                            }
+                            output = Some(map.next_value()?);
+                        }
+                        Field::Location => {
+                            if location.is_some() {
+                                return Err(A::Error::duplicate_field("location"));
+                            }
+                            location = Some(map.next_value()?);
+                        }
+                        Field::Suite => {
+                            if suite.is_some() {
+                                return Err(A::Error::duplicate_field("suite"));
+                            }
+                            suite = Some(map.next_value()?);
+                        }
+                    }
+                }
+
+                Ok(TrialOwned {
+                    name: name.ok_or_else(|| A::Error::missing_field("name"))?,
+                    outcome: outcome.ok_or_else(|| A::Error::missing_field("outcome"))?,
+                    output: output.ok_or_else(|| A::Error::missing_field("output"))?,
+                    // The location and suite are optional, so they may be omitted.
+                    location: location.unwrap_or(None),
+                    suite: suite.unwrap_or(None),
+                })
+            }
+        }
+
+        const FIELDS: &[&str] = &["name", "outcome", "output", "location", "suite"];
+
+        deserializer.deserialize_struct("Trial", FIELDS, TrialVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LocationOwned, OutcomeOwned, TrialOwned};
+    use crate::{Location, Outcome, Trial};
+    use alloc::{borrow::ToOwned, string::ToString};
+
+    fn failed() -> Trial<'static, &'static str> {
+        Trial {
+            name: "tests::it_fails",
+            outcome: Outcome::Failed {
+                message: "panicked at 'foo', src/lib.rs:20:9",
+            },
+            output: "frame 3\n",
+            location: Some(Location {
+                file: "src/lib.rs",
+                line: 19,
+            }),
+            suite: Some("slow"),
+        }
+    }
+
+    fn passed() -> Trial<'static, &'static str> {
+        Trial {
+            name: "tests::it_works",
+            outcome: Outcome::Passed,
+            output: "",
+            location: None,
+            suite: None,
+        }
+    }
+
+    fn failed_owned() -> TrialOwned {
+        TrialOwned {
+            name: "tests::it_fails".to_owned(),
+            outcome: OutcomeOwned::Failed {
+                message: "panicked at 'foo', src/lib.rs:20:9".to_owned(),
+            },
+            output: "frame 3\n".to_owned(),
+            location: Some(LocationOwned {
+                file: "src/lib.rs".to_owned(),
+                line: 19,
+            }),
+            suite: Some("slow".to_owned()),
+        }
+    }
+
+    #[test]
+    fn from_borrowed() {
+        assert_eq!(TrialOwned::from(&failed()), failed_owned());
+    }
+
+    #[test]
+    fn from_borrowed_formats_message() {
+        let trial = Trial {
+            name: "a",
+            outcome: Outcome::Failed { message: 42 },
+            output: "",
+            location: None,
+            suite: None,
+        };
+
+        assert_eq!(
+            TrialOwned::from(trial).outcome,
+            OutcomeOwned::Failed {
+                message: "42".to_owned()
+            }
+        );
+    }
+
+    #[test]
+    fn as_borrowed() {
+        assert_eq!(failed_owned().as_borrowed(), failed());
+    }
+
+    #[test]
+    fn eq_borrowed() {
+        assert_eq!(failed_owned(), failed());
+        assert_ne!(failed_owned(), passed());
+    }
+
+    #[test]
+    fn location_display() {
+        assert_eq!(
+            LocationOwned {
+                file: "src/lib.rs".to_owned(),
+                line: 4,
+            }
+            .to_string(),
+            "src/lib.rs:4"
+        );
+    }
+
+    #[test]
+    fn postcard_borrowed_to_owned() {
+        for trial in [failed(), passed()] {
+            let mut buffer = [0; 128];
+            let bytes = postcard::to_slice(&trial, &mut buffer).unwrap();
+
+            assert_eq!(
+                postcard::from_bytes::<TrialOwned>(bytes).unwrap(),
+                TrialOwned::from(&trial)
+            );
+        }
+    }
+
+    #[test]
+    fn postcard_owned_to_borrowed() {
+        let mut buffer = [0; 128];
+        let bytes = postcard::to_slice(&failed_owned(), &mut buffer).unwrap();
+
+        assert_eq!(
+            postcard::from_bytes::<Trial<&str>>(bytes).unwrap(),
+            failed()
+        );
+    }
+
+    #[test]
+    fn json_borrowed_to_owned() {
+        for trial in [failed(), passed()] {
+            let json = serde_json::to_string(&trial).unwrap();
+
+            assert_eq!(
+                serde_json::from_str::<TrialOwned>(&json).unwrap(),
+                TrialOwned::from(&trial)
+            );
+        }
+    }
+
+    #[test]
+    fn json_owned_to_borrowed() {
+        let json = serde_json::to_string(&TrialOwned::from(passed())).unwrap();
+
+        assert_eq!(
+            serde_json::from_str::<Trial<&str>>(&json).unwrap(),
+            passed()
+        );
+    }
+
+    #[test]
+    fn json_schema() {
+        assert_eq!(
+            serde_json::to_string(&failed_owned()).unwrap(),
+            r#"{"name":"tests::it_fails","outcome":{"Failed":{"message":"panicked at 'foo', src/lib.rs:20:9"}},"output":"frame 3\n","location":{"file":"src/lib.rs","line":19},"suite":"slow"}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&TrialOwned::from(passed())).unwrap(),
+            r#"{"name":"tests::it_works","outcome":"Passed","output":"","location":null,"suite":null}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&OutcomeOwned::Ignored).unwrap(),
+            r#""Ignored""#
+        );
+    }
+
+    #[test]
+    fn json_escaped_strings() {
+        // Strings containing escapes can't be borrowed from the input.
+        let json = r#"{"name":"a","outcome":{"Failed":{"message":"expected \"b\""}},"output":"line\nline"}"#;
+
+        assert!(serde_json::from_str::<Trial<&str>>(json).is_err());
+        assert_eq!(
+            serde_json::from_str::<TrialOwned>(json).unwrap(),
+            TrialOwned {
+                name: "a".to_owned(),
+                outcome: OutcomeOwned::Failed {
+                    message: "expected \"b\"".to_owned(),
+                },
+                output: "line\nline".to_owned(),
+                location: None,
+                suite: None,
+            }
+        );
+    }
+
+    #[test]
+    fn json_missing_field() {
+        let error = serde_json::from_str::<TrialOwned>(r#"{"name":"a","output":""}"#).unwrap_err();
+
+        assert!(error.to_string().starts_with("missing field `outcome`"));
+    }
+
+    #[test]
+    fn json_duplicate_field() {
+        let error = serde_json::from_str::<TrialOwned>(
+            r#"{"name":"a","name":"b","outcome":"Passed","output":""}"#,
+        )
+        .unwrap_err();
+
+        assert!(error.to_string().starts_with("duplicate field `name`"));
+    }
+}
@@ -0,0 +1,161 @@
+//! Utilities for measuring the performance of code on the Game Boy Advance.
+//!
+//! Measurements are taken using timers 2 and 3, cascaded to form a single 32-bit counter that is
+//! incremented every CPU cycle. This allows measuring up to roughly four minutes of execution at
+//! single cycle resolution.
+//!
+//! ```no_run
+//! use gba_test::{assert_cycles_under, perf::Cycles};
+//!
+//! # fn update() {}
+//! // Ensure the update logic fits within a single frame.
+//! assert_cycles_under!(update(), Cycles::FRAME);
+//! ```
+
+use core::{fmt, fmt::Display, ptr};
+use voladdress::{Safe, VolAddress};
+
+#[doc(inline)]
+pub use core::hint::black_box;
+
+/// Timer 2 counter/reload.
+const TM2CNT_L: VolAddress<u16, Safe, Safe> = unsafe { VolAddress::new(0x0400_0108) };
+/// Timer 2 control.
+const TM2CNT_H: VolAddress<u16, Safe, Safe> = unsafe { VolAddress::new(0x0400_010A) };
+/// Timer 3 counter/reload.
+const TM3CNT_L: VolAddress<u16, Safe, Safe> = unsafe { VolAddress::new(0x0400_010C) };
+/// Timer 3 control.
+const TM3CNT_H: VolAddress<u16, Safe, Safe> = unsafe { VolAddress::new(0x0400_010E) };
+
+/// Timer control flag for incrementing when the previous timer overflows.
+const TIMER_CASCADE: u16 = 0b0000_0000_0000_0100;
+/// Timer control flag for starting the timer.
+const TIMER_ENABLE: u16 = 0b0000_0000_1000_0000;
+
+/// The cycles spent by [`measure()`] itself, if they have been calibrated yet.
+static mut OVERHEAD: Option<u32> = None;
+
+/// A number of CPU cycles.
+///
+/// The CPU runs at 2<sup>24</sup> Hz, so a single cycle is roughly 59.6 nanoseconds.
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(all(feature = "runner", target = "thumbv4t-none-eabi")))
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Cycles(pub u32);
+
+impl Cycles {
+    /// The number of cycles in a single scanline, including horizontal blank.
+    pub const SCANLINE: Self = Self(1232);
+    /// The number of cycles in a single frame, including vertical blank.
+    pub const FRAME: Self = Self(Self::SCANLINE.0 * 228);
+
+    /// The CPU clock frequency, in Hz.
+    const FREQUENCY: u64 = 1 << 24;
+
+    /// Returns the number of cycles.
+    pub const fn get(self) -> u32 {
+        self.0
+    }
+
+    /// Converts to microseconds, rounding down.
+    pub const fn as_micros(self) -> u32 {
+        (self.0 as u64 * 1_000_000 / Self::FREQUENCY) as u32
+    }
+
+    /// Converts to scanlines, rounding down.
+    pub const fn as_scanlines(self) -> u32 {
+        self.0 / Self::SCANLINE.0
+    }
+}
+
+impl From<u32> for Cycles {
+    fn from(cycles: u32) -> Self {
+        Self(cycles)
+    }
+}
+
+impl Display for Cycles {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "{} cycles", self.0)
+    }
+}
+
+/// Runs `f` with the timers running, returning the raw number of elapsed cycles.
+#[inline(never)]
+fn measure_raw<F>(f: F) -> u32
+where
+    F: FnOnce(),
+{
+    // Timer 3 counts overflows of timer 2, which counts every cycle.
+    TM2CNT_H.write(0);
+    TM3CNT_H.write(0);
+    TM2CNT_L.write(0);
+    TM3CNT_L.write(0);
+    TM3CNT_H.write(TIMER_ENABLE | TIMER_CASCADE);
+    TM2CNT_H.write(TIMER_ENABLE);
+
+    f();
+
+    // Stopping the timers preserves their counts.
+    TM2CNT_H.write(0);
+    TM3CNT_H.write(0);
+    (TM3CNT_L.read() as u32) << 16 | TM2CNT_L.read() as u32
+}
+
+/// Measures the number of cycles taken to execute `f`.
+///
+/// The cycles spent starting and stopping the timers are calibrated on the first call and
+/// subtracted from every measurement. Interrupts occurring while `f` is running are included in
+/// the measurement.
+///
+/// This uses timers 2 and 3, overwriting any configuration they previously had.
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(all(feature = "runner", target = "thumbv4t-none-eabi")))
+)]
+pub fn measure<F>(f: F) -> Cycles
+where
+    F: FnOnce(),
+{
+    // SAFETY: `OVERHEAD` is only ever accessed on the main thread.
+    let overhead =
+        *unsafe { &mut *ptr::addr_of_mut!(OVERHEAD) }.get_or_insert_with(|| measure_raw(|| {}));
+    Cycles(measure_raw(f).saturating_sub(overhead))
+}
+
+/// Asserts that evaluating an expression takes fewer than the given number of cycles.
+///
+/// The budget can be given either as a [`Cycles`] or as a `u32`. If the budget is exceeded, this
+/// panics with the measured number of cycles.
+///
+/// ```no_run
+/// use gba_test::assert_cycles_under;
+///
+/// # fn multiply(a: u32, b: u32) -> u32 { a * b }
+/// assert_cycles_under!(multiply(3, 4), 100);
+/// ```
+///
+/// [`Cycles`]: crate::perf::Cycles
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(all(feature = "runner", target = "thumbv4t-none-eabi")))
+)]
+#[macro_export]
+macro_rules! assert_cycles_under {
+    ($expr:expr, $budget:expr $(,)?) => {{
+        let budget: $crate::perf::Cycles = ::core::convert::From::from($budget);
+        let cycles = $crate::perf::measure(|| {
+            $crate::perf::black_box($expr);
+        });
+        if cycles >= budget {
+            ::core::panic!(
+                "`{}` took {}, exceeding the budget of {}",
+                ::core::stringify!($expr),
+                cycles,
+                budget,
+            );
+        }
+    }};
+}
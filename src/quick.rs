@@ -0,0 +1,282 @@
+//! Property-style testing using generated inputs.
+//!
+//! A property is checked by evaluating it against many pseudo-randomly generated inputs, using
+//! the [`check!`] macro. Inputs are generated deterministically from a seed, which is derived from
+//! the test being run unless one is given explicitly. On failure, the seed is included in the
+//! failure message along with the failing inputs, so the failure can be reproduced.
+//!
+//! ```no_run
+//! use gba_test::check;
+//!
+//! check!(|x: u32, y: u16| -> bool { x.wrapping_add(y as u32).wrapping_sub(y as u32) == x });
+//! ```
+//!
+//! [`check!`]: crate::check!
+
+/// The number of times a property is checked if no other number is given.
+pub const DEFAULT_ITERATIONS: u32 = 100;
+
+/// The seed that test seeds are derived from.
+#[cfg(all(feature = "runner", any(target = "thumbv4t-none-eabi", doc)))]
+const BASE_SEED: u32 = 0x2545_F491;
+
+/// A small, deterministic pseudo-random number generator.
+///
+/// This is an xorshift generator with 32 bits of state. It is not suitable for anything requiring
+/// unpredictability.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Rng {
+    /// The current state. This is never zero.
+    state: u32,
+}
+
+impl Rng {
+    /// Creates a generator from a seed.
+    ///
+    /// The seed is mixed before use, so that similar seeds produce unrelated streams.
+    pub const fn new(seed: u32) -> Self {
+        let mut state = seed.wrapping_add(0x9E37_79B9);
+        state = (state ^ (state >> 16)).wrapping_mul(0x85EB_CA6B);
+        state = (state ^ (state >> 13)).wrapping_mul(0xC2B2_AE35);
+        state ^= state >> 16;
+        // The all-zero state is a fixed point of xorshift.
+        if state == 0 {
+            state = 1;
+        }
+        Self { state }
+    }
+
+    /// Returns the next 32 pseudo-random bits.
+    pub fn next_u32(&mut self) -> u32 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 17;
+        self.state ^= self.state << 5;
+        self.state
+    }
+}
+
+/// A type whose values can be generated for checking properties.
+pub trait Gen: Sized {
+    /// Generates a value using the given generator.
+    fn generate(rng: &mut Rng) -> Self;
+}
+
+macro_rules! impl_gen_small {
+    ($($ty:ty),*) => {
+        $(
+            impl Gen for $ty {
+                fn generate(rng: &mut Rng) -> Self {
+                    rng.next_u32() as $ty
+                }
+            }
+        )*
+    };
+}
+
+impl_gen_small!(u8, u16, u32, usize, i8, i16, i32, isize);
+
+impl Gen for u64 {
+    fn generate(rng: &mut Rng) -> Self {
+        (rng.next_u32() as u64) << 32 | rng.next_u32() as u64
+    }
+}
+
+impl Gen for i64 {
+    fn generate(rng: &mut Rng) -> Self {
+        u64::generate(rng) as i64
+    }
+}
+
+impl Gen for bool {
+    fn generate(rng: &mut Rng) -> Self {
+        rng.next_u32() & 1 != 0
+    }
+}
+
+impl<T, const N: usize> Gen for [T; N]
+where
+    T: Gen,
+{
+    fn generate(rng: &mut Rng) -> Self {
+        core::array::from_fn(|_| T::generate(rng))
+    }
+}
+
+impl<T> Gen for Option<T>
+where
+    T: Gen,
+{
+    /// Generates `None` a quarter of the time.
+    fn generate(rng: &mut Rng) -> Self {
+        if rng.next_u32() & 0b11 == 0 {
+            None
+        } else {
+            Some(T::generate(rng))
+        }
+    }
+}
+
+/// Returns the seed used by [`check!`] for the current test.
+///
+/// The seed is derived from the test's index, so each test uses a distinct stream of inputs that
+/// stays the same from run to run, regardless of the order the tests are run in.
+///
+/// [`check!`]: crate::check!
+#[cfg(all(feature = "runner", any(target = "thumbv4t-none-eabi", doc)))]
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(all(feature = "runner", target = "thumbv4t-none-eabi")))
+)]
+pub fn test_seed() -> u32 {
+    BASE_SEED ^ crate::runner::current_test_index().unwrap_or(0) as u32
+}
+
+/// Checks that a property holds for many generated inputs.
+///
+/// The property is written as a closure whose parameters all have types implementing [`Gen`] and
+/// [`Debug`], and which returns `bool`. It is evaluated [`DEFAULT_ITERATIONS`] times, each time
+/// with newly generated inputs. If it returns `false`, this panics with the iteration, the seed,
+/// and the inputs.
+///
+/// The number of iterations and the seed can be given before the closure, in either order. By
+/// default, the seed is [`test_seed()`], and giving the seed from a failure message reproduces the
+/// failure.
+///
+/// ```no_run
+/// use gba_test::check;
+///
+/// check!(iterations = 1000, seed = 0x1234, |values: [u8; 4]| {
+///     values.iter().map(|&value| value as u32).sum::<u32>() <= 1020
+/// });
+/// ```
+///
+/// [`Gen`]: crate::quick::Gen
+/// [`Debug`]: core::fmt::Debug
+/// [`DEFAULT_ITERATIONS`]: crate::quick::DEFAULT_ITERATIONS
+/// [`test_seed()`]: crate::quick::test_seed()
+#[macro_export]
+macro_rules! check {
+    (iterations = $iterations:expr, seed = $seed:expr, |$($arg:ident: $ty:ty),+ $(,)?| $(-> bool)? $body:expr $(,)?) => {{
+        let iterations: u32 = $iterations;
+        let seed: u32 = $seed;
+        let mut rng = $crate::quick::Rng::new(seed);
+        for iteration in 0..iterations {
+            let start = rng;
+            $(let $arg: $ty = $crate::quick::Gen::generate(&mut rng);)+
+            if !(|$($arg: $ty),+| -> bool { $body })($($arg),+) {
+                // The property may have consumed the inputs, so they are generated again.
+                let mut rng = start;
+                $(let $arg: $ty = $crate::quick::Gen::generate(&mut rng);)+
+                ::core::panic!(
+                    ::core::concat!(
+                        "property failed on iteration {} of {} (seed: {:#010x})",
+                        $("\n  ", ::core::stringify!($arg), " = {:?}",)+
+                    ),
+                    iteration + 1,
+                    iterations,
+                    seed,
+                    $($arg,)+
+                );
+            }
+        }
+    }};
+    (seed = $seed:expr, iterations = $iterations:expr, $($rest:tt)+) => {
+        $crate::check!(iterations = $iterations, seed = $seed, $($rest)+)
+    };
+    (seed = $seed:expr, $($rest:tt)+) => {
+        $crate::check!(iterations = $crate::quick::DEFAULT_ITERATIONS, seed = $seed, $($rest)+)
+    };
+    (iterations = $iterations:expr, $($rest:tt)+) => {
+        $crate::check!(iterations = $iterations, seed = $crate::quick::test_seed(), $($rest)+)
+    };
+    ($($rest:tt)+) => {
+        $crate::check!(
+            iterations = $crate::quick::DEFAULT_ITERATIONS,
+            seed = $crate::quick::test_seed(),
+            $($rest)+
+        )
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Gen, Rng};
+
+    #[test]
+    fn deterministic() {
+        let mut a = Rng::new(42);
+        let mut b = Rng::new(42);
+
+        for _ in 0..100 {
+            assert_eq!(a.next_u32(), b.next_u32());
+        }
+    }
+
+    #[test]
+    fn distinct_seeds() {
+        let mut a = Rng::new(0);
+        let mut b = Rng::new(1);
+
+        assert_ne!(a.next_u32(), b.next_u32());
+    }
+
+    #[test]
+    fn nonzero_state() {
+        for seed in 0..1000 {
+            assert_ne!(Rng::new(seed).next_u32(), 0);
+        }
+    }
+
+    #[test]
+    fn generate_array() {
+        let mut rng = Rng::new(7);
+        let values = <[u32; 3]>::generate(&mut rng);
+
+        let mut rng = Rng::new(7);
+        assert_eq!(values, [rng.next_u32(), rng.next_u32(), rng.next_u32()]);
+    }
+
+    #[test]
+    fn generate_option() {
+        let mut rng = Rng::new(0);
+        let values = <[Option<u8>; 64]>::generate(&mut rng);
+
+        assert!(values.iter().any(Option::is_none));
+        assert!(values.iter().any(Option::is_some));
+    }
+
+    #[test]
+    fn check_passes() {
+        check!(seed = 1, |x: u32, y: u16| -> bool {
+            x.wrapping_add(y as u32).wrapping_sub(y as u32) == x
+        });
+    }
+
+    #[test]
+    fn check_iterations() {
+        let mut count = 0;
+        check!(iterations = 5, seed = 1, |_x: u8| {
+            count += 1;
+            true
+        });
+
+        assert_eq!(count, 5);
+    }
+
+    #[test]
+    fn check_seed_before_iterations() {
+        let mut count = 0;
+        check!(seed = 1, iterations = 5, |_x: u8| {
+            count += 1;
+            true
+        });
+
+        assert_eq!(count, 5);
+    }
+
+    #[test]
+    #[should_panic(expected = "(seed: 0x00000001)\n  x = ")]
+    fn check_fails() {
+        check!(seed = 1, |x: u8| x < 128);
+    }
+}
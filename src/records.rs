@@ -0,0 +1,756 @@
+//! The format test results are written to SRAM in.
+//!
+//! Results are written as a sequence of records, so that every complete record can be read back
+//! even if writing stopped partway through, such as when SRAM runs out of space or the ROM crashes.
+//!
+//! The results begin with a header: the 4-byte magic `GBAT`, followed by the version of the format
+//! as a little-endian `u16`. Results written in any other version are not read, rather than being
+//! misread. The version is increased whenever the format changes, including the serialization of
+//! [`Trial`] and [`Outcome`].
+//!
+//! The header is followed by the status of the test run: `0` once the run has finished, or `1` if
+//! it was aborted, in which case it is followed by the error message as a `postcard` `&str`. Any
+//! other value means the run has not finished. The status is followed by:
+//!
+//! - the number of tests, as a `postcard` `usize`;
+//! - the [`Environment`] the tests were run in, as a single byte: `0` for
+//!   [`HardwareOrUnknown`], or `1` for [`Mgba`];
+//! - one record per reported trial, each made up of its length as a little-endian `u16` followed
+//!   by the `postcard`-serialized [`Trial`];
+//! - a terminator record, made up of a length of `0` followed by the number of trials that did not
+//!   fit as a `postcard` `usize` and the seed used to shuffle the tests as a `postcard`
+//!   `Option<u32>`.
+//!
+//! While a trial is being written, its length reads as `0xFFFF`. Space for the terminator is
+//! always reserved, so that it can still be written once the trials no longer fit.
+//!
+//! [`Outcome`]: crate::Outcome
+//! [`Environment`]: crate::Environment
+//! [`HardwareOrUnknown`]: crate::Environment::HardwareOrUnknown
+//! [`Mgba`]: crate::Environment::Mgba
+
+use crate::Environment;
+#[cfg(any(feature = "std", test))]
+use crate::Trial;
+#[cfg(any(feature = "std", test))]
+use alloc::vec::Vec;
+#[cfg(any(feature = "std", test))]
+use core::fmt;
+#[cfg(any(all(feature = "runner", any(target = "thumbv4t-none-eabi", doc)), test))]
+use {
+    crate::{display::SerializeDisplay, flavors::Sram},
+    core::{fmt::Display, ptr},
+    serde::Serialize,
+};
+
+/// The magic identifying the start of test results.
+const MAGIC: [u8; 4] = *b"GBAT";
+/// The version of the format results are written in.
+///
+/// This must be increased whenever the format changes, including the serialization of [`Trial`]
+/// and [`Outcome`].
+///
+/// [`Trial`]: crate::Trial
+/// [`Outcome`]: crate::Outcome
+const VERSION: u16 = 2;
+/// The size of the magic and version.
+const HEADER_SIZE: usize = MAGIC.len() + 2;
+/// The value of a byte that has not been written, as SRAM starts out erased.
+#[cfg(any(feature = "std", test))]
+const ERASED: u8 = 0xFF;
+
+/// The status of a test run that has finished.
+const FINISHED: u8 = 0;
+/// The status of a test run that was aborted, matching `postcard`'s encoding of `Result::Err`.
+#[cfg(any(feature = "std", test))]
+const ABORTED: u8 = 1;
+/// The status of a test run that has not yet finished.
+#[cfg(any(all(feature = "runner", any(target = "thumbv4t-none-eabi", doc)), test))]
+const UNFINISHED: u8 = 0xFF;
+
+/// The length of a terminator record.
+const TERMINATOR: u16 = 0;
+/// The length of a record that is still being written.
+#[cfg(any(all(feature = "runner", any(target = "thumbv4t-none-eabi", doc)), test))]
+const IN_PROGRESS: u16 = 0xFFFF;
+/// The size of a record's length.
+const LENGTH_SIZE: usize = 2;
+/// The most space a terminator record can take up.
+///
+/// This is the length, followed by a `usize` and an `Option<u32>` encoded as varints on a 32-bit
+/// target.
+#[cfg(any(all(feature = "runner", any(target = "thumbv4t-none-eabi", doc)), test))]
+const TERMINATOR_CAPACITY: usize = LENGTH_SIZE + 5 + 1 + 5;
+
+/// Writes test results as records.
+#[cfg(any(all(feature = "runner", any(target = "thumbv4t-none-eabi", doc)), test))]
+pub(crate) struct Writer {
+    /// The location of the header.
+    start: *mut u8,
+    /// The location of the next record.
+    ///
+    /// This is guaranteed to leave room for a terminator before `end` after `start()` is called.
+    position: *mut u8,
+    /// The location at which writing stops.
+    end: *mut u8,
+    /// The number of trials that have not fit.
+    dropped: usize,
+}
+
+#[cfg(any(all(feature = "runner", any(target = "thumbv4t-none-eabi", doc)), test))]
+impl Writer {
+    /// Creates a writer to the memory from `start` up to, but not including, `end`.
+    ///
+    /// # Safety
+    /// Every location from `start` up to `end` must be valid for writes, and there must be room for
+    /// at least the header, the status, the number of tests, and a terminator.
+    pub(crate) const unsafe fn new(start: *mut u8, end: *mut u8) -> Self {
+        Self {
+            start,
+            position: start,
+            end,
+            dropped: 0,
+        }
+    }
+
+    /// The location of the status, following the header.
+    fn status(&self) -> *mut u8 {
+        self.start.wrapping_add(HEADER_SIZE)
+    }
+
+    /// Writes the magic and version.
+    fn write_header(&mut self) {
+        for (offset, byte) in MAGIC.into_iter().chain(VERSION.to_le_bytes()).enumerate() {
+            // SAFETY: There is always room for the header before `end`.
+            unsafe {
+                ptr::write_volatile(self.start.add(offset), byte);
+            }
+        }
+    }
+
+    /// The location at which writing records stops, leaving room for the terminator.
+    fn records_end(&self) -> *mut u8 {
+        self.end.wrapping_sub(TERMINATOR_CAPACITY)
+    }
+
+    /// Writes the length of the record at the current position.
+    fn write_length(&mut self, length: u16) {
+        for (offset, byte) in length.to_le_bytes().into_iter().enumerate() {
+            // SAFETY: `position` always leaves room for at least a terminator's length before
+            // `end`.
+            unsafe {
+                ptr::write_volatile(self.position.add(offset), byte);
+            }
+        }
+    }
+
+    /// Begins a test run of `tests` tests in the given environment, marking the results as
+    /// unfinished.
+    ///
+    /// Any previously written results are discarded.
+    pub(crate) fn start(
+        &mut self,
+        tests: usize,
+        environment: Environment,
+    ) -> Result<(), postcard::Error> {
+        // The status is written first, so that previous results are never read with this header.
+        // SAFETY: The status is valid for writes.
+        unsafe {
+            ptr::write_volatile(self.status(), UNFINISHED);
+        }
+        self.write_header();
+        self.dropped = 0;
+        self.position = self.status().wrapping_add(1);
+        // SAFETY: Every location up to `end` is valid for writes.
+        self.position = postcard::serialize_with_flavor(&(tests, environment as u8), unsafe {
+            Sram::new(self.position, self.records_end())
+        })?;
+        Ok(())
+    }
+
+    /// Writes a record containing `value`.
+    ///
+    /// If `value` does not fit, it and every record after it are dropped, and a terminator
+    /// counting the dropped records is written in their place. If serialization fails for any
+    /// other reason, the error is returned and the next record is written over this one.
+    pub(crate) fn record<T>(&mut self, value: &T) -> Result<(), postcard::Error>
+    where
+        T: Serialize,
+    {
+        let data = self.position.wrapping_add(LENGTH_SIZE);
+        if self.dropped == 0 && data <= self.records_end() {
+            self.write_length(IN_PROGRESS);
+            // SAFETY: Every location up to `end` is valid for writes.
+            match postcard::serialize_with_flavor(value, unsafe {
+                Sram::new(data, self.records_end())
+            }) {
+                Ok(end) => {
+                    // Records are bounded by the size of SRAM, so the length always fits.
+                    self.write_length((end as usize - data as usize) as u16);
+                    self.position = end;
+                    return Ok(());
+                }
+                Err(postcard::Error::SerializeBufferFull) => {}
+                Err(error) => return Err(error),
+            }
+        }
+        self.dropped += 1;
+        self.terminate(None)
+    }
+
+    /// Writes a terminator at the current position.
+    fn terminate(&mut self, seed: Option<u32>) -> Result<(), postcard::Error> {
+        self.write_length(TERMINATOR);
+        // SAFETY: Every location up to `end` is valid for writes.
+        postcard::serialize_with_flavor(&(self.dropped, seed), unsafe {
+            Sram::new(self.position.wrapping_add(LENGTH_SIZE), self.end)
+        })?;
+        Ok(())
+    }
+
+    /// Ends the test run, recording the seed used to shuffle the tests, if any.
+    pub(crate) fn finish(&mut self, seed: Option<u32>) -> Result<(), postcard::Error> {
+        self.terminate(seed)?;
+        // SAFETY: The status is valid for writes.
+        unsafe {
+            ptr::write_volatile(self.status(), FINISHED);
+        }
+        Ok(())
+    }
+
+    /// Aborts the test run with the given message, discarding any written records.
+    pub(crate) fn abort<M>(&mut self, message: M) -> Result<(), postcard::Error>
+    where
+        M: Display,
+    {
+        self.write_header();
+        // SAFETY: Every location up to `end` is valid for writes.
+        postcard::serialize_with_flavor(
+            &Result::<(), _>::Err(SerializeDisplay(message)),
+            unsafe { Sram::new(self.status(), self.end) },
+        )?;
+        Ok(())
+    }
+}
+
+/// Why the test results read from a save file are incomplete.
+#[cfg(any(feature = "std", test))]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "std")))]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum Truncation<'a> {
+    /// The test run was aborted with the given message, and no results were kept.
+    Aborted { message: &'a str },
+    /// SRAM ran out of space, and the results of the last `dropped` tests were not written.
+    OutOfSpace { dropped: usize },
+    /// The results end partway through, such as when the test run has not finished.
+    Incomplete,
+    /// The results were written in a different version of the format than this version of
+    /// `gba_test` reads, and none of them were read.
+    ///
+    /// This happens when the tests were built with a different version of `gba_test`.
+    VersionMismatch { expected: u16, found: u16 },
+    /// The data does not contain test results.
+    UnrecognizedFormat,
+}
+
+#[cfg(any(feature = "std", test))]
+impl fmt::Display for Truncation<'_> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Aborted { message } => write!(formatter, "test run aborted: {}", message),
+            Self::OutOfSpace { dropped } => {
+                write!(formatter, "{} results did not fit in SRAM", dropped)
+            }
+            Self::Incomplete => formatter.write_str("test run did not finish"),
+            Self::VersionMismatch { expected, found } => write!(
+                formatter,
+                "results were written in format version {}, but only version {} can be read",
+                found, expected
+            ),
+            Self::UnrecognizedFormat => formatter.write_str("data does not contain test results"),
+        }
+    }
+}
+
+/// Everything read from a save file.
+#[cfg(any(feature = "std", test))]
+struct Results<'a> {
+    trials: Vec<Trial<'a, &'a str>>,
+    truncation: Option<Truncation<'a>>,
+    seed: Option<u32>,
+    environment: Option<Environment>,
+}
+
+/// Reads every complete record from `bytes`.
+#[cfg(any(feature = "std", test))]
+fn read(bytes: &[u8]) -> Results<'_> {
+    let mut results = Results {
+        trials: Vec::new(),
+        truncation: Some(Truncation::Incomplete),
+        seed: None,
+        environment: None,
+    };
+
+    // A header that is still being written contains the start of the magic, followed by erased
+    // bytes.
+    if bytes
+        .iter()
+        .zip(MAGIC)
+        .any(|(&byte, expected)| byte != expected && byte != ERASED)
+    {
+        results.truncation = Some(Truncation::UnrecognizedFormat);
+        return results;
+    }
+    let Some((header, bytes)) = bytes.split_first_chunk::<HEADER_SIZE>() else {
+        return results;
+    };
+    if header[..MAGIC.len()] != MAGIC {
+        return results;
+    }
+    let version = u16::from_le_bytes([header[MAGIC.len()], header[MAGIC.len() + 1]]);
+    if version == u16::from_le_bytes([ERASED; 2]) {
+        return results;
+    }
+    if version != VERSION {
+        results.truncation = Some(Truncation::VersionMismatch {
+            expected: VERSION,
+            found: version,
+        });
+        return results;
+    }
+
+    let Some((&status, remaining)) = bytes.split_first() else {
+        return results;
+    };
+    if status == ABORTED {
+        if let Ok(Err(message)) = postcard::from_bytes::<Result<(), &str>>(bytes) {
+            results.truncation = Some(Truncation::Aborted { message });
+        }
+        return results;
+    }
+    let Ok((_, remaining)) = postcard::take_from_bytes::<usize>(remaining) else {
+        return results;
+    };
+    let Some((environment, mut remaining)) = remaining
+        .split_first()
+        .and_then(|(&byte, remaining)| Some((Environment::from_byte(byte)?, remaining)))
+    else {
+        return results;
+    };
+    results.environment = Some(environment);
+
+    while let Some((length, rest)) = remaining.split_first_chunk::<LENGTH_SIZE>() {
+        let length = u16::from_le_bytes(*length);
+        if length == TERMINATOR {
+            let Ok(((dropped, seed), _)) = postcard::take_from_bytes::<(usize, Option<u32>)>(rest)
+            else {
+                break;
+            };
+            results.seed = seed;
+            results.truncation = if dropped > 0 {
+                Some(Truncation::OutOfSpace { dropped })
+            } else if status == FINISHED {
+                None
+            } else {
+                Some(Truncation::Incomplete)
+            };
+            break;
+        }
+        // A record that is still being written has a length longer than the remaining bytes.
+        let Some(record) = rest.get(..length.into()) else {
+            break;
+        };
+        let Ok(trial) = postcard::from_bytes(record) else {
+            break;
+        };
+        results.trials.push(trial);
+        remaining = &rest[record.len()..];
+    }
+
+    results
+}
+
+/// Reads the test results from the contents of a save file.
+///
+/// Every trial that was written completely is returned, in the order the tests were run, even if
+/// the rest of the results are missing. If not every test's result is present, the reason is
+/// returned as well.
+///
+/// # Example
+/// ```no_run
+/// use std::fs;
+///
+/// let bytes = fs::read("tests.sav").unwrap();
+/// let (trials, truncation) = gba_test::read_trials(&bytes);
+/// if let Some(truncation) = truncation {
+///     eprintln!("results are incomplete: {}", truncation);
+/// }
+/// for trial in trials {
+///     println!("{}: {:?}", trial.name, trial.outcome);
+/// }
+/// ```
+#[cfg(any(feature = "std", test))]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "std")))]
+pub fn read_trials(bytes: &[u8]) -> (Vec<Trial<'_, &str>>, Option<Truncation<'_>>) {
+    let results = read(bytes);
+    (results.trials, results.truncation)
+}
+
+/// Reads the seed used to shuffle the tests from the contents of a save file.
+///
+/// This is `None` if the tests were not shuffled, or if the test run did not reach its end.
+#[cfg(any(feature = "std", test))]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "std")))]
+pub fn read_seed(bytes: &[u8]) -> Option<u32> {
+    read(bytes).seed
+}
+
+/// Reads the environment the tests were run in from the contents of a save file.
+///
+/// This is `None` if the test run did not get far enough to record it, or if it was aborted.
+#[cfg(any(feature = "std", test))]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "std")))]
+pub fn read_environment(bytes: &[u8]) -> Option<Environment> {
+    read(bytes).environment
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        read_environment, read_seed, read_trials, Truncation, Writer, HEADER_SIZE, MAGIC,
+        TERMINATOR_CAPACITY, VERSION,
+    };
+    use crate::{Environment, Outcome, Trial};
+    use alloc::{vec, vec::Vec};
+    use core::{fmt, fmt::Display};
+
+    fn trial(name: &str) -> Trial<'_, &str> {
+        Trial {
+            name,
+            outcome: Outcome::Failed {
+                message: "assertion failed",
+            },
+            output: "",
+            location: None,
+            suite: None,
+        }
+    }
+
+    /// Writes `trials` to a buffer of `len` bytes, returning the buffer and the number of bytes
+    /// written before the terminator.
+    fn write(len: usize, trials: &[Trial<&str>], seed: Option<u32>) -> (Vec<u8>, usize) {
+        let mut buffer = vec![0xFF; len];
+        let start = buffer.as_mut_ptr();
+        let mut writer = unsafe { Writer::new(start, start.wrapping_add(len)) };
+        writer
+            .start(trials.len(), Environment::HardwareOrUnknown)
+            .unwrap();
+        for trial in trials {
+            writer.record(trial).unwrap();
+        }
+        let used = writer.position as usize - start as usize;
+        writer.finish(seed).unwrap();
+        (buffer, used)
+    }
+
+    #[test]
+    fn round_trip() {
+        let trials = [trial("a"), trial("b")];
+        let (bytes, _) = write(256, &trials, Some(42));
+
+        assert_eq!(read_trials(&bytes), (Vec::from(trials), None));
+        assert_eq!(read_seed(&bytes), Some(42));
+        assert_eq!(
+            read_environment(&bytes),
+            Some(Environment::HardwareOrUnknown)
+        );
+    }
+
+    #[test]
+    fn round_trip_empty() {
+        let (bytes, _) = write(256, &[], None);
+
+        assert_eq!(read_trials(&bytes), (vec![], None));
+        assert_eq!(read_seed(&bytes), None);
+    }
+
+    #[test]
+    fn exact_fit() {
+        let trials = [trial("a"), trial("b"), trial("c")];
+        let (_, used) = write(256, &trials, None);
+        let (bytes, _) = write(used + TERMINATOR_CAPACITY, &trials, Some(42));
+
+        assert_eq!(read_trials(&bytes), (Vec::from(trials), None));
+        assert_eq!(read_seed(&bytes), Some(42));
+    }
+
+    #[test]
+    fn one_byte_short() {
+        let trials = [trial("a"), trial("b"), trial("c")];
+        let (_, used) = write(256, &trials, None);
+        let (bytes, _) = write(used + TERMINATOR_CAPACITY - 1, &trials, Some(42));
+
+        assert_eq!(
+            read_trials(&bytes),
+            (
+                vec![trial("a"), trial("b")],
+                Some(Truncation::OutOfSpace { dropped: 1 })
+            )
+        );
+        assert_eq!(read_seed(&bytes), Some(42));
+    }
+
+    #[test]
+    fn many_dropped() {
+        let trials = [
+            trial("a"),
+            trial("b"),
+            trial("c"),
+            trial("d"),
+            trial("e"),
+            trial("f"),
+        ];
+        let (_, used) = write(256, &trials[..1], None);
+        let (bytes, _) = write(used + TERMINATOR_CAPACITY, &trials, None);
+
+        assert_eq!(
+            read_trials(&bytes),
+            (
+                vec![trial("a")],
+                Some(Truncation::OutOfSpace { dropped: 5 })
+            )
+        );
+    }
+
+    #[test]
+    fn cut_off() {
+        let trials = [trial("a"), trial("b"), trial("c")];
+        let (bytes, _) = write(256, &trials, Some(42));
+        let (_, full) = read_trials(&bytes);
+        assert_eq!(full, None);
+
+        for len in 0..bytes.len() {
+            let (read, truncation) = read_trials(&bytes[..len]);
+            // Every complete trial is recovered, in order.
+            assert_eq!(read, trials[..read.len()], "{}", len);
+            if truncation.is_some() {
+                assert_eq!(truncation, Some(Truncation::Incomplete), "{}", len);
+            }
+        }
+        assert_eq!(
+            read_trials(&bytes[..4]),
+            (vec![], Some(Truncation::Incomplete))
+        );
+    }
+
+    #[test]
+    fn unfinished() {
+        let mut buffer = vec![0; 256];
+        let start = buffer.as_mut_ptr();
+        let mut writer = unsafe { Writer::new(start, start.wrapping_add(256)) };
+        writer.start(3, Environment::HardwareOrUnknown).unwrap();
+        writer.record(&trial("a")).unwrap();
+
+        assert_eq!(
+            read_trials(&buffer),
+            (vec![trial("a")], Some(Truncation::Incomplete))
+        );
+    }
+
+    #[test]
+    fn failed_record_overwritten() {
+        struct Unformattable;
+
+        impl Display for Unformattable {
+            fn fmt(&self, _formatter: &mut fmt::Formatter) -> fmt::Result {
+                Err(fmt::Error)
+            }
+        }
+
+        let mut buffer = vec![0; 256];
+        let start = buffer.as_mut_ptr();
+        let mut writer = unsafe { Writer::new(start, start.wrapping_add(256)) };
+        writer.start(2, Environment::HardwareOrUnknown).unwrap();
+        assert_eq!(
+            writer.record(&Trial {
+                name: "a",
+                outcome: Outcome::Failed {
+                    message: Unformattable,
+                },
+                output: "",
+                location: None,
+                suite: None,
+            }),
+            Err(postcard::Error::CollectStrError)
+        );
+        writer.record(&trial("b")).unwrap();
+        writer.finish(None).unwrap();
+
+        assert_eq!(read_trials(&buffer), (vec![trial("b")], None));
+    }
+
+    #[test]
+    fn aborted() {
+        let mut buffer = vec![0; 256];
+        let start = buffer.as_mut_ptr();
+        let mut writer = unsafe { Writer::new(start, start.wrapping_add(256)) };
+        writer.start(1, Environment::HardwareOrUnknown).unwrap();
+        writer.record(&trial("a")).unwrap();
+        writer.abort("out of memory").unwrap();
+
+        assert_eq!(
+            read_trials(&buffer),
+            (
+                vec![],
+                Some(Truncation::Aborted {
+                    message: "out of memory"
+                })
+            )
+        );
+        assert_eq!(read_environment(&buffer), None);
+    }
+
+    #[test]
+    fn environment() {
+        let mut buffer = vec![0xFF; 256];
+        let start = buffer.as_mut_ptr();
+        let mut writer = unsafe { Writer::new(start, start.wrapping_add(256)) };
+        writer.start(1, Environment::Mgba).unwrap();
+
+        assert_eq!(buffer[HEADER_SIZE + 2], 1);
+        assert_eq!(read_environment(&buffer), Some(Environment::Mgba));
+        assert_eq!(read_trials(&buffer), (vec![], Some(Truncation::Incomplete)));
+    }
+
+    #[test]
+    fn environment_unrecognized() {
+        let (mut bytes, _) = write(256, &[trial("a")], None);
+        bytes[HEADER_SIZE + 2] = 2;
+
+        assert_eq!(read_environment(&bytes), None);
+        assert_eq!(read_trials(&bytes), (vec![], Some(Truncation::Incomplete)));
+    }
+
+    #[test]
+    fn environment_not_written() {
+        let (bytes, _) = write(256, &[trial("a")], None);
+
+        assert_eq!(read_environment(&bytes[..HEADER_SIZE + 2]), None);
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!(read_trials(&[]), (vec![], Some(Truncation::Incomplete)));
+    }
+
+    #[test]
+    fn erased() {
+        assert_eq!(
+            read_trials(&[0xFF; 256]),
+            (vec![], Some(Truncation::Incomplete))
+        );
+    }
+
+    #[test]
+    fn header() {
+        let (bytes, _) = write(256, &[trial("a")], None);
+
+        assert_eq!(&bytes[..MAGIC.len()], b"GBAT");
+        assert_eq!(bytes[MAGIC.len()..HEADER_SIZE], VERSION.to_le_bytes());
+    }
+
+    #[test]
+    fn partial_header() {
+        let (mut bytes, _) = write(256, &[trial("a")], None);
+        for len in 0..HEADER_SIZE {
+            bytes[len..HEADER_SIZE].fill(0xFF);
+
+            assert_eq!(
+                read_trials(&bytes),
+                (vec![], Some(Truncation::Incomplete)),
+                "{}",
+                len
+            );
+        }
+    }
+
+    #[test]
+    fn truncated_magic() {
+        assert_eq!(read_trials(b"GB"), (vec![], Some(Truncation::Incomplete)));
+        assert_eq!(
+            read_trials(b"GX"),
+            (vec![], Some(Truncation::UnrecognizedFormat))
+        );
+    }
+
+    #[test]
+    fn unrecognized_format() {
+        let (mut bytes, _) = write(256, &[trial("a")], None);
+        bytes[..MAGIC.len()].copy_from_slice(b"ABCD");
+
+        assert_eq!(
+            read_trials(&bytes),
+            (vec![], Some(Truncation::UnrecognizedFormat))
+        );
+        assert_eq!(read_seed(&bytes), None);
+    }
+
+    #[test]
+    fn version_mismatch() {
+        let (mut bytes, _) = write(256, &[trial("a")], Some(42));
+        bytes[MAGIC.len()..HEADER_SIZE].copy_from_slice(&(VERSION + 1).to_le_bytes());
+
+        assert_eq!(
+            read_trials(&bytes),
+            (
+                vec![],
+                Some(Truncation::VersionMismatch {
+                    expected: VERSION,
+                    found: VERSION + 1,
+                })
+            )
+        );
+        assert_eq!(read_seed(&bytes), None);
+    }
+
+    #[test]
+    fn aborted_header() {
+        let mut buffer = vec![0xFF; 256];
+        let start = buffer.as_mut_ptr();
+        let mut writer = unsafe { Writer::new(start, start.wrapping_add(256)) };
+        writer.abort("global setup failed").unwrap();
+
+        assert_eq!(
+            read_trials(&buffer),
+            (
+                vec![],
+                Some(Truncation::Aborted {
+                    message: "global setup failed"
+                })
+            )
+        );
+    }
+
+    #[test]
+    fn display_truncation() {
+        assert_eq!(
+            alloc::format!("{}", Truncation::OutOfSpace { dropped: 3 }),
+            "3 results did not fit in SRAM"
+        );
+    }
+
+    #[test]
+    fn display_version_mismatch() {
+        assert_eq!(
+            alloc::format!(
+                "{}",
+                Truncation::VersionMismatch {
+                    expected: 1,
+                    found: 2
+                }
+            ),
+            "results were written in format version 2, but only version 1 can be read"
+        );
+    }
+}
@@ -0,0 +1,97 @@
+//! Reporting of the exit code once all tests have been run.
+//!
+//! Emulators differ in how a ROM can signal that it has finished. By default, the exit code is
+//! reported using the `swi #0x03` convention understood by `mgba-rom-test`, but other methods can
+//! be selected using [`set_report_method()`].
+//!
+//! [`set_report_method()`]: crate::set_report_method()
+
+use crate::ExitCode;
+use core::ptr;
+use voladdress::{Safe, Unsafe, VolAddress};
+
+/// The scanline currently being drawn.
+const VCOUNT: VolAddress<u16, Safe, Unsafe> = unsafe { VolAddress::new(0x0400_0006) };
+
+/// The first scanline of vertical blank.
+const VBLANK_SCANLINE: u16 = 160;
+
+/// The number of frames the exit code is written to a mailbox for.
+const MAILBOX_FRAMES: u32 = 60;
+
+/// How the exit code is reported once all tests have been run.
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(all(feature = "runner", target = "thumbv4t-none-eabi")))
+)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ReportMethod {
+    /// Place the exit code in `r0` and call `swi #0x03`.
+    ///
+    /// This is the default. When using `mgba-rom-test`, pass `-S 0x03 -R r0` to use the reported
+    /// value as the emulator's exit code.
+    MgbaSwi,
+    /// Write [`MAILBOX_MAGIC`] to `address`, followed by the exit code as a `u32` at
+    /// `address + 4`.
+    ///
+    /// The values are rewritten at the start of every vertical blank for 60 frames, giving
+    /// debuggers and emulator scripts polling the address time to observe them. `address` must be
+    /// 4-byte aligned and point to 8 bytes of writable memory that are not used by the tests, such
+    /// as [`DEFAULT_MAILBOX`] at the end of EWRAM.
+    ///
+    /// [`MAILBOX_MAGIC`]: ReportMethod::MAILBOX_MAGIC
+    /// [`DEFAULT_MAILBOX`]: ReportMethod::DEFAULT_MAILBOX
+    MemoryMailbox {
+        /// The address the magic value and exit code are written to.
+        address: usize,
+    },
+    /// Do not report the exit code.
+    None,
+}
+
+impl ReportMethod {
+    /// The value written before the exit code when reporting using [`MemoryMailbox`].
+    ///
+    /// This is the bytes `GBAT` in little-endian order.
+    ///
+    /// [`MemoryMailbox`]: ReportMethod::MemoryMailbox
+    pub const MAILBOX_MAGIC: u32 = u32::from_le_bytes(*b"GBAT");
+    /// A suggested address for [`MemoryMailbox`], occupying the last 8 bytes of EWRAM.
+    ///
+    /// [`MemoryMailbox`]: ReportMethod::MemoryMailbox
+    pub const DEFAULT_MAILBOX: usize = 0x0203_FFF8;
+
+    /// Reports the exit code, then halts.
+    pub(crate) fn report(self, exit_code: ExitCode) -> ! {
+        match self {
+            Self::MgbaSwi => {
+                // SAFETY: Halting has no memory safety requirements. Outside of `mgba-rom-test`,
+                // the call may return, clobbering the registers the BIOS is free to use.
+                unsafe {
+                    core::arch::asm!(
+                        "swi #0x03",
+                        in("r0") exit_code as usize,
+                        clobber_abi("C"),
+                    );
+                }
+            }
+            Self::MemoryMailbox { address } => {
+                let mailbox = address as *mut u32;
+                for _ in 0..MAILBOX_FRAMES {
+                    // SAFETY: The user guarantees the mailbox is valid for writes.
+                    unsafe {
+                        ptr::write_volatile(mailbox, Self::MAILBOX_MAGIC);
+                        ptr::write_volatile(mailbox.add(1), exit_code as u32);
+                    }
+                    // Wait for the next vertical blank to begin.
+                    while VCOUNT.read() == VBLANK_SCANLINE {}
+                    while VCOUNT.read() != VBLANK_SCANLINE {}
+                }
+            }
+            Self::None => {}
+        }
+        loop {
+            core::hint::spin_loop();
+        }
+    }
+}
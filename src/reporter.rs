@@ -0,0 +1,134 @@
+//! Reporting of test results.
+//!
+//! As each test completes, the test runner passes its result to a [`Reporter`]. By default, the
+//! results are serialized to SRAM using `postcard`, but a custom reporter can be provided using
+//! [`runner_with_reporter()`].
+//!
+//! [`runner_with_reporter()`]: crate::runner_with_reporter()
+
+use crate::{hardware, records::Writer, Outcome, Summary, Trial};
+use core::fmt::Display;
+
+/// The start of the SRAM.
+const SRAM_START: *mut u8 = 0x0E00_0000 as *mut u8;
+/// The end of the SRAM, exclusive.
+const SRAM_END: *mut u8 = 0x0E01_0000 as *mut u8;
+
+/// The message reported in place of a failure message that could not be formatted.
+const UNFORMATTABLE_MESSAGE: &str = "<failure message could not be formatted>";
+
+/// Receives the results of tests as they are run.
+///
+/// Implement this trait to send test results somewhere other than SRAM, such as a flashcart's USB
+/// interface. A reporter can be installed using [`runner_with_reporter()`].
+///
+/// [`runner_with_reporter()`]: crate::runner_with_reporter()
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(all(feature = "runner", target = "thumbv4t-none-eabi")))
+)]
+pub trait Reporter {
+    /// Called once before any tests are run, with the total number of tests.
+    ///
+    /// By default, this does nothing.
+    fn start(&mut self, _tests: usize) {}
+
+    /// Reports the result of a single test.
+    ///
+    /// This is called once for every test, in the order the tests are run. Note that formatting a
+    /// failure message may return an error partway through, such as when a panic's payload has a
+    /// `Display` implementation that fails.
+    fn report(&mut self, trial: &Trial<&dyn Display>);
+
+    /// Called once after all tests have been run.
+    fn finish(&mut self, summary: &Summary);
+
+    /// Called instead of [`finish()`] if the test run could not be completed.
+    ///
+    /// No further methods are called afterward. By default, this does nothing.
+    ///
+    /// [`finish()`]: Reporter::finish()
+    fn abort(&mut self, _message: &dyn Display) {}
+}
+
+/// Reports test results by serializing them to SRAM using `postcard`.
+///
+/// Each trial is written as its own length-prefixed record as soon as it is reported, so every
+/// complete record can be read back even if the test run never finishes. Once SRAM is full, the
+/// remaining trials are dropped and counted instead. The format is described in the `records`
+/// module, and can be read using `read_trials()` with the `std` feature enabled.
+pub(crate) struct SramReporter {
+    writer: Writer,
+}
+
+impl SramReporter {
+    /// Creates a new SRAM reporter.
+    pub(crate) const fn new() -> Self {
+        Self {
+            // SAFETY: The whole of SRAM is valid for writes.
+            writer: unsafe { Writer::new(SRAM_START, SRAM_END) },
+        }
+    }
+
+    /// Handle an error that occurred during test execution.
+    ///
+    /// We can't panic in this context, as that would cause the code to loop until the stack
+    /// overflows. Instead, this function attempts to write the error to SRAM.
+    fn handle_error<E>(error: E)
+    where
+        E: Display,
+    {
+        // If writing to SRAM fails here, there is not much else that can be done. Unwrapping the
+        // result would lead to a panic loop, causing a stack overflow, so we simply ignore the
+        // error if there is one.
+        #[allow(unused_must_use)]
+        {
+            // SAFETY: The whole of SRAM is valid for writes.
+            unsafe { Writer::new(SRAM_START, SRAM_END) }.abort(error);
+        }
+    }
+}
+
+impl Reporter for SramReporter {
+    fn start(&mut self, tests: usize) {
+        // The reporter is stored in a static, whose initial value is not loaded by every runtime,
+        // so the writer's bounds are set again here rather than relied on from `new()`.
+        // SAFETY: The whole of SRAM is valid for writes.
+        self.writer = unsafe { Writer::new(SRAM_START, SRAM_END) };
+        self.writer
+            .start(tests, hardware::environment())
+            .unwrap_or_else(Self::handle_error);
+    }
+
+    fn report(&mut self, trial: &Trial<&dyn Display>) {
+        match self.writer.record(trial) {
+            Ok(()) => {}
+            // The failure message could not be formatted. Since a record is only kept once it is
+            // written completely, the partially written trial is overwritten by one with a fixed
+            // message, keeping the test's result.
+            Err(postcard::Error::CollectStrError) => self
+                .writer
+                .record(&Trial {
+                    name: trial.name,
+                    outcome: Outcome::Failed {
+                        message: &UNFORMATTABLE_MESSAGE as &dyn Display,
+                    },
+                    output: trial.output,
+                    location: trial.location,
+                    suite: trial.suite,
+                })
+                .unwrap_or_else(Self::handle_error),
+            Err(error) => Self::handle_error(error),
+        }
+    }
+
+    fn finish(&mut self, summary: &Summary) {
+        self.writer
+            .finish(summary.seed)
+            .unwrap_or_else(Self::handle_error);
+    }
+
+    fn abort(&mut self, message: &dyn Display) {
+        Self::handle_error(message);
+    }
+}
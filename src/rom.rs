@@ -0,0 +1,358 @@
+//! Conversion of a built test executable into a ROM that can be run directly.
+//!
+//! The ELF file produced by `cargo test --no-run` is not itself a valid `.gba` file: its loaded
+//! data has to be extracted into a flat binary, and the cartridge header has to contain the
+//! Nintendo logo and a correct complement check. [`from_elf()`] does both, padding the ROM to a
+//! power-of-two size, so that the result can be run on hardware or in any emulator.
+
+use crate::elf::{self, read_u16, read_u32, IDENT};
+use alloc::{vec, vec::Vec};
+use core::{fmt, fmt::Display};
+
+/// The address the cartridge ROM is mapped to.
+const ROM_START: u32 = 0x0800_0000;
+/// The largest ROM that can be mapped into the Game Boy Advance's address space.
+pub const MAX_SIZE: usize = 32 * 1024 * 1024;
+/// The size of the cartridge header at the start of the ROM.
+const HEADER_SIZE: usize = 0xC0;
+/// The offset of the Nintendo logo within the header.
+const LOGO_OFFSET: usize = 0x04;
+/// The offset of the fixed value within the header.
+const FIXED_VALUE_OFFSET: usize = 0xB2;
+/// The value that must be at [`FIXED_VALUE_OFFSET`].
+const FIXED_VALUE: u8 = 0x96;
+/// The offset of the complement check within the header.
+const COMPLEMENT_OFFSET: usize = 0xBD;
+/// The byte that unused space in the ROM is filled with.
+const PADDING: u8 = 0xFF;
+
+/// The type of a program header describing a loaded segment.
+const PT_LOAD: u32 = 1;
+/// The size of an ELF program header.
+const PROGRAM_HEADER_SIZE: usize = 32;
+
+/// The compressed Nintendo logo, which the BIOS requires to be in the cartridge header.
+pub const LOGO: [u8; 156] = [
+    0x24, 0xFF, 0xAE, 0x51, 0x69, 0x9A, 0xA2, 0x21, 0x3D, 0x84, 0x82, 0x0A, 0x84, 0xE4, 0x09, 0xAD,
+    0x11, 0x24, 0x8B, 0x98, 0xC0, 0x81, 0x7F, 0x21, 0xA3, 0x52, 0xBE, 0x19, 0x93, 0x09, 0xCE, 0x20,
+    0x10, 0x46, 0x4A, 0x4A, 0xF8, 0x27, 0x31, 0xEC, 0x58, 0xC7, 0xE8, 0x33, 0x82, 0xE3, 0xCE, 0xBF,
+    0x85, 0xF4, 0xDF, 0x94, 0xCE, 0x4B, 0x09, 0xC1, 0x94, 0x56, 0x8A, 0xC0, 0x13, 0x72, 0xA7, 0xFC,
+    0x9F, 0x84, 0x4D, 0x73, 0xA3, 0xCA, 0x9A, 0x61, 0x58, 0x97, 0xA3, 0x27, 0xFC, 0x03, 0x98, 0x76,
+    0x23, 0x1D, 0xC7, 0x61, 0x03, 0x04, 0xAE, 0x56, 0xBF, 0x38, 0x84, 0x00, 0x40, 0xA7, 0x0E, 0xFD,
+    0xFF, 0x52, 0xFE, 0x03, 0x6F, 0x95, 0x30, 0xF1, 0x97, 0xFB, 0xC0, 0x85, 0x60, 0xD6, 0x80, 0x25,
+    0xA9, 0x63, 0xBE, 0x03, 0x01, 0x4E, 0x38, 0xE2, 0xF9, 0xA2, 0x34, 0xFF, 0xBB, 0x3E, 0x03, 0x44,
+    0x78, 0x00, 0x90, 0xCB, 0x88, 0x11, 0x3A, 0x94, 0x65, 0xC0, 0x7C, 0x63, 0x87, 0xF0, 0x3C, 0xAF,
+    0xD6, 0x25, 0xE4, 0x8B, 0x38, 0x0A, 0xAC, 0x72, 0x21, 0xD4, 0xF8, 0x07,
+];
+
+/// An error encountered while converting an ELF file into a ROM.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum Error {
+    /// The ELF file could not be read.
+    Elf(elf::Error),
+    /// A segment is loaded from an address outside of the cartridge ROM.
+    OutsideRom {
+        /// The address the segment is loaded from.
+        address: u32,
+    },
+    /// The ROM would be larger than [`MAX_SIZE`].
+    TooLarge {
+        /// The size of the ROM, in bytes, before padding.
+        size: u64,
+    },
+}
+
+impl Display for Error {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Elf(error) => error.fmt(formatter),
+            Self::OutsideRom { address } => write!(
+                formatter,
+                "segment at {:#010x} is not loaded from cartridge ROM",
+                address
+            ),
+            Self::TooLarge { size } => write!(
+                formatter,
+                "ROM is {} bytes, but at most {} bytes can be mapped",
+                size, MAX_SIZE
+            ),
+        }
+    }
+}
+
+impl From<elf::Error> for Error {
+    fn from(error: elf::Error) -> Self {
+        Self::Elf(error)
+    }
+}
+
+/// Returns the complement check of a cartridge header.
+///
+/// # Panics
+/// Panics if `rom` is shorter than the cartridge header.
+pub fn complement_check(rom: &[u8]) -> u8 {
+    rom[0xA0..COMPLEMENT_OFFSET]
+        .iter()
+        .fold(0u8, |sum, &byte| sum.wrapping_sub(byte))
+        .wrapping_sub(0x19)
+}
+
+/// Writes the Nintendo logo, the fixed value, and the complement check into a cartridge header.
+///
+/// The rest of the header, such as the entry point and the game title, is left as it is.
+///
+/// # Panics
+/// Panics if `rom` is shorter than the cartridge header.
+pub fn fix_header(rom: &mut [u8]) {
+    rom[LOGO_OFFSET..LOGO_OFFSET + LOGO.len()].copy_from_slice(&LOGO);
+    rom[FIXED_VALUE_OFFSET] = FIXED_VALUE;
+    rom[COMPLEMENT_OFFSET] = complement_check(rom);
+}
+
+/// Converts an ELF file built for the Game Boy Advance into a ROM.
+///
+/// Every segment loaded from cartridge ROM is copied to its load address, with gaps between them
+/// filled with `0xFF`. The ROM is then padded with `0xFF` to the next power of two, and its header
+/// is fixed using [`fix_header()`].
+///
+/// # Example
+/// ```no_run
+/// use std::fs;
+///
+/// let elf = fs::read("target/thumbv4t-none-eabi/debug/deps/tests-0123456789abcdef").unwrap();
+/// let rom = gba_test::rom::from_elf(&elf).unwrap();
+/// fs::write("target/thumbv4t-none-eabi/debug/deps/tests-0123456789abcdef.gba", rom).unwrap();
+/// ```
+pub fn from_elf(bytes: &[u8]) -> Result<Vec<u8>, Error> {
+    if bytes.get(..IDENT.len()) != Some(&IDENT) {
+        return Err(elf::Error::NotElf.into());
+    }
+    let program_headers = read_u32(bytes, 0x1C)? as usize;
+    let program_header_size = read_u16(bytes, 0x2A)? as usize;
+    let program_header_count = read_u16(bytes, 0x2C)? as usize;
+    if program_header_size < PROGRAM_HEADER_SIZE {
+        return Err(elf::Error::Malformed.into());
+    }
+
+    // Find the data of each segment, along with its offset into the ROM.
+    let mut segments = Vec::new();
+    for index in 0..program_header_count {
+        let header = program_headers + index * program_header_size;
+        let kind = read_u32(bytes, header)?;
+        let offset = read_u32(bytes, header + 4)? as usize;
+        let address = read_u32(bytes, header + 12)?;
+        let size = read_u32(bytes, header + 16)? as usize;
+        if kind != PT_LOAD || size == 0 {
+            continue;
+        }
+        let start = address
+            .checked_sub(ROM_START)
+            .filter(|&start| (start as usize) < MAX_SIZE)
+            .ok_or(Error::OutsideRom { address })? as usize;
+        let end = start as u64 + size as u64;
+        if end > MAX_SIZE as u64 {
+            return Err(Error::TooLarge { size: end });
+        }
+        let data = bytes
+            .get(offset..)
+            .and_then(|data| data.get(..size))
+            .ok_or(elf::Error::Malformed)?;
+        segments.push((start, data));
+    }
+
+    let len = segments
+        .iter()
+        .map(|(start, data)| start + data.len())
+        .max()
+        .unwrap_or(0);
+    let mut rom = vec![PADDING; len.max(HEADER_SIZE).next_power_of_two()];
+    for (start, data) in segments {
+        rom[start..start + data.len()].copy_from_slice(data);
+    }
+    fix_header(&mut rom);
+    Ok(rom)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{complement_check, fix_header, from_elf, Error, LOGO, MAX_SIZE, PT_LOAD};
+    use crate::elf;
+    use alloc::{vec, vec::Vec};
+
+    /// The address of the ROM.
+    const ROM: u32 = 0x0800_0000;
+    /// The address of internal work RAM.
+    const IWRAM: u32 = 0x0300_0000;
+
+    /// A segment to be written into an ELF file.
+    struct Segment {
+        kind: u32,
+        address: u32,
+        contents: Vec<u8>,
+    }
+
+    fn load(address: u32, contents: &[u8]) -> Segment {
+        Segment {
+            kind: PT_LOAD,
+            address,
+            contents: contents.to_vec(),
+        }
+    }
+
+    /// Writes a minimal 32-bit little-endian ELF file containing the given segments.
+    fn elf(segments: Vec<Segment>) -> Vec<u8> {
+        let mut bytes = vec![0; 52];
+        bytes[..6].copy_from_slice(&[0x7F, b'E', b'L', b'F', 1, 1]);
+        let program_headers = bytes.len();
+        bytes.extend(vec![0; 32 * segments.len()]);
+        for (index, segment) in segments.iter().enumerate() {
+            let offset = bytes.len() as u32;
+            bytes.extend(&segment.contents);
+            let header = program_headers + index * 32;
+            for (field, value) in [
+                segment.kind,
+                offset,
+                segment.address,
+                segment.address,
+                segment.contents.len() as u32,
+                segment.contents.len() as u32,
+            ]
+            .into_iter()
+            .enumerate()
+            {
+                bytes[header + field * 4..header + field * 4 + 4]
+                    .copy_from_slice(&value.to_le_bytes());
+            }
+        }
+        bytes[0x1C..0x20].copy_from_slice(&(program_headers as u32).to_le_bytes());
+        bytes[0x2A..0x2C].copy_from_slice(&32u16.to_le_bytes());
+        bytes[0x2C..0x2E].copy_from_slice(&(segments.len() as u16).to_le_bytes());
+        bytes
+    }
+
+    /// Returns a cartridge header with the given title and complement check, as written by `gbafix`.
+    fn header(title: &[u8], complement: u8) -> Vec<u8> {
+        let mut header = vec![0; 0xC0];
+        header[..4].copy_from_slice(&[0x2E, 0x00, 0x00, 0xEA]);
+        header[4..0xA0].copy_from_slice(&LOGO);
+        header[0xA0..0xA0 + title.len()].copy_from_slice(title);
+        header[0xB2] = 0x96;
+        header[0xBD] = complement;
+        header
+    }
+
+    #[test]
+    fn complement_check_blank() {
+        assert_eq!(complement_check(&header(b"", 0)), 0x51);
+    }
+
+    #[test]
+    fn complement_check_title() {
+        assert_eq!(complement_check(&header(b"GBA_TEST", 0)), 0xE8);
+    }
+
+    #[test]
+    fn fix_header_blank() {
+        let mut rom = vec![0; 0xC0];
+        rom[..4].copy_from_slice(&[0x2E, 0x00, 0x00, 0xEA]);
+        fix_header(&mut rom);
+
+        assert_eq!(rom, header(b"", 0x51));
+    }
+
+    #[test]
+    fn fix_header_keeps_title() {
+        let mut rom = vec![0; 0xC0];
+        rom[..4].copy_from_slice(&[0x2E, 0x00, 0x00, 0xEA]);
+        rom[0xA0..0xA8].copy_from_slice(b"GBA_TEST");
+        fix_header(&mut rom);
+
+        assert_eq!(rom, header(b"GBA_TEST", 0xE8));
+    }
+
+    #[test]
+    fn from_elf_pads_to_power_of_two() {
+        let mut code = vec![0; 0xC0];
+        code[..4].copy_from_slice(&[0x2E, 0x00, 0x00, 0xEA]);
+        code.extend([1; 0x50]);
+        let rom = from_elf(&elf(vec![load(ROM, &code)])).unwrap();
+
+        assert_eq!(rom.len(), 0x200);
+        assert_eq!(rom[..0xC0], header(b"", 0x51));
+        assert_eq!(rom[0xC0..0x110], [1; 0x50]);
+        assert_eq!(rom[0x110..], [0xFF; 0xF0]);
+    }
+
+    #[test]
+    fn from_elf_fills_gaps() {
+        let rom = from_elf(&elf(vec![load(ROM, &[0; 0xC0]), load(ROM + 0xC4, &[1, 2])])).unwrap();
+
+        assert_eq!(rom.len(), 0x100);
+        assert_eq!(rom[0xC0..0xC8], [0xFF, 0xFF, 0xFF, 0xFF, 1, 2, 0xFF, 0xFF]);
+    }
+
+    #[test]
+    fn from_elf_minimum_size() {
+        let rom = from_elf(&elf(vec![load(ROM, &[0x2E, 0x00, 0x00, 0xEA])])).unwrap();
+
+        assert_eq!(rom.len(), 0x100);
+        assert_eq!(rom[..4], [0x2E, 0x00, 0x00, 0xEA]);
+        assert_eq!(rom[4..0xA0], LOGO);
+        assert_eq!(rom[0xB2], 0x96);
+        assert_eq!(rom[0xBD], complement_check(&rom));
+    }
+
+    #[test]
+    fn from_elf_skips_other_segments() {
+        let rom = from_elf(&elf(vec![
+            load(ROM, &[0; 0xC0]),
+            Segment {
+                kind: 0x6474_E551,
+                address: 0,
+                contents: vec![1; 4],
+            },
+            load(IWRAM, &[]),
+        ]))
+        .unwrap();
+
+        assert_eq!(rom.len(), 0x100);
+    }
+
+    #[test]
+    fn from_elf_outside_rom() {
+        assert_eq!(
+            from_elf(&elf(vec![load(ROM, &[0; 0xC0]), load(IWRAM, &[1])])),
+            Err(Error::OutsideRom { address: IWRAM })
+        );
+    }
+
+    #[test]
+    fn from_elf_too_large() {
+        let address = ROM + MAX_SIZE as u32 - 2;
+        assert_eq!(
+            from_elf(&elf(vec![load(address, &[1, 2, 3, 4])])),
+            Err(Error::TooLarge {
+                size: MAX_SIZE as u64 + 2
+            })
+        );
+    }
+
+    #[test]
+    fn from_elf_not_elf() {
+        assert_eq!(
+            from_elf(b"not an elf file"),
+            Err(Error::Elf(elf::Error::NotElf))
+        );
+    }
+
+    #[test]
+    fn from_elf_truncated() {
+        let bytes = elf(vec![load(ROM, &[0; 0xC0])]);
+
+        assert_eq!(
+            from_elf(&bytes[..0x60]),
+            Err(Error::Elf(elf::Error::Malformed))
+        );
+    }
+}
@@ -4,87 +4,422 @@
 //! code here should only ever be run on a Game Boy Advance, and the safety considerations do not
 //! apply for other targets.
 
-use crate::{display::SerializeDisplay, flavors::Sram, Ignore, Outcome, TestCase, Trial};
-use core::{fmt::Display, panic::PanicInfo, ptr};
-use serde::Serialize;
+#[cfg(feature = "io-diff")]
+use crate::io_diff::{self, Diff};
+#[cfg(feature = "log-capture")]
+use crate::log_capture;
+#[cfg(feature = "shuffle")]
+use crate::shuffle::{self, Permutation};
+use crate::{
+    bios::RegisterRamReset,
+    buffer::Buffer,
+    contains::contains,
+    failed_names, hardware, mgba_log, order, output,
+    report_method::ReportMethod,
+    reporter::{Reporter, SramReporter},
+    truncate::{Truncated, DEFAULT_MESSAGE_LIMIT},
+    watchdog, Capability, Environment, ExitCode, Location, Outcome, ShouldErr, ShouldPanic,
+    Summary, TestCase, Trial,
+};
+#[cfg(not(feature = "shuffle"))]
+use core::ops::Range;
+use core::{
+    fmt,
+    fmt::{Display, Formatter, Write},
+    panic::PanicInfo,
+    ptr,
+};
 use voladdress::{Safe, Unsafe, VolAddress};
 
-/// The current write position in SRAM.
-///
-/// This value begins at 1 byte past the start of SRAM, as the first byte is reserved for the
-/// `Result` variant. The `Result` variant is not written until the tests have finished, which
-/// signals that the written data is now valid `postcard` data.
-static mut SRAM_POS: *mut u8 = 0x0E00_0001 as *mut u8;
-/// The start of the SRAM.
-const SRAM_START: *mut u8 = 0x0E00_0000 as *mut u8;
-
 /// Wait state for interfacing with the GBA Cartridge.
 ///
 /// This must be properly configured prior to interacting with the cartridge. Otherwise, garbage
 /// data may be read/written.
 const WAITCNT: VolAddress<u16, Safe, Unsafe> = unsafe { VolAddress::new(0x0400_0204) };
 
-/// The remaining tests to be run.
+/// All tests being run.
 static mut TESTS: &[&dyn TestCase] = &[];
+/// The positions in the sorted order of the remaining tests to be run, in the order they will be
+/// run.
+#[cfg(not(feature = "shuffle"))]
+static mut ORDER: Range<usize> = 0..0;
+/// The positions in the sorted order of the remaining tests to be run, in the order they will be
+/// run.
+#[cfg(feature = "shuffle")]
+static mut ORDER: Permutation = Permutation::empty();
+/// The index into `TESTS` of the current test.
+static mut CURRENT: usize = 0;
+/// The position of the current test in the sorted order of `TESTS`.
+static mut CURRENT_POSITION: usize = 0;
+/// The number of tests that have been started, including the current test.
+static mut STARTED: usize = 0;
+/// The iteration of the current test being run, starting from `1`.
+///
+/// This is `0` when no test is in progress.
+static mut ITERATION: u32 = 0;
 /// The name of the current test.
 static mut TEST_NAME: &str = "";
+/// Whether the current test is expected to panic.
+static mut SHOULD_PANIC: ShouldPanic = ShouldPanic::No;
+/// Whether the current test is expected to return an `Err`.
+static mut SHOULD_ERR: ShouldErr = ShouldErr::No;
+/// Whether any tests in `TESTS` share the same name.
+static mut DUPLICATE_NAMES: bool = false;
+/// Storage for the name of the current test when it must be disambiguated from other tests with
+/// the same name.
+static mut DISAMBIGUATED_NAME: Buffer<256> = Buffer::new();
+/// Whether the reset executed before each test has been configured.
+///
+/// Statics are not guaranteed to be initialized to non-zero values before the runner is called, so
+/// this flag is used to determine whether `REGISTER_RAM_RESET` holds a valid value. If it is
+/// `false`, the default reset is used.
+static mut REGISTER_RAM_RESET_CONFIGURED: bool = false;
+/// The reset executed before each test, if configured.
+static mut REGISTER_RAM_RESET: RegisterRamReset = RegisterRamReset::new();
+/// Whether the watchdog timeout has been configured.
+///
+/// If this is `false`, the default timeout is used.
+static mut TIMEOUT_CONFIGURED: bool = false;
+/// The number of frames a test may run for before being failed by the watchdog, if configured.
+static mut TIMEOUT: u32 = 0;
+/// Whether the failure message limit has been configured.
+///
+/// If this is `false`, the default limit is used.
+static mut MESSAGE_LIMIT_CONFIGURED: bool = false;
+/// The maximum number of bytes of a failure message that are reported, if configured.
+static mut MESSAGE_LIMIT: usize = 0;
+/// Whether the number of times each test is run has been configured.
+///
+/// If this is `false`, each test is run once.
+static mut REPEAT_COUNT_CONFIGURED: bool = false;
+/// The number of times each test is run, if configured.
+static mut REPEAT_COUNT: u32 = 0;
+/// Whether the method used to report the exit code has been configured.
+///
+/// If this is `false`, [`ReportMethod::MgbaSwi`] is used.
+static mut REPORT_METHOD_CONFIGURED: bool = false;
+/// The method used to report the exit code, if configured.
+static mut REPORT_METHOD: ReportMethod = ReportMethod::MgbaSwi;
+/// A function run once before the first test, if configured.
+static mut GLOBAL_SETUP: Option<fn()> = None;
+/// Whether tests are listed instead of being run.
+static mut LIST_ONLY: bool = false;
+/// What the runner is currently doing, determining how a panic is handled.
+static mut PHASE: Phase = Phase::Framework;
+/// The number of panics within the runner itself since a test was last run.
+static mut FRAMEWORK_PANICS: u32 = 0;
+/// A function formatting the messages of failed tests from their panics, if configured.
+static mut FAILURE_FORMATTER: Option<FailureFormatter> = None;
+/// The reporter receiving the test results.
+static mut REPORTER: Option<&'static mut dyn Reporter> = None;
+/// The default reporter, used by [`runner()`].
+static mut SRAM_REPORTER: SramReporter = SramReporter::new();
+/// The tally of test outcomes so far.
+static mut SUMMARY: Summary = Summary {
+    passed: 0,
+    failed: 0,
+    ignored: 0,
+    timed_out: 0,
+    seed: None,
+    environment: Environment::HardwareOrUnknown,
+};
 
-/// Write data at the beginning of SRAM.
+/// The number of consecutive panics within the runner itself that are recovered from.
 ///
-/// This will overwrite whatever data is already written there.
-fn write_to_sram<T>(value: T) -> Result<(), postcard::Error>
-where
-    T: Serialize,
-{
-    postcard::serialize_with_flavor(&value, unsafe { Sram::new(SRAM_START) })?;
-    Ok(())
+/// Once exceeded, the test run is aborted, so that a persistent bug can't cause an endless loop.
+const MAX_FRAMEWORK_PANICS: u32 = 3;
+
+/// What the runner is currently doing.
+#[derive(Clone, Copy)]
+enum Phase {
+    /// The runner's own bookkeeping, such as before or between tests.
+    Framework,
+    /// The global setup function.
+    GlobalSetup,
+    /// A test.
+    Test,
+    /// Aborting the test run.
+    Aborting,
+}
+
+/// A function writing the failure message of a test from its panic.
+///
+/// This is configured using [`set_failure_formatter()`].
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(all(feature = "runner", target = "thumbv4t-none-eabi")))
+)]
+pub type FailureFormatter = fn(&PanicInfo, &mut dyn Write) -> fmt::Result;
+
+/// A writer discarding everything written to it.
+struct Discard;
+
+impl Write for Discard {
+    fn write_str(&mut self, _s: &str) -> fmt::Result {
+        Ok(())
+    }
 }
 
-/// Write data to the end of SRAM.
+/// The failure message of a panicking test.
 ///
-/// This increments the current SRAM position, ensuring data is not overwritten on future calls.
-fn append_to_sram<T>(value: T) -> Result<(), postcard::Error>
-where
-    T: Serialize,
-{
-    // SAFETY: `SRAM_POS` is guaranteed to be less than or equal to `SRAM_END`, and therefore will
-    // point to a valid position in SRAM.
-    let new_position = postcard::serialize_with_flavor(&value, unsafe { Sram::new(SRAM_POS) })?;
-    // SAFETY: `SRAM_POS` is only ever accessed on the main thread.
+/// This is formatted using the configured failure formatter, if there is one.
+struct PanicMessage<'a>(&'a PanicInfo<'a>);
+
+impl Display for PanicMessage<'_> {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        // SAFETY: `FAILURE_FORMATTER` is only ever accessed on the main thread.
+        match unsafe { FAILURE_FORMATTER } {
+            // The formatter is first run with its output discarded, so that if it fails, the
+            // default message can be used instead before anything has been written.
+            Some(format) if format(self.0, &mut Discard).is_ok() => format(self.0, formatter),
+            _ => self.0.fmt(formatter),
+        }
+    }
+}
+
+/// A failure message annotated with the iteration it occurred on.
+struct IterationFailure<'a> {
+    iteration: u32,
+    count: u32,
+    message: &'a dyn Display,
+}
+
+impl Display for IterationFailure<'_> {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "failed on iteration {} of {}: {}",
+            self.iteration, self.count, self.message
+        )
+    }
+}
+
+/// A failure message followed by the IO registers changed while running the test.
+#[cfg(feature = "io-diff")]
+struct WithIoDiff<'a> {
+    message: &'a dyn Display,
+    diff: Diff<'a>,
+}
+
+#[cfg(feature = "io-diff")]
+impl Display for WithIoDiff<'_> {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        self.message.fmt(formatter)?;
+        if !self.diff.is_empty() {
+            write!(formatter, "\n--- changed registers ---\n{}", self.diff)?;
+        }
+        Ok(())
+    }
+}
+
+/// A failure message followed by the log lines captured while running the test.
+#[cfg(feature = "log-capture")]
+struct WithLog<'a> {
+    message: &'a dyn Display,
+    log: &'a str,
+}
+
+#[cfg(feature = "log-capture")]
+impl Display for WithLog<'_> {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        self.message.fmt(formatter)?;
+        if !self.log.is_empty() {
+            write!(
+                formatter,
+                "\n--- captured log ---\n{}",
+                self.log.trim_end_matches('\n')
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Passes the result of the current test to the reporter, ending the test.
+fn report_test_result(outcome: Outcome<&dyn Display>) {
+    // SAFETY: `SUMMARY` is only ever accessed on the main thread.
+    let summary = unsafe { &mut *ptr::addr_of_mut!(SUMMARY) };
+    match outcome {
+        Outcome::Passed => summary.passed += 1,
+        Outcome::Failed { .. } => {
+            summary.failed += 1;
+            // SAFETY: `TEST_NAME` is only ever accessed on the main thread.
+            failed_names::record(unsafe { TEST_NAME });
+        }
+        Outcome::Ignored => summary.ignored += 1,
+    }
+
+    let annotated;
+    let truncated;
+    #[cfg(feature = "io-diff")]
+    let with_io_diff;
+    #[cfg(feature = "log-capture")]
+    let with_log;
+    let outcome = match outcome {
+        Outcome::Failed { message } => {
+            let count = repeat_count();
+            let message = if count > 1 {
+                annotated = IterationFailure {
+                    // SAFETY: `ITERATION` is only ever accessed on the main thread.
+                    iteration: unsafe { ITERATION },
+                    count,
+                    message,
+                };
+                &annotated as &dyn Display
+            } else {
+                message
+            };
+            truncated = Truncated {
+                value: message,
+                limit: message_limit(),
+            };
+            let message = &truncated as &dyn Display;
+            // The changed registers and captured log are bounded by their own sizes, so they are
+            // not counted against the message limit. This keeps them from being cut off.
+            #[cfg(feature = "io-diff")]
+            let message = {
+                with_io_diff = WithIoDiff {
+                    message,
+                    diff: io_diff::diff(),
+                };
+                &with_io_diff as &dyn Display
+            };
+            #[cfg(feature = "log-capture")]
+            let message = {
+                with_log = WithLog {
+                    message,
+                    log: log_capture::captured(),
+                };
+                &with_log as &dyn Display
+            };
+            Outcome::Failed { message }
+        }
+        outcome => outcome,
+    };
+
+    // SAFETY: `TESTS` and `CURRENT` are only ever accessed on the main thread, and `CURRENT` is
+    // always a valid index into `TESTS` while a test is in progress.
+    let test = unsafe { TESTS[CURRENT] };
+    let location = test.location();
+
+    // SAFETY: `REPORTER` and `TEST_NAME` are only ever accessed on the main thread.
+    if let Some(reporter) = unsafe { (*ptr::addr_of_mut!(REPORTER)).as_deref_mut() } {
+        reporter.report(&Trial {
+            name: unsafe { TEST_NAME },
+            outcome,
+            output: output::captured(),
+            location: (location != Location::UNKNOWN).then_some(location),
+            suite: test.suite(),
+        });
+    }
+
+    // SAFETY: `ITERATION` is only ever accessed on the main thread.
     unsafe {
-        SRAM_POS = new_position;
+        ITERATION = 0;
     }
-    Ok(())
 }
 
-/// Handle an error that occurred during test execution.
+/// Records that the current iteration of the current test passed.
 ///
-/// We can't panic in this context, as that would cause the code to loop until the stack overflows.
-/// Instead, this function attempts to write the error to SRAM.
-fn handle_error<E>(error: E)
-where
-    E: Display,
-{
-    // If writing to SRAM fails here, there is not much else that can be done. Unwrapping the
-    // result would lead to a panic loop, causing a stack overflow, so we simply ignore the error
-    // if there is one.
-    #[allow(unused_must_use)]
-    {
-        write_to_sram(Result::<(), _>::Err(SerializeDisplay(error)));
+/// The test is reported as passed once every iteration has passed. Otherwise, the next call to
+/// `run_tests()` runs the next iteration.
+fn pass_iteration() {
+    // SAFETY: `ITERATION` is only ever accessed on the main thread.
+    if unsafe { ITERATION } >= repeat_count() {
+        report_test_result(Outcome::Passed);
     }
 }
 
-/// Saves the serialized test result to SRAM.
-fn report_test_result<FailedMessage>(outcome: Outcome<FailedMessage>)
-where
-    FailedMessage: Copy + Display,
-{
-    append_to_sram(Trial {
-        // SAFETY: `TEST_NAME` is only ever accessed on the main thread.
-        name: unsafe { TEST_NAME },
-        outcome,
+/// Returns whether any of the given tests share the same name.
+fn has_duplicate_names(tests: &[&dyn TestCase]) -> bool {
+    tests.iter().enumerate().any(|(index, test)| {
+        tests[..index]
+            .iter()
+            .any(|other| other.name() == test.name())
     })
-    .unwrap_or_else(handle_error);
+}
+
+/// Returns the name to report for the test at `position` in the sorted order.
+///
+/// If tests earlier in the sorted order share the same name, a suffix of the form `#n` is
+/// appended, where `n` is the test's position among the tests with that name. This keeps every
+/// reported name unique, regardless of the order the tests are run in.
+fn test_name(tests: &'static [&'static dyn TestCase], position: usize) -> &'static str {
+    let name = tests[order::index(position)].name();
+    // SAFETY: `DUPLICATE_NAMES` is only ever accessed on the main thread.
+    if !unsafe { DUPLICATE_NAMES } {
+        return name;
+    }
+    let occurrence = (0..position)
+        .filter(|&other| tests[order::index(other)].name() == name)
+        .count()
+        + 1;
+    if occurrence == 1 {
+        return name;
+    }
+
+    // SAFETY: `DISAMBIGUATED_NAME` is only ever accessed on the main thread, and the previous
+    // test's name is no longer in use.
+    let buffer = unsafe { &mut *ptr::addr_of_mut!(DISAMBIGUATED_NAME) };
+    buffer.clear();
+    // Writing to the buffer never fails.
+    let _ = write!(buffer, "{}#{}", name, occurrence);
+    buffer.as_str()
+}
+
+/// Returns the number of frames a test may run for before being failed by the watchdog.
+fn timeout() -> u32 {
+    // SAFETY: `TIMEOUT_CONFIGURED` and `TIMEOUT` are only ever accessed on the main thread.
+    unsafe {
+        if TIMEOUT_CONFIGURED {
+            TIMEOUT
+        } else {
+            watchdog::DEFAULT_TIMEOUT
+        }
+    }
+}
+
+/// Returns the maximum number of bytes of a failure message that are reported.
+fn message_limit() -> usize {
+    // SAFETY: `MESSAGE_LIMIT_CONFIGURED` and `MESSAGE_LIMIT` are only ever accessed on the main
+    // thread.
+    unsafe {
+        if MESSAGE_LIMIT_CONFIGURED {
+            MESSAGE_LIMIT
+        } else {
+            DEFAULT_MESSAGE_LIMIT
+        }
+    }
+}
+
+/// Returns the number of times each test is run.
+fn repeat_count() -> u32 {
+    // SAFETY: `REPEAT_COUNT_CONFIGURED` and `REPEAT_COUNT` are only ever accessed on the main
+    // thread.
+    unsafe {
+        if REPEAT_COUNT_CONFIGURED {
+            REPEAT_COUNT
+        } else {
+            1
+        }
+    }
+}
+
+/// Logs the name of a test, along with its location if known, in list-only mode.
+fn log_listed(name: &str, test: &dyn TestCase) {
+    let location = test.location();
+    if location == Location::UNKNOWN {
+        mgba_log::info(format_args!("gba_test: listed {}", name));
+    } else {
+        mgba_log::info(format_args!("gba_test: listed {} at {}", name, location));
+    }
+}
+
+/// Logs that a test was skipped, as the capability it requires was not detected.
+fn log_missing_capability(name: &str, capability: Capability) {
+    mgba_log::info(format_args!(
+        "gba_test: skipped {}: missing capability: {}",
+        name, capability
+    ));
 }
 
 /// Runs the remaining tests.
@@ -92,59 +427,682 @@ where
 /// The current test being executed is tracked using global state. This allows the runner to
 /// recover when a test panics.
 fn run_tests() -> ! {
-    // SAFETY: `TESTS` is only ever mutated on the main thread.
-    while let Some((test, tests)) = unsafe { TESTS }.split_first() {
-        // SAFETY: `TESTS` and `TEST_NAME` are only ever mutated on the main thread.
+    loop {
+        // SAFETY: `ITERATION`, `ORDER`, `CURRENT`, `CURRENT_POSITION`, `TESTS`, `TEST_NAME`,
+        // `SHOULD_PANIC`, `SHOULD_ERR`, and `LIST_ONLY` are only ever accessed on the main thread.
         unsafe {
-            TESTS = tests;
-            TEST_NAME = test.name();
+            // Move on to the next test, unless the current one has iterations remaining.
+            if ITERATION == 0 {
+                let Some(position) = (*ptr::addr_of_mut!(ORDER)).next() else {
+                    break;
+                };
+                let index = order::index(position);
+                CURRENT = index;
+                CURRENT_POSITION = position;
+                STARTED += 1;
+                TEST_NAME = test_name(TESTS, position);
+                SHOULD_PANIC = TESTS[index].should_panic();
+                SHOULD_ERR = TESTS[index].should_err();
+                if LIST_ONLY {
+                    log_listed(TEST_NAME, TESTS[index]);
+                }
+                if LIST_ONLY || TESTS[index].ignore().is_ignored() {
+                    output::clear();
+                    #[cfg(feature = "log-capture")]
+                    log_capture::clear();
+                    report_test_result(Outcome::Ignored);
+                    continue;
+                }
+                if let Some(capability) = TESTS[index]
+                    .requires()
+                    .filter(|capability| !hardware::is_available(*capability))
+                {
+                    output::clear();
+                    #[cfg(feature = "log-capture")]
+                    log_capture::clear();
+                    log_missing_capability(TEST_NAME, capability);
+                    report_test_result(Outcome::Ignored);
+                    continue;
+                }
+            }
+            ITERATION += 1;
         }
+        // SAFETY: `TESTS` is only ever mutated on the main thread, and `CURRENT` is always a valid
+        // index into it.
+        let test = unsafe { TESTS[CURRENT] };
+        output::clear();
+        #[cfg(feature = "log-capture")]
+        log_capture::clear();
 
-        match test.ignore() {
-            Ignore::No => {
-                test.run();
-                report_test_result(Outcome::<&str>::Passed);
+        // SAFETY: `REGISTER_RAM_RESET_CONFIGURED`, `REGISTER_RAM_RESET`, and `WAITCNT` are only
+        // ever accessed on the main thread.
+        unsafe {
+            if REGISTER_RAM_RESET_CONFIGURED {
+                REGISTER_RAM_RESET.execute();
+            } else {
+                RegisterRamReset::DEFAULT.execute();
             }
-            Ignore::Yes => report_test_result(Outcome::<&str>::Ignored),
+            // The reset may have cleared the wait state.
+            WAITCNT.write(3);
+        }
+        // Frames are still counted when timeouts are disabled, for the test's frame budget and
+        // for `frames()`.
+        let frames = match timeout() {
+            0 => u32::MAX,
+            timeout => timeout,
+        };
+        watchdog::arm(frames);
+        // The snapshot is taken after the watchdog is started, so that its changes to the
+        // interrupt registers are not reported.
+        #[cfg(feature = "io-diff")]
+        io_diff::snapshot();
+        // SAFETY: `FRAMEWORK_PANICS` and `PHASE` are only ever accessed on the main thread.
+        unsafe {
+            FRAMEWORK_PANICS = 0;
+            PHASE = Phase::Test;
+        }
+        test.run();
+        // SAFETY: `PHASE` is only ever accessed on the main thread.
+        unsafe {
+            PHASE = Phase::Framework;
+        }
+        let elapsed = watchdog::disarm();
+        if test.should_panic().expects_panic() {
+            report_test_result(Outcome::Failed {
+                message: &"test did not panic as expected",
+            })
+        } else {
+            pass_within_budget(test, elapsed);
+        }
+    }
+
+    // SAFETY: `REPORTER` and `SUMMARY` are only ever accessed on the main thread.
+    unsafe {
+        if let Some(reporter) = (*ptr::addr_of_mut!(REPORTER)).as_deref_mut() {
+            reporter.finish(&*ptr::addr_of!(SUMMARY));
         }
     }
 
-    write_to_sram(Ok::<(), ()>(())).unwrap_or_else(handle_error);
+    // SAFETY: `SUMMARY` is only ever accessed on the main thread.
+    let summary = unsafe { &*ptr::addr_of!(SUMMARY) };
+    mgba_log::info(format_args!(
+        "gba_test: result={}; passed={}; failed={}; ignored={}; total={}; duration_frames={}",
+        if summary.failed == 0 { "ok" } else { "failed" },
+        summary.passed,
+        summary.failed,
+        summary.ignored,
+        summary.passed + summary.failed + summary.ignored,
+        watchdog::elapsed(),
+    ));
+
+    halt(ExitCode::from_summary(summary))
+}
 
+/// Halts, reporting the exit code using the configured report method.
+fn halt(exit_code: ExitCode) -> ! {
+    // SAFETY: `REPORT_METHOD_CONFIGURED` and `REPORT_METHOD` are only ever accessed on the main
+    // thread.
+    let report_method = unsafe {
+        if REPORT_METHOD_CONFIGURED {
+            REPORT_METHOD
+        } else {
+            ReportMethod::MgbaSwi
+        }
+    };
+    report_method.report(exit_code)
+}
+
+/// Aborts the test run, reporting the given message.
+fn abort(message: &dyn Display) -> ! {
+    // SAFETY: `PHASE` and `REPORTER` are only ever accessed on the main thread.
     unsafe {
-        core::arch::asm!("swi #0x03",);
+        PHASE = Phase::Aborting;
+        if let Some(reporter) = (*ptr::addr_of_mut!(REPORTER)).as_deref_mut() {
+            reporter.abort(message);
+        }
     }
-    loop {}
+    halt(ExitCode::FrameworkPanic)
+}
+
+/// Recovers from a panic within the runner itself.
+///
+/// If a test is in progress, it is failed and the remaining tests are run. Otherwise, or if the
+/// runner has panicked too many times in a row, the test run is aborted.
+fn framework_panic(info: &PanicInfo) -> ! {
+    // SAFETY: `FRAMEWORK_PANICS` is only ever accessed on the main thread.
+    let panics = unsafe {
+        FRAMEWORK_PANICS += 1;
+        FRAMEWORK_PANICS
+    };
+    let message = format_args!("framework error: {}", info);
+    if panics > MAX_FRAMEWORK_PANICS || current_test_index().is_none() {
+        abort(&message)
+    }
+    report_test_result(Outcome::Failed { message: &message });
+    run_tests()
 }
 
 /// Defines a panic handler for running tests.
 ///
 /// This panic handler is configured to continue execution after a panic, allowing tests to
 /// continue being run after the current test panics.
+/// Passes the current iteration of `test`, unless it took more than its frame budget.
+fn pass_within_budget(test: &dyn TestCase, elapsed: u32) {
+    match test.max_frames() {
+        Some(max_frames) if elapsed > max_frames => report_test_result(Outcome::Failed {
+            message: &format_args!("took {} frames, budget {}", elapsed, max_frames),
+        }),
+        _ => pass_iteration(),
+    }
+}
+
 #[panic_handler]
 fn panic(info: &PanicInfo) -> ! {
-    report_test_result(Outcome::Failed { message: info });
+    let elapsed = watchdog::disarm();
+    // SAFETY: `PHASE` is only ever accessed on the main thread.
+    let phase = unsafe { PHASE };
+    // SAFETY: `PHASE` is only ever accessed on the main thread.
+    unsafe {
+        PHASE = Phase::Framework;
+    }
+    match phase {
+        Phase::Framework => framework_panic(info),
+        Phase::GlobalSetup => abort(&format_args!("global setup failed: {}", info)),
+        Phase::Test => {}
+        // Reporting the abort failed. There is nothing left to report to.
+        Phase::Aborting => halt(ExitCode::FrameworkPanic),
+    }
+    // SAFETY: `SHOULD_PANIC` is only ever accessed on the main thread.
+    let should_panic = unsafe { SHOULD_PANIC };
+    if !should_panic.expects_panic() {
+        report_test_result(Outcome::Failed {
+            message: &PanicMessage(info),
+        });
+        run_tests()
+    }
+    if let Some(expected) = should_panic
+        .expected_message()
+        .filter(|expected| !contains(info, expected))
+    {
+        report_test_result(Outcome::Failed {
+            message: &format_args!(
+                "panic message: \"{}\", expected substring: \"{}\"",
+                PanicMessage(info),
+                expected
+            ),
+        })
+    } else if let Some(file_substring) = should_panic.expected_location().filter(|file_substring| {
+        !info
+            .location()
+            .is_some_and(|location| location.file().contains(file_substring))
+    }) {
+        match info.location() {
+            Some(location) => report_test_result(Outcome::Failed {
+                message: &format_args!(
+                    "panic location: \"{}\", expected file substring: \"{}\"",
+                    location, file_substring
+                ),
+            }),
+            None => report_test_result(Outcome::Failed {
+                message: &format_args!(
+                    "panic location unknown, expected file substring: \"{}\"",
+                    file_substring
+                ),
+            }),
+        }
+    } else {
+        // SAFETY: `TESTS` is only ever mutated on the main thread, and `CURRENT` is always a valid
+        // index into it.
+        pass_within_budget(unsafe { TESTS[CURRENT] }, elapsed)
+    }
     run_tests()
 }
 
+/// Fails the current test with the given message, continuing with the remaining tests.
+///
+/// This is used to report failures returned by tests through [`Termination`].
+///
+/// [`Termination`]: crate::Termination
+pub(crate) fn fail(message: &dyn Display) -> ! {
+    watchdog::disarm();
+    // SAFETY: `PHASE` is only ever accessed on the main thread.
+    unsafe {
+        PHASE = Phase::Framework;
+    }
+    report_test_result(Outcome::Failed { message });
+    run_tests()
+}
+
+/// Skips the current test with the given reason, continuing with the remaining tests.
+///
+/// This is used by [`skip!`]. The test is reported as ignored, even if it was expected to panic,
+/// and the reason is written to mGBA's log.
+///
+/// [`skip!`]: crate::skip!
+pub(crate) fn skip(reason: &dyn Display) -> ! {
+    watchdog::disarm();
+    // SAFETY: `PHASE` is only ever accessed on the main thread.
+    let phase = unsafe { PHASE };
+    // SAFETY: `PHASE` is only ever accessed on the main thread.
+    unsafe {
+        PHASE = Phase::Framework;
+    }
+    if !matches!(phase, Phase::Test) {
+        panic!("`skip!()` can only be used within a test");
+    }
+    // SAFETY: `TEST_NAME` is only ever accessed on the main thread.
+    mgba_log::info(format_args!(
+        "gba_test: skipped {}: {}",
+        unsafe { TEST_NAME },
+        reason
+    ));
+    report_test_result(Outcome::Ignored);
+    run_tests()
+}
+
+/// Returns whether the current test is expected to return an `Err`.
+pub(crate) fn should_err() -> ShouldErr {
+    // SAFETY: `SHOULD_ERR` is only ever accessed on the main thread.
+    unsafe { SHOULD_ERR }
+}
+
+/// Fails the current test after the watchdog times out, continuing with the remaining tests.
+///
+/// This is called by the watchdog's interrupt handler, after it has returned the CPU to System
+/// mode.
+pub(crate) extern "C" fn watchdog_expired() -> ! {
+    // The timer has already been stopped, but the frames the test ran for still need to be
+    // counted.
+    watchdog::disarm();
+    // SAFETY: `PHASE` is only ever accessed on the main thread.
+    unsafe {
+        PHASE = Phase::Framework;
+    }
+    // SAFETY: `SUMMARY` is only ever accessed on the main thread.
+    unsafe {
+        (*ptr::addr_of_mut!(SUMMARY)).timed_out += 1;
+    }
+    report_test_result(Outcome::Failed {
+        message: &format_args!("test timed out after {} frames", timeout()),
+    });
+    run_tests()
+}
+
+/// Configures the memory and registers cleared before each test is run.
+///
+/// By default, everything except work RAM is cleared, preventing one test's use of the hardware
+/// from affecting the next. The `no-vram-clear` feature leaves VRAM intact, and the
+/// `preserve-audio` feature leaves the sound, timer, DMA, and interrupt registers intact so that
+/// audio driven by them keeps playing. This must be called before the test runner is started, such as in
+/// `main()` before calling the test harness.
+///
+/// If external work RAM is cleared, the state the runner keeps there, such as the list of failed
+/// test names, is left intact.
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(all(feature = "runner", target = "thumbv4t-none-eabi")))
+)]
+pub fn set_register_ram_reset(reset: RegisterRamReset) {
+    // SAFETY: `REGISTER_RAM_RESET_CONFIGURED` and `REGISTER_RAM_RESET` are only ever accessed on
+    // the main thread.
+    unsafe {
+        REGISTER_RAM_RESET = reset;
+        REGISTER_RAM_RESET_CONFIGURED = true;
+    }
+}
+
+/// Configures the number of frames a test may run for before it is failed.
+///
+/// A watchdog fails any test that runs for longer than this, continuing on with the remaining
+/// tests. By default, tests may run for 1800 frames, which is roughly 30 seconds. Setting this to
+/// `0` disables the timeout, although the watchdog still counts frames for [`frames()`]. This
+/// must be called before the test runner is started, such as in `main()` before calling the test
+/// harness.
+///
+/// The watchdog is driven by timer 1's overflow interrupt, and installs its own interrupt handler
+/// before each test. It can't fire while a test has interrupts disabled, or after a test has
+/// installed a different interrupt handler.
+///
+/// [`frames()`]: crate::frames()
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(all(feature = "runner", target = "thumbv4t-none-eabi")))
+)]
+pub fn set_default_timeout(frames: u32) {
+    // SAFETY: `TIMEOUT_CONFIGURED` and `TIMEOUT` are only ever accessed on the main thread.
+    unsafe {
+        TIMEOUT = frames;
+        TIMEOUT_CONFIGURED = true;
+    }
+}
+
+/// Configures the number of times each test is run.
+///
+/// Running tests repeatedly helps to find flaky tests, such as those depending on hardware timing.
+/// A test passes only if every iteration passes. Otherwise, it is reported as failed as soon as an
+/// iteration fails, with the message prefixed by `failed on iteration k of n: `. Ignored tests are
+/// not run at all. By default, each test is run once, and a count of `0` is treated as `1`. This
+/// must be called before the test runner is started, such as in `main()` before calling the test
+/// harness.
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(all(feature = "runner", target = "thumbv4t-none-eabi")))
+)]
+pub fn set_repeat_count(count: u32) {
+    // SAFETY: `REPEAT_COUNT_CONFIGURED` and `REPEAT_COUNT` are only ever accessed on the main
+    // thread.
+    unsafe {
+        REPEAT_COUNT = count.max(1);
+        REPEAT_COUNT_CONFIGURED = true;
+    }
+}
+
+/// Configures how the exit code is reported once all tests have been run.
+///
+/// By default, [`ReportMethod::MgbaSwi`] is used, which is understood by `mgba-rom-test`. This
+/// must be called before the test runner is started, such as in `main()` before calling the test
+/// harness.
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(all(feature = "runner", target = "thumbv4t-none-eabi")))
+)]
+pub fn set_report_method(method: ReportMethod) {
+    // SAFETY: `REPORT_METHOD_CONFIGURED` and `REPORT_METHOD` are only ever accessed on the main
+    // thread.
+    unsafe {
+        REPORT_METHOD = method;
+        REPORT_METHOD_CONFIGURED = true;
+    }
+}
+
+/// Configures the runner to list the tests instead of running them.
+///
+/// Each test is logged to mGBA's log as `gba_test: listed <name> at <file>:<line>`, and reported
+/// as ignored, without running it or the function configured using [`set_global_setup()`]. This
+/// allows checking which tests are compiled into a ROM, such as to catch tests accidentally
+/// excluded by a `#[cfg]`. By default, tests are run. This must be called before the test runner
+/// is started, such as in `main()` before calling the test harness.
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(all(feature = "runner", target = "thumbv4t-none-eabi")))
+)]
+pub fn set_list_only(list_only: bool) {
+    // SAFETY: `LIST_ONLY` is only ever accessed on the main thread.
+    unsafe {
+        LIST_ONLY = list_only;
+    }
+}
+
+/// Configures a function to be run once before the first test.
+///
+/// This is useful for expensive setup shared by all tests, such as detecting the cartridge's save
+/// type. Only work RAM is preserved by the reset executed before each test by default, so any
+/// state the function sets up should be stored there. If the function panics, no tests are run,
+/// and the run is reported as failed with the message prefixed by `global setup failed: `. This
+/// must be called before the test runner is started, such as in `main()` before calling the test
+/// harness.
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(all(feature = "runner", target = "thumbv4t-none-eabi")))
+)]
+pub fn set_global_setup(setup: fn()) {
+    // SAFETY: `GLOBAL_SETUP` is only ever accessed on the main thread.
+    unsafe {
+        GLOBAL_SETUP = Some(setup);
+    }
+}
+
+/// Configures how the failure messages of panicking tests are written.
+///
+/// By default, a failed test's message is its panic formatted using `Display`, such as
+/// `panicked at 'message', src/lib.rs:1:1`. A custom formatter can trim file paths, leave out a
+/// large payload, or add context specific to the application. If the formatter returns an error,
+/// the default message is used instead. The formatter may be called more than once for the same
+/// panic, so it should write the same output each time. This must be called before the test
+/// runner is started, such as in `main()` before calling the test harness.
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(all(feature = "runner", target = "thumbv4t-none-eabi")))
+)]
+pub fn set_failure_formatter(formatter: FailureFormatter) {
+    // SAFETY: `FAILURE_FORMATTER` is only ever accessed on the main thread.
+    unsafe {
+        FAILURE_FORMATTER = Some(formatter);
+    }
+}
+
+/// Configures the maximum number of bytes of a failure message that are reported.
+///
+/// Longer messages, such as `assert_eq!` failures comparing large arrays, are cut off and followed
+/// by a note containing their full length. This keeps a single failure from using up the space
+/// available for reporting results. By default, 1024 bytes are reported. This must be called before
+/// the test runner is started, such as in `main()` before calling the test harness.
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(all(feature = "runner", target = "thumbv4t-none-eabi")))
+)]
+pub fn set_message_limit(bytes: usize) {
+    // SAFETY: `MESSAGE_LIMIT_CONFIGURED` and `MESSAGE_LIMIT` are only ever accessed on the main
+    // thread.
+    unsafe {
+        MESSAGE_LIMIT = bytes;
+        MESSAGE_LIMIT_CONFIGURED = true;
+    }
+}
+
+/// The progress of a test run.
+///
+/// This is returned by [`progress()`].
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(all(feature = "runner", target = "thumbv4t-none-eabi")))
+)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Progress {
+    /// The position of the current test in the order the tests are run, starting from `0`.
+    ///
+    /// Ignored tests are included.
+    pub current_index: usize,
+    /// The total number of tests, including ignored tests.
+    pub total: usize,
+}
+
+/// Returns the progress of the test run.
+///
+/// This is only available while a test is running. If called at any other time, such as in
+/// `main()` before the test harness is started, this returns `None`.
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(all(feature = "runner", target = "thumbv4t-none-eabi")))
+)]
+pub fn progress() -> Option<Progress> {
+    // SAFETY: `ITERATION`, `STARTED`, and `TESTS` are only ever accessed on the main thread.
+    unsafe {
+        if ITERATION == 0 {
+            return None;
+        }
+        let tests = TESTS;
+        Some(Progress {
+            current_index: STARTED - 1,
+            total: tests.len(),
+        })
+    }
+}
+
+/// Returns the position of the current test when the tests are sorted by module path and name.
+///
+/// Unlike [`Progress::current_index`], this does not depend on the order the tests are run in.
+/// This returns `None` if no test is in progress.
+pub(crate) fn current_test_index() -> Option<usize> {
+    // SAFETY: `ITERATION` and `CURRENT_POSITION` are only ever accessed on the main thread.
+    unsafe {
+        if ITERATION == 0 {
+            None
+        } else {
+            Some(CURRENT_POSITION)
+        }
+    }
+}
+
 /// A test runner to execute tests as a Game Boy Advance ROM.
+///
+/// The test results are serialized to SRAM, one test at a time, and can be read from the save
+/// file using `read_trials()` with the `std` feature enabled. If SRAM runs out of space, the
+/// results that fit are kept and the number of tests that did not fit is recorded. To report
+/// results elsewhere, use [`runner_with_reporter()`].
+///
+/// Tests are run in order of their module paths, and then of their names, rather than in the
+/// unspecified order the compiler collects them in. The module path of a test defined using
+/// `#[test]` ends with the name of its function, so such tests are run in order of their full
+/// paths. The cases of a parameterized test are run in the order they are given. The sorted order
+/// is kept in EWRAM alongside the list of failed test names described below; if there are more
+/// than 8192 tests, they are run in the order they were collected instead.
+///
+/// When the runner starts, it detects whether it is running on mGBA by probing the emulator's
+/// debug registers, which is safe to do on hardware. The detected [`Environment`] is recorded in
+/// the results, where it can be read using `read_environment()`, and in the [`Summary`].
+///
+/// Once all tests have been run, a summary is written as a single line to mGBA's log, for
+/// scraping by CI scripts. Its format is stable:
+///
+/// ```text
+/// gba_test: result=<ok|failed>; passed=N; failed=N; ignored=N; total=N; duration_frames=N
+/// ```
+///
+/// `duration_frames` counts the whole frames tests ran for, as measured by the watchdog. It is
+/// `0` if the watchdog has been disabled using [`set_default_timeout()`].
+///
+/// The names of failed tests are also listed in memory under the `GBA_TEST_FAILED_NAMES` symbol,
+/// so that they can be read from a memory dump or savestate without decoding the results. The list
+/// starts with the magic bytes `GBTF`, followed by its length in bytes as a little-endian `u16`,
+/// followed by up to 2042 bytes of names, one per line. If not all names fit, the list ends with a
+/// `...` line. The list is kept in EWRAM, so the linker script must place the `.gba_test_ewram`
+/// section there as a `NOLOAD` section, as the script written by `gba_test_init` does.
+///
+/// With the `log-capture` feature enabled, the runner installs a [`log`] logger before running any
+/// tests. Messages are forwarded to mGBA's log, and the last 1 KiB of lines logged by each test are
+/// appended to its failure message below a `--- captured log ---` separator. Since only one logger
+/// can be installed, crates like `mgba_log` should not be initialized alongside it. Tests can
+/// inspect the records they logged using `logs()` and `assert_logged!`.
+///
+/// With the `io-diff` feature enabled, a set of IO registers is recorded before each test,
+/// including the display, background, sound, DMA, timer, and interrupt control registers. Those
+/// that a failing test changed are appended to its failure message below a
+/// `--- changed registers ---` separator, such as `DISPCNT 0400→0000, IE 0001→0041`.
+///
+/// [`log`]: https://docs.rs/log
 #[cfg_attr(
     doc_cfg,
     doc(cfg(all(feature = "runner", target = "thumbv4t-none-eabi")))
 )]
 pub fn runner(tests: &'static [&'static dyn TestCase]) {
-    // SAFETY: `TESTS`, `SRAM_POS`, and `WAITCNT` are only ever accessed on the main thread.
+    // SAFETY: `SRAM_REPORTER` is only ever accessed here, and this reference is only ever used on
+    // the main thread.
+    runner_with_reporter(tests, unsafe { &mut *ptr::addr_of_mut!(SRAM_REPORTER) });
+}
+
+/// Lines logged when no tests are provided to the runner, describing the setup a crate needs for
+/// its tests to be collected.
+const NO_TESTS_HINT: [&str; 6] = [
+    "gba_test: no tests were found; check that the crate root contains:",
+    "gba_test:     #![feature(custom_test_frameworks)]",
+    "gba_test:     #![test_runner(gba_test::runner)]",
+    "gba_test:     #![reexport_test_harness_main = \"test_harness\"]",
+    "gba_test: and that `main()` calls `test_harness()`, such as by using `gba_test::harness_main!()`",
+    "gba_test: tests must also be defined using `#[gba_test::test]` within `#[cfg(test)]` code",
+];
+
+/// Warns in mGBA's log that no tests were provided to the runner.
+///
+/// This is most often caused by a crate missing part of its test setup, which otherwise builds and
+/// runs without any indication of what went wrong.
+fn warn_no_tests() {
+    for line in NO_TESTS_HINT {
+        mgba_log::log(mgba_log::Level::Warn, format_args!("{}", line));
+    }
+}
+
+/// Warns in mGBA's log that there are too many tests to sort.
+fn warn_unsorted() {
+    mgba_log::log(
+        mgba_log::Level::Warn,
+        format_args!(
+            "gba_test: more than {} tests; running them in the order they were collected",
+            order::MAX_TESTS
+        ),
+    );
+}
+
+/// A test runner to execute tests as a Game Boy Advance ROM, passing results to the given
+/// reporter.
+///
+/// To use this runner, define your own runner function that calls this one with your reporter,
+/// and provide that function to the `#![test_runner]` attribute.
+///
+/// If the runner panics outside of a test while a test is in progress, such as within the
+/// reporter or a [`TestCase`] method, that test fails with a message starting with
+/// `framework error:` and the remaining tests are run. A panic outside of any test, or more than
+/// three such panics in a row, aborts the test run instead.
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(all(feature = "runner", target = "thumbv4t-none-eabi")))
+)]
+pub fn runner_with_reporter(
+    tests: &'static [&'static dyn TestCase],
+    reporter: &'static mut dyn Reporter,
+) {
+    // SAFETY: `TESTS`, `DUPLICATE_NAMES`, `ORDER`, `SUMMARY`, `PHASE`, `FRAMEWORK_PANICS`, and
+    // `WAITCNT` are only ever accessed on the main thread.
     unsafe {
         TESTS = tests;
-        // It seems this value must be reinitialized, otherwise it is always nullptr.
-        SRAM_POS = 0x0E00_0001 as *mut u8;
+        STARTED = 0;
+        DUPLICATE_NAMES = has_duplicate_names(tests);
+        SUMMARY = Summary {
+            environment: hardware::environment(),
+            ..Summary::default()
+        };
+        PHASE = Phase::Framework;
+        FRAMEWORK_PANICS = 0;
+        failed_names::clear();
+        if !order::sort(tests) {
+            warn_unsorted();
+        }
+        #[cfg(not(feature = "shuffle"))]
+        {
+            ORDER = 0..tests.len();
+        }
+        #[cfg(feature = "shuffle")]
+        {
+            let seed = shuffle::seed();
+            ORDER = Permutation::new(tests.len(), seed);
+            SUMMARY.seed = Some(seed);
+        }
 
         // Enable writes to SRAM.
         WAITCNT.write(3);
     }
 
-    // Write the number of expected results.
-    append_to_sram(tests.len()).unwrap_or_else(handle_error);
+    #[cfg(feature = "log-capture")]
+    log_capture::install();
+
+    if tests.is_empty() {
+        warn_no_tests();
+    }
+
+    reporter.start(tests.len());
+    // SAFETY: `REPORTER` is only ever accessed on the main thread.
+    unsafe {
+        REPORTER = Some(reporter);
+    }
+
+    // SAFETY: `GLOBAL_SETUP`, `LIST_ONLY`, and `PHASE` are only ever accessed on the main thread.
+    unsafe {
+        if let Some(setup) = GLOBAL_SETUP.filter(|_| !LIST_ONLY) {
+            PHASE = Phase::GlobalSetup;
+            setup();
+            PHASE = Phase::Framework;
+        }
+    }
 
     run_tests();
 }
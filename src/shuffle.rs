@@ -0,0 +1,139 @@
+//! Randomized ordering of tests.
+//!
+//! Running tests in a random order helps to reveal hidden dependencies between tests. The order is
+//! computed on the fly from a seed, so no storage proportional to the number of tests is required.
+//! The order that is shuffled is the sorted order, so a seed gives the same order across builds
+//! of the same tests.
+
+/// The seed used to shuffle the test order.
+///
+/// This is stored in ROM under the symbol `GBA_TEST_SHUFFLE_SEED`, allowing a specific order to
+/// be reproduced by patching the ROM with the seed reported by a previous run.
+#[no_mangle]
+#[used]
+static GBA_TEST_SHUFFLE_SEED: u32 = 0x9E37_79B9;
+
+/// Returns the seed used to shuffle the test order.
+#[cfg(not(test))]
+pub(crate) fn seed() -> u32 {
+    // SAFETY: The seed is read volatilely, as its value may be patched after compilation.
+    unsafe { core::ptr::read_volatile(&GBA_TEST_SHUFFLE_SEED) }
+}
+
+/// A pseudo-random permutation of the indices `0..len`.
+///
+/// This is a linear congruential generator with full period over the smallest power of two
+/// greater than or equal to `len`. Values outside of `0..len` are skipped, which yields every
+/// index exactly once.
+#[derive(Debug)]
+pub(crate) struct Permutation {
+    /// The number of indices being permuted.
+    len: u32,
+    /// The number of indices not yet yielded.
+    remaining: u32,
+    /// The modulus of the generator, minus one.
+    mask: u32,
+    /// The multiplier of the generator. This is always congruent to 1 mod 4.
+    multiplier: u32,
+    /// The increment of the generator. This is always odd.
+    increment: u32,
+    /// The next value produced by the generator.
+    state: u32,
+}
+
+impl Permutation {
+    /// Returns a permutation of no indices.
+    pub(crate) const fn empty() -> Self {
+        Self {
+            len: 0,
+            remaining: 0,
+            mask: 0,
+            multiplier: 1,
+            increment: 1,
+            state: 0,
+        }
+    }
+
+    /// Creates a permutation of the indices `0..len`, determined by `seed`.
+    pub(crate) fn new(len: usize, seed: u32) -> Self {
+        let len = len as u32;
+        let mask = len.checked_next_power_of_two().unwrap_or(0).wrapping_sub(1);
+        Self {
+            len,
+            remaining: len,
+            mask,
+            multiplier: (seed >> 16) << 2 | 1,
+            increment: seed | 1,
+            state: seed.rotate_right(8) & mask,
+        }
+    }
+}
+
+impl Iterator for Permutation {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        loop {
+            let value = self.state;
+            self.state = self
+                .multiplier
+                .wrapping_mul(self.state)
+                .wrapping_add(self.increment)
+                & self.mask;
+            if value < self.len {
+                self.remaining -= 1;
+                return Some(value as usize);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Permutation;
+    use alloc::vec::Vec;
+
+    const SEEDS: [u32; 5] = [0, 1, 0x9E37_79B9, 0xDEAD_BEEF, u32::MAX];
+
+    #[test]
+    fn empty() {
+        assert_eq!(Permutation::empty().next(), None);
+    }
+
+    #[test]
+    fn new_empty() {
+        for seed in SEEDS {
+            assert_eq!(Permutation::new(0, seed).next(), None);
+        }
+    }
+
+    #[test]
+    fn yields_every_index_once() {
+        for seed in SEEDS {
+            for len in 1..=100 {
+                let mut indices: Vec<usize> = Permutation::new(len, seed).collect();
+                indices.sort_unstable();
+                assert_eq!(indices, (0..len).collect::<Vec<_>>());
+            }
+        }
+    }
+
+    #[test]
+    fn deterministic() {
+        assert_eq!(
+            Permutation::new(50, 0xDEAD_BEEF).collect::<Vec<_>>(),
+            Permutation::new(50, 0xDEAD_BEEF).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn different_seeds_different_orders() {
+        assert_ne!(
+            Permutation::new(50, 0xDEAD_BEEF).collect::<Vec<_>>(),
+            Permutation::new(50, 0x9E37_79B9).collect::<Vec<_>>()
+        );
+    }
+}
@@ -0,0 +1,49 @@
+//! Skipping of tests from within their bodies.
+//!
+//! Some tests can only determine whether they apply once they are running, such as after probing
+//! for a peripheral. Such tests can use [`skip!`] to end early without failing.
+//!
+//! [`skip!`]: crate::skip!
+
+use crate::runner;
+use core::fmt;
+
+/// Skips the current test with the given reason.
+///
+/// This is an implementation detail of [`skip!`].
+///
+/// [`skip!`]: crate::skip!
+#[doc(hidden)]
+pub fn skip(reason: fmt::Arguments) -> ! {
+    runner::skip(&reason)
+}
+
+/// Ends the current test early, reporting it as ignored.
+///
+/// This accepts the same arguments as [`format!`], which are used as the reason the test was
+/// skipped. The reason is written to mGBA's log as `gba_test: skipped <name>: <reason>`. The test
+/// is reported as ignored rather than passed or failed, even if it uses `#[should_panic]` or
+/// `#[should_err]`.
+///
+/// ```no_run
+/// use gba_test::skip;
+///
+/// # fn rumble_detected() -> bool { false }
+/// if !rumble_detected() {
+///     skip!("no rumble cart detected");
+/// }
+/// ```
+///
+/// Using this outside of a test panics.
+///
+/// [`format!`]: https://doc.rust-lang.org/alloc/macro.format.html
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(all(feature = "runner", target = "thumbv4t-none-eabi")))
+)]
+#[macro_export]
+macro_rules! skip {
+    ($($arg:tt)+) => {
+        $crate::__skip(::core::format_args!($($arg)+))
+    };
+}
@@ -0,0 +1,127 @@
+//! Reporting of values returned by tests.
+//!
+//! Tests defined using the [`test`] attribute may return any type implementing [`Termination`],
+//! allowing the `?` operator to be used within them.
+//!
+//! [`test`]: crate::test
+
+use crate::{contains::contains, runner};
+use core::{
+    fmt,
+    fmt::{Debug, Formatter},
+};
+
+/// A value that can be returned from a test.
+///
+/// The returned value determines whether the test passed or failed. Failing a test this way
+/// reports it in the same way as a panic, with the given failure message.
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(all(feature = "runner", target = "thumbv4t-none-eabi")))
+)]
+pub trait Termination {
+    /// Reports the outcome of the test, failing it if this value represents a failure.
+    ///
+    /// This does not return if the test fails.
+    fn terminate(self);
+}
+
+impl Termination for () {
+    fn terminate(self) {}
+}
+
+impl<T, E> Termination for Result<T, E>
+where
+    T: Termination,
+    E: Debug,
+{
+    /// Fails the test if this is an `Err`, with the message `Error: ` followed by the error's
+    /// `Debug` representation.
+    fn terminate(self) {
+        match self {
+            Ok(value) => value.terminate(),
+            Err(error) => runner::fail(&format_args!("Error: {:?}", error)),
+        }
+    }
+}
+
+impl<T> Termination for Option<T>
+where
+    T: Termination,
+{
+    /// Fails the test if this is `None`, with the message `returned None`.
+    fn terminate(self) {
+        match self {
+            Some(value) => value.terminate(),
+            None => runner::fail(&"returned None"),
+        }
+    }
+}
+
+/// Reports the outcome of a test that is expected to return an `Err`.
+///
+/// This is used by the [`test`] attribute in place of [`Termination`] for tests annotated with
+/// `#[should_err]`. The test fails if the value is `Ok`, or if the `Debug` representation of the
+/// error does not contain the expected message. It is not considered part of the public API.
+///
+/// [`test`]: crate::test
+#[doc(hidden)]
+pub fn expect_err<T, E>(result: Result<T, E>)
+where
+    E: Debug,
+{
+    match result {
+        Ok(_) => runner::fail(&"expected Err, got Ok"),
+        Err(error) => {
+            if let Some(expected) = runner::should_err()
+                .expected_message()
+                .filter(|expected| !contains(format_args!("{:?}", error), expected))
+            {
+                runner::fail(&format_args!(
+                    "error: {:?}, expected substring: \"{}\"",
+                    error, expected
+                ))
+            }
+        }
+    }
+}
+
+/// A test failure with a message.
+///
+/// Returning this from a test fails it with the given message, without panicking. It can also be
+/// used as the error type of a test returning a `Result`, in which case the failure message is
+/// `Error: ` followed by the contained message.
+///
+/// ```no_run
+/// # #![feature(custom_test_frameworks)]
+/// use gba_test::{test, Failure};
+///
+/// #[test]
+/// fn foo() -> Result<(), Failure> {
+///     # let precondition = true;
+///     if !precondition {
+///         return Err(Failure("precondition not met"));
+///     }
+///     Ok(())
+/// }
+/// ```
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(all(feature = "runner", target = "thumbv4t-none-eabi")))
+)]
+#[derive(Clone, Copy, Eq, PartialEq)]
+pub struct Failure(pub &'static str);
+
+impl Debug for Failure {
+    /// Writes the contained message, without quotes.
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        formatter.write_str(self.0)
+    }
+}
+
+impl Termination for Failure {
+    /// Fails the test with the contained message.
+    fn terminate(self) {
+        runner::fail(&self.0)
+    }
+}
@@ -8,13 +8,317 @@
 //! [`runner`]: crate::runner()
 //! [`test`]: crate::test
 
+use core::{
+    fmt,
+    fmt::{Display, Formatter},
+};
+#[cfg(feature = "serde")]
+use serde::{
+    de,
+    de::{Deserialize, Deserializer, Error as _, MapAccess, SeqAccess, Visitor},
+    ser::{Serialize, SerializeStruct, Serializer},
+};
+
 /// Defines whether a test should be ignored or not.
+///
+/// New variants may be added in the future, so prefer the accessor methods over matching on the
+/// variants directly.
 #[derive(Clone, Copy, Debug)]
+#[non_exhaustive]
 pub enum Ignore {
     /// The test should be run.
     No,
     /// The test should not be run.
     Yes,
+    /// The test should not be run, for the given reason.
+    YesWithMessage(&'static str),
+}
+
+impl Ignore {
+    /// Returns whether the test should not be run.
+    pub fn is_ignored(&self) -> bool {
+        !matches!(self, Self::No)
+    }
+
+    /// Returns the reason the test is ignored, if one was given.
+    pub fn message(&self) -> Option<&'static str> {
+        match self {
+            Self::YesWithMessage(message) => Some(message),
+            Self::No | Self::Yes => None,
+        }
+    }
+}
+
+/// Defines whether a test is expected to panic.
+///
+/// New variants may be added in the future, so prefer the accessor methods over matching on the
+/// variants directly.
+#[derive(Clone, Copy, Debug)]
+#[non_exhaustive]
+pub enum ShouldPanic {
+    /// The test should not panic.
+    No,
+    /// The test should panic.
+    Yes,
+    /// The test should panic with a message containing the given string.
+    YesWithMessage(&'static str),
+    /// The test should panic in a file whose path contains the given string.
+    YesWithLocation {
+        /// A substring of the path of the file where the panic must occur.
+        file_substring: &'static str,
+    },
+    /// The test should panic with a message containing `expected`, in a file whose path contains
+    /// `file_substring`.
+    YesWithMessageAndLocation {
+        /// A substring of the panic message.
+        expected: &'static str,
+        /// A substring of the path of the file where the panic must occur.
+        file_substring: &'static str,
+    },
+}
+
+impl ShouldPanic {
+    /// Returns whether the test is expected to panic.
+    pub fn expects_panic(&self) -> bool {
+        !matches!(self, Self::No)
+    }
+
+    /// Returns the string the panic message must contain, if any.
+    pub fn expected_message(&self) -> Option<&'static str> {
+        match self {
+            Self::YesWithMessage(expected) | Self::YesWithMessageAndLocation { expected, .. } => {
+                Some(expected)
+            }
+            Self::No | Self::Yes | Self::YesWithLocation { .. } => None,
+        }
+    }
+
+    /// Returns the string the path of the file where the panic occurs must contain, if any.
+    pub fn expected_location(&self) -> Option<&'static str> {
+        match self {
+            Self::YesWithLocation { file_substring }
+            | Self::YesWithMessageAndLocation { file_substring, .. } => Some(file_substring),
+            Self::No | Self::Yes | Self::YesWithMessage(_) => None,
+        }
+    }
+}
+
+/// Defines whether a test is expected to return an `Err`.
+///
+/// New variants may be added in the future, so prefer the accessor methods over matching on the
+/// variants directly.
+#[derive(Clone, Copy, Debug)]
+#[non_exhaustive]
+pub enum ShouldErr {
+    /// The test should not return an `Err`.
+    No,
+    /// The test should return an `Err`.
+    Yes,
+    /// The test should return an `Err` whose `Debug` representation contains the given string.
+    YesWithMessage(&'static str),
+}
+
+impl ShouldErr {
+    /// Returns whether the test is expected to return an `Err`.
+    pub fn expects_err(&self) -> bool {
+        !matches!(self, Self::No)
+    }
+
+    /// Returns the string the `Debug` representation of the error must contain, if any.
+    pub fn expected_message(&self) -> Option<&'static str> {
+        match self {
+            Self::YesWithMessage(expected) => Some(expected),
+            Self::No | Self::Yes => None,
+        }
+    }
+}
+
+/// A piece of hardware a test may require.
+///
+/// Tests requiring a capability that isn't detected are not run, and are reported as ignored.
+/// Detection is conservative: hardware that can't be positively identified is treated as missing.
+/// New variants may be added in the future.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum Capability {
+    /// Battery-backed SRAM on the cartridge.
+    ///
+    /// This is detected by writing to the last byte of SRAM and reading it back, after which the
+    /// original byte is restored. Flash and EEPROM saves are not detected as SRAM.
+    Sram,
+    /// A real-time clock connected to the cartridge's GPIO port.
+    ///
+    /// This is detected by reading the date from the clock and checking that it is valid.
+    Rtc,
+    /// A link cable connecting this Game Boy Advance to another.
+    ///
+    /// This is detected using multi-player mode, and only when this Game Boy Advance is the
+    /// parent, meaning it is plugged into the cable's smaller plug. Others connected to the cable
+    /// can't tell it apart from no cable at all.
+    LinkCable,
+}
+
+impl Capability {
+    /// Returns the name of the capability, as used by the `requires` argument of the `#[test]`
+    /// attribute.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Sram => "sram",
+            Self::Rtc => "rtc",
+            Self::LinkCable => "link_cable",
+        }
+    }
+}
+
+impl Display for Capability {
+    /// Writes the name of the capability.
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        formatter.write_str(self.name())
+    }
+}
+
+/// A location in source code where a test is defined.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Location<'a> {
+    /// The path of the file, as given by [`file!()`].
+    pub file: &'a str,
+    /// The line number, starting at `1`.
+    pub line: u32,
+}
+
+impl Location<'static> {
+    /// The location of a test whose definition is not known.
+    ///
+    /// This is returned by the default implementation of [`TestCase::location()`].
+    pub const UNKNOWN: Self = Self {
+        file: "<unknown>",
+        line: 0,
+    };
+}
+
+impl Display for Location<'_> {
+    /// Writes the location in the form `file:line`.
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(formatter, "{}:{}", self.file, self.line)
+    }
+}
+
+#[cfg(feature = "serde")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "serde")))]
+impl Serialize for Location<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut location = serializer.serialize_struct("Location", 2)?;
+
+        location.serialize_field("file", self.file)?;
+        location.serialize_field("line", &self.line)?;
+
+        location.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "serde")))]
+impl<'de> Deserialize<'de> for Location<'de> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        enum Field {
+            File,
+            Line,
+        }
+
+        impl<'de> Deserialize<'de> for Field {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                struct FieldVisitor;
+
+                impl<'de> Visitor<'de> for FieldVisitor {
+                    type Value = Field;
+
+                    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                        formatter.write_str("`file` or `line`")
+                    }
+
+                    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+                    where
+                        E: de::Error,
+                    {
+                        match v {
+                            "file" => Ok(Field::File),
+                            "line" => Ok(Field::Line),
+                            _ => Err(E::unknown_field(v, FIELDS)),
+                        }
+                    }
+                }
+
+                deserializer.deserialize_identifier(FieldVisitor)
+            }
+        }
+
+        struct LocationVisitor;
+
+        impl<'de> Visitor<'de> for LocationVisitor {
+            type Value = Location<'de>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("struct Location")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let file = seq
+                    .next_element()?
+                    .ok_or(de::Error::missing_field("file"))?;
+                let line = seq
+                    .next_element()?
+                    .ok_or(de::Error::missing_field("line"))?;
+
+                Ok(Location { file, line })
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let mut file = None;
+                let mut line = None;
+
+                while let Some(key) = map.next_key()? {
+                    match key {
+                        Field::File => {
+                            if file.is_some() {
+                                return Err(A::Error::duplicate_field("file"));
+                            }
+                            file = Some(map.next_value()?);
+                        }
+                        Field::Line => {
+                            if line.is_some() {
+                                return Err(A::Error::duplicate_field("line"));
+                            }
+                            line = Some(map.next_value()?);
+                        }
+                    }
+                }
+
+                Ok(Location {
+                    file: file.ok_or_else(|| A::Error::missing_field("file"))?,
+                    line: line.ok_or_else(|| A::Error::missing_field("line"))?,
+                })
+            }
+        }
+
+        const FIELDS: &[&str] = &["file", "line"];
+
+        deserializer.deserialize_struct("Location", FIELDS, LocationVisitor)
+    }
 }
 
 /// Defines a test case executable by the test runner.
@@ -22,6 +326,14 @@ pub trait TestCase {
     /// The name of the test.
     fn name(&self) -> &str;
 
+    /// The path of the module the test is defined in.
+    ///
+    /// Tests are run in order of their module paths, and then of their names. By default, this
+    /// returns an empty path, so that such tests are ordered by name before any others.
+    fn module_path(&self) -> &str {
+        ""
+    }
+
     /// The actual test itself.
     ///
     /// If this method panics, the test is considered a failure. Otherwise, the test is considered
@@ -33,6 +345,57 @@ pub trait TestCase {
     /// If this method returns true, the test function will not be run at all (but it will still be
     /// compiled). This allows for time-consuming or expensive tests to be conditionally disabled.
     fn ignore(&self) -> Ignore;
+
+    /// Whether the test is expected to panic.
+    ///
+    /// If the test is expected to panic, it is considered a failure if it does not panic, and a
+    /// success if it does. By default, tests are not expected to panic.
+    fn should_panic(&self) -> ShouldPanic {
+        ShouldPanic::No
+    }
+
+    /// Whether the test is expected to return an `Err`.
+    ///
+    /// If the test is expected to return an `Err`, it is considered a failure if it returns `Ok`,
+    /// and a success if it returns an `Err`. By default, tests are not expected to return an
+    /// `Err`.
+    fn should_err(&self) -> ShouldErr {
+        ShouldErr::No
+    }
+
+    /// Where the test is defined.
+    ///
+    /// This is reported alongside the test's result. By default, this returns
+    /// [`Location::UNKNOWN`], in which case no location is reported.
+    fn location(&self) -> Location<'static> {
+        Location::UNKNOWN
+    }
+
+    /// The number of whole frames the test may take to complete.
+    ///
+    /// If the test completes after running for more frames than this, it is considered a failure,
+    /// even if it would otherwise have passed, such as by panicking as expected. By default, tests
+    /// have no frame budget.
+    fn max_frames(&self) -> Option<u32> {
+        None
+    }
+
+    /// The name of the suite the test belongs to, if any.
+    ///
+    /// Suites group tests independently of the modules they are defined in, such as tests that are
+    /// slow or that require real hardware. The suite is reported alongside the test's result. By
+    /// default, tests do not belong to a suite.
+    fn suite(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// The hardware the test requires, if any.
+    ///
+    /// If the hardware isn't detected, the test is not run and is reported as ignored. By default,
+    /// tests don't require any hardware.
+    fn requires(&self) -> Option<Capability> {
+        None
+    }
 }
 
 /// A standard test.
@@ -40,16 +403,92 @@ pub trait TestCase {
 /// This struct is created by the `#[test]` attribute. This struct is not to be used directly and
 /// is not considered part of the public API. If you want to use a similar struct, you should
 /// define one locally and implement `TestCase` for it directly.
+///
+/// One of these is stored in the ROM for every test, so it is kept small. Settings that most tests
+/// don't use are stored separately in [`TestDetails`], which is only referenced when needed.
 #[doc(hidden)]
 pub struct Test {
     /// The name of the test.
     pub name: &'static str,
+    /// The path of the module where the test is defined.
+    ///
+    /// For tests defined using `#[test]`, this is the module generated for the test, which is named
+    /// after the test function.
+    pub module_path: &'static str,
     /// The test function itself.
     pub test: fn(),
-    /// Whether the test should be excluded.
+    /// The path of the file where the test is defined.
+    pub file: &'static str,
+    /// The line where the test is defined.
+    pub line: u32,
+    /// The flags set by the test's attributes, such as [`Test::IGNORE`].
+    pub flags: u8,
+    /// The less common settings of the test, if any are used.
+    pub details: Option<&'static TestDetails>,
+}
+
+impl Test {
+    /// The test should not be run.
     ///
     /// This is set by the `#[ignore]` attribute.
-    pub ignore: Ignore,
+    pub const IGNORE: u8 = 0b0000_0001;
+    /// The test is expected to panic.
+    ///
+    /// This is set by the `#[should_panic]` attribute.
+    pub const SHOULD_PANIC: u8 = 0b0000_0010;
+    /// The test is expected to return an `Err`.
+    ///
+    /// This is set by the `#[should_err]` attribute.
+    pub const SHOULD_ERR: u8 = 0b0000_0100;
+
+    /// Returns the test's details, or the defaults if it has none.
+    fn details(&self) -> &TestDetails {
+        self.details.unwrap_or(&TestDetails::DEFAULT)
+    }
+}
+
+/// The settings of a [`Test`] that most tests don't use.
+///
+/// This struct is created by the `#[test]` attribute, and is not considered part of the public
+/// API.
+#[doc(hidden)]
+pub struct TestDetails {
+    /// The reason the test is ignored.
+    ///
+    /// This is set by `#[ignore = <expr>]`.
+    pub ignore_message: Option<&'static str>,
+    /// The string the panic message or the `Debug` representation of the error must contain.
+    ///
+    /// This is set by the `expected` argument of `#[should_panic]` or `#[should_err]`.
+    pub expected: Option<&'static str>,
+    /// The string the path of the file where the panic occurs must contain.
+    ///
+    /// This is set by the `location` argument of `#[should_panic]`.
+    pub file_substring: Option<&'static str>,
+    /// The number of frames the test may take to complete.
+    ///
+    /// This is set by the `max_frames` argument of the `#[test]` attribute.
+    pub max_frames: Option<u32>,
+    /// The suite the test belongs to.
+    ///
+    /// This is set by the `suite` argument of the `#[test]` attribute.
+    pub suite: Option<&'static str>,
+    /// The hardware the test requires.
+    ///
+    /// This is set by the `requires` argument of the `#[test]` attribute.
+    pub requires: Option<Capability>,
+}
+
+impl TestDetails {
+    /// The details of a test using none of them.
+    pub const DEFAULT: Self = Self {
+        ignore_message: None,
+        expected: None,
+        file_substring: None,
+        max_frames: None,
+        suite: None,
+        requires: None,
+    };
 }
 
 impl TestCase for Test {
@@ -57,11 +496,404 @@ impl TestCase for Test {
         self.name
     }
 
+    fn module_path(&self) -> &str {
+        self.module_path
+    }
+
     fn run(&self) {
         (self.test)()
     }
 
     fn ignore(&self) -> Ignore {
-        self.ignore
+        match (
+            self.flags & Self::IGNORE != 0,
+            self.details().ignore_message,
+        ) {
+            (false, _) => Ignore::No,
+            (true, None) => Ignore::Yes,
+            (true, Some(message)) => Ignore::YesWithMessage(message),
+        }
+    }
+
+    fn should_panic(&self) -> ShouldPanic {
+        let details = self.details();
+        match (
+            self.flags & Self::SHOULD_PANIC != 0,
+            details.expected,
+            details.file_substring,
+        ) {
+            (false, ..) => ShouldPanic::No,
+            (true, None, None) => ShouldPanic::Yes,
+            (true, Some(expected), None) => ShouldPanic::YesWithMessage(expected),
+            (true, None, Some(file_substring)) => ShouldPanic::YesWithLocation { file_substring },
+            (true, Some(expected), Some(file_substring)) => {
+                ShouldPanic::YesWithMessageAndLocation {
+                    expected,
+                    file_substring,
+                }
+            }
+        }
+    }
+
+    fn should_err(&self) -> ShouldErr {
+        match (self.flags & Self::SHOULD_ERR != 0, self.details().expected) {
+            (false, _) => ShouldErr::No,
+            (true, None) => ShouldErr::Yes,
+            (true, Some(expected)) => ShouldErr::YesWithMessage(expected),
+        }
+    }
+
+    fn location(&self) -> Location<'static> {
+        Location {
+            file: self.file,
+            line: self.line,
+        }
+    }
+
+    fn max_frames(&self) -> Option<u32> {
+        self.details().max_frames
+    }
+
+    fn suite(&self) -> Option<&'static str> {
+        self.details().suite
+    }
+
+    fn requires(&self) -> Option<Capability> {
+        self.details().requires
+    }
+}
+
+/// A description of a test, read from a built ROM by host tools.
+///
+/// One of these is placed in the `.gba_test_meta` section for every test by the `#[test]`
+/// attribute, allowing the tests in a ROM to be listed without running it. This struct is not
+/// considered part of the public API.
+///
+/// On the Game Boy Advance, this is made up of the address and length of the test's name, the
+/// address and length of its module path, and its flags, as defined on [`Test`], each as a
+/// little-endian `u32` except for the flags, which are a single byte followed by padding.
+#[doc(hidden)]
+#[repr(C)]
+pub struct TestRecord {
+    name: *const u8,
+    name_len: usize,
+    path: *const u8,
+    path_len: usize,
+    flags: u8,
+}
+
+impl TestRecord {
+    /// Creates a record for the test with the given name, module path, and flags.
+    pub const fn new(name: &'static str, path: &'static str, flags: u8) -> Self {
+        Self {
+            name: name.as_ptr(),
+            name_len: name.len(),
+            path: path.as_ptr(),
+            path_len: path.len(),
+            flags,
+        }
+    }
+}
+
+// SAFETY: The pointers are to `'static` strings, which are never mutated.
+unsafe impl Sync for TestRecord {}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        Capability, Ignore, Location, ShouldErr, ShouldPanic, Test, TestCase, TestDetails,
+    };
+    use alloc::{borrow::ToOwned, format, vec};
+    use claims::{assert_err_eq, assert_ok_eq};
+    use core::mem::size_of;
+    use serde::{de::Error as _, Deserialize, Serialize};
+    use serde_assert::{de, Deserializer, Serializer, Token, Tokens};
+
+    #[test]
+    fn ignore_no() {
+        assert!(!Ignore::No.is_ignored());
+        assert_eq!(Ignore::No.message(), None);
+    }
+
+    #[test]
+    fn ignore_yes() {
+        assert!(Ignore::Yes.is_ignored());
+        assert_eq!(Ignore::Yes.message(), None);
+    }
+
+    #[test]
+    fn ignore_yes_with_message() {
+        assert!(Ignore::YesWithMessage("foo").is_ignored());
+        assert_eq!(Ignore::YesWithMessage("foo").message(), Some("foo"));
+    }
+
+    #[test]
+    fn should_panic_no() {
+        assert!(!ShouldPanic::No.expects_panic());
+        assert_eq!(ShouldPanic::No.expected_message(), None);
+        assert_eq!(ShouldPanic::No.expected_location(), None);
+    }
+
+    #[test]
+    fn should_panic_yes() {
+        assert!(ShouldPanic::Yes.expects_panic());
+        assert_eq!(ShouldPanic::Yes.expected_message(), None);
+        assert_eq!(ShouldPanic::Yes.expected_location(), None);
+    }
+
+    #[test]
+    fn should_panic_yes_with_message() {
+        let should_panic = ShouldPanic::YesWithMessage("foo");
+        assert!(should_panic.expects_panic());
+        assert_eq!(should_panic.expected_message(), Some("foo"));
+        assert_eq!(should_panic.expected_location(), None);
+    }
+
+    #[test]
+    fn should_panic_yes_with_location() {
+        let should_panic = ShouldPanic::YesWithLocation {
+            file_substring: "src/foo.rs",
+        };
+        assert!(should_panic.expects_panic());
+        assert_eq!(should_panic.expected_message(), None);
+        assert_eq!(should_panic.expected_location(), Some("src/foo.rs"));
+    }
+
+    #[test]
+    fn should_panic_yes_with_message_and_location() {
+        let should_panic = ShouldPanic::YesWithMessageAndLocation {
+            expected: "foo",
+            file_substring: "src/foo.rs",
+        };
+        assert!(should_panic.expects_panic());
+        assert_eq!(should_panic.expected_message(), Some("foo"));
+        assert_eq!(should_panic.expected_location(), Some("src/foo.rs"));
+    }
+
+    #[test]
+    fn should_err_no() {
+        assert!(!ShouldErr::No.expects_err());
+        assert_eq!(ShouldErr::No.expected_message(), None);
+    }
+
+    #[test]
+    fn should_err_yes() {
+        assert!(ShouldErr::Yes.expects_err());
+        assert_eq!(ShouldErr::Yes.expected_message(), None);
+    }
+
+    #[test]
+    fn should_err_yes_with_message() {
+        let should_err = ShouldErr::YesWithMessage("foo");
+        assert!(should_err.expects_err());
+        assert_eq!(should_err.expected_message(), Some("foo"));
+    }
+
+    #[test]
+    fn display_capability() {
+        assert_eq!(format!("{}", Capability::Sram), "sram");
+        assert_eq!(format!("{}", Capability::Rtc), "rtc");
+        assert_eq!(format!("{}", Capability::LinkCable), "link_cable");
+    }
+
+    fn noop() {}
+
+    const DETAILS: TestDetails = TestDetails {
+        ignore_message: Some("slow"),
+        expected: Some("foo"),
+        file_substring: Some("src/foo.rs"),
+        max_frames: Some(5),
+        suite: Some("hardware"),
+        requires: Some(Capability::Sram),
+    };
+
+    #[test]
+    fn test_defaults() {
+        let test = Test {
+            name: "foo",
+            module_path: "crate::tests",
+            test: noop,
+            file: "src/lib.rs",
+            line: 42,
+            flags: 0,
+            details: None,
+        };
+        assert_eq!(test.name(), "foo");
+        assert!(!test.ignore().is_ignored());
+        assert!(!test.should_panic().expects_panic());
+        assert!(!test.should_err().expects_err());
+        assert_eq!(
+            test.location(),
+            Location {
+                file: "src/lib.rs",
+                line: 42
+            }
+        );
+        assert_eq!(test.max_frames(), None);
+        assert_eq!(test.suite(), None);
+        assert_eq!(test.requires(), None);
+    }
+
+    #[test]
+    fn test_flags_without_details() {
+        let test = Test {
+            name: "foo",
+            module_path: "crate::tests",
+            test: noop,
+            file: "src/lib.rs",
+            line: 42,
+            flags: Test::IGNORE | Test::SHOULD_PANIC,
+            details: None,
+        };
+        assert!(test.ignore().is_ignored());
+        assert_eq!(test.ignore().message(), None);
+        assert!(test.should_panic().expects_panic());
+        assert_eq!(test.should_panic().expected_message(), None);
+        assert_eq!(test.should_panic().expected_location(), None);
+        assert!(!test.should_err().expects_err());
+    }
+
+    #[test]
+    fn test_details_without_flags() {
+        let test = Test {
+            name: "foo",
+            module_path: "crate::tests",
+            test: noop,
+            file: "src/lib.rs",
+            line: 42,
+            flags: 0,
+            details: Some(&DETAILS),
+        };
+        assert!(!test.ignore().is_ignored());
+        assert!(!test.should_panic().expects_panic());
+        assert!(!test.should_err().expects_err());
+        assert_eq!(test.max_frames(), Some(5));
+        assert_eq!(test.suite(), Some("hardware"));
+        assert_eq!(test.requires(), Some(Capability::Sram));
+    }
+
+    #[test]
+    fn test_should_panic_details() {
+        let test = Test {
+            name: "foo",
+            module_path: "crate::tests",
+            test: noop,
+            file: "src/lib.rs",
+            line: 42,
+            flags: Test::IGNORE | Test::SHOULD_PANIC,
+            details: Some(&DETAILS),
+        };
+        assert_eq!(test.ignore().message(), Some("slow"));
+        assert_eq!(test.should_panic().expected_message(), Some("foo"));
+        assert_eq!(test.should_panic().expected_location(), Some("src/foo.rs"));
+    }
+
+    #[test]
+    fn test_should_err_details() {
+        let test = Test {
+            name: "foo",
+            module_path: "crate::tests",
+            test: noop,
+            file: "src/lib.rs",
+            line: 42,
+            flags: Test::SHOULD_ERR,
+            details: Some(&DETAILS),
+        };
+        assert!(!test.should_panic().expects_panic());
+        assert!(test.should_err().expects_err());
+        assert_eq!(test.should_err().expected_message(), Some("foo"));
+    }
+
+    #[test]
+    fn test_size() {
+        // A `Test` is stored in the ROM for every test, so its size is kept to that of the name,
+        // module path, file, and function pointers, along with the line and flags.
+        assert!(size_of::<Test>() <= 5 * size_of::<&str>());
+    }
+
+    #[test]
+    fn display_location() {
+        assert_eq!(
+            format!(
+                "{}",
+                Location {
+                    file: "src/foo.rs",
+                    line: 123
+                }
+            ),
+            "src/foo.rs:123"
+        );
+    }
+
+    #[test]
+    fn serialize_deserialize_location() {
+        let serializer = Serializer::builder().build();
+        let tokens = assert_ok_eq!(
+            Location {
+                file: "src/foo.rs",
+                line: 123
+            }
+            .serialize(&serializer),
+            Tokens(vec![
+                Token::Struct {
+                    name: "Location",
+                    len: 2
+                },
+                Token::Field("file"),
+                Token::Str("src/foo.rs".to_owned()),
+                Token::Field("line"),
+                Token::U32(123),
+                Token::StructEnd
+            ])
+        );
+
+        let mut deserializer = Deserializer::builder().tokens(tokens).build();
+        assert_ok_eq!(
+            Location::deserialize(&mut deserializer),
+            Location {
+                file: "src/foo.rs",
+                line: 123
+            }
+        );
+    }
+
+    #[test]
+    fn deserialize_location_missing_field_line() {
+        let mut deserializer = Deserializer::builder()
+            .tokens(Tokens(vec![
+                Token::Struct {
+                    name: "Location",
+                    len: 1,
+                },
+                Token::Field("file"),
+                Token::Str("src/foo.rs".to_owned()),
+                Token::StructEnd,
+            ]))
+            .build();
+        assert_err_eq!(
+            Location::deserialize(&mut deserializer),
+            de::Error::missing_field("line")
+        );
+    }
+
+    #[test]
+    fn deserialize_location_duplicate_field_file() {
+        let mut deserializer = Deserializer::builder()
+            .tokens(Tokens(vec![
+                Token::Struct {
+                    name: "Location",
+                    len: 2,
+                },
+                Token::Field("file"),
+                Token::Str("src/foo.rs".to_owned()),
+                Token::Field("file"),
+                Token::Str("src/bar.rs".to_owned()),
+                Token::StructEnd,
+            ]))
+            .build();
+        assert_err_eq!(
+            Location::deserialize(&mut deserializer),
+            de::Error::duplicate_field("file")
+        );
     }
 }
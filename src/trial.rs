@@ -2,6 +2,7 @@
 
 #[cfg(feature = "serde")]
 use crate::display::SerializeDisplay;
+use crate::Location;
 use core::{fmt, fmt::Display, str};
 #[cfg(feature = "serde")]
 use serde::{
@@ -228,6 +229,58 @@ impl<'de> Deserialize<'de> for Outcome<&'de str> {
     }
 }
 
+/// The environment a test run took place in, as detected by the runner.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum Environment {
+    /// No emulator was detected. The tests were either run on hardware or on an emulator that
+    /// can't be identified.
+    #[default]
+    HardwareOrUnknown = 0,
+    /// The tests were run on mGBA, identified by its debug output registers.
+    Mgba = 1,
+}
+
+impl Environment {
+    /// Returns the environment identified by `byte`, as written to SRAM.
+    #[cfg(any(feature = "std", test))]
+    pub(crate) fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            0 => Some(Self::HardwareOrUnknown),
+            1 => Some(Self::Mgba),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Environment {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(match self {
+            Self::HardwareOrUnknown => "hardware or unknown",
+            Self::Mgba => "mGBA",
+        })
+    }
+}
+
+/// A summary of the outcomes of all tests in a test run.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Summary {
+    /// The number of tests that passed.
+    pub passed: usize,
+    /// The number of tests that failed.
+    pub failed: usize,
+    /// The number of tests that were excluded from the test run.
+    pub ignored: usize,
+    /// The number of tests that were aborted after running for too long.
+    ///
+    /// These tests are also counted in `failed`.
+    pub timed_out: usize,
+    /// The seed used to shuffle the order the tests were run in, if they were shuffled.
+    pub seed: Option<u32>,
+    /// The environment the tests were run in.
+    pub environment: Environment,
+}
+
 /// A single test result.
 #[derive(Debug, Eq, PartialEq)]
 pub struct Trial<'a, FailedMessage> {
@@ -235,6 +288,12 @@ pub struct Trial<'a, FailedMessage> {
     pub name: &'a str,
     /// The test's outcome.
     pub outcome: Outcome<FailedMessage>,
+    /// Output captured while the test was running.
+    pub output: &'a str,
+    /// Where the test is defined, if known.
+    pub location: Option<Location<'a>>,
+    /// The suite the test belongs to, if any.
+    pub suite: Option<&'a str>,
 }
 
 #[cfg(feature = "serde")]
@@ -247,10 +306,13 @@ where
     where
         S: Serializer,
     {
-        let mut trial = serializer.serialize_struct("Trial", 2)?;
+        let mut trial = serializer.serialize_struct("Trial", 5)?;
 
         trial.serialize_field("name", self.name)?;
         trial.serialize_field("outcome", &self.outcome)?;
+        trial.serialize_field("output", self.output)?;
+        trial.serialize_field("location", &self.location)?;
+        trial.serialize_field("suite", &self.suite)?;
 
         trial.end()
     }
@@ -266,6 +328,9 @@ impl<'de> Deserialize<'de> for Trial<'de, &'de str> {
         enum Field {
             Name,
             Outcome,
+            Output,
+            Location,
+            Suite,
         }
 
         impl<'de> Deserialize<'de> for Field {
@@ -279,7 +344,7 @@ impl<'de> Deserialize<'de> for Trial<'de, &'de str> {
                     type Value = Field;
 
                     fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-                        formatter.write_str("`name` or `outcome`")
+                        formatter.write_str("`name`, `outcome`, `output`, `location`, or `suite`")
                     }
 
                     fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
@@ -289,6 +354,9 @@ impl<'de> Deserialize<'de> for Trial<'de, &'de str> {
                         match v {
                             "name" => Ok(Field::Name),
                             "outcome" => Ok(Field::Outcome),
+                            "output" => Ok(Field::Output),
+                            "location" => Ok(Field::Location),
+                            "suite" => Ok(Field::Suite),
                             _ => Err(E::unknown_field(v, FIELDS)),
                         }
                     }
@@ -317,8 +385,20 @@ impl<'de> Deserialize<'de> for Trial<'de, &'de str> {
                 let outcome = seq
                     .next_element()?
                     .ok_or(de::Error::missing_field("outcome"))?;
+                let output = seq
+                    .next_element()?
+                    .ok_or(de::Error::missing_field("output"))?;
+                // The location and suite are optional, so they may be omitted.
+                let location = seq.next_element()?.unwrap_or(None);
+                let suite = seq.next_element()?.unwrap_or(None);
 
-                Ok(Trial { name, outcome })
+                Ok(Trial {
+                    name,
+                    outcome,
+                    output,
+                    location,
+                    suite,
+                })
             }
 
             fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
@@ -327,6 +407,9 @@ impl<'de> Deserialize<'de> for Trial<'de, &'de str> {
             {
                 let mut name = None;
                 let mut outcome = None;
+                let mut output = None;
+                let mut location = None;
+                let mut suite = None;
 
                 while let Some(key) = map.next_key()? {
                     match key {
@@ -342,17 +425,39 @@ impl<'de> Deserialize<'de> for Trial<'de, &'de str> {
                             }
                             outcome = Some(map.next_value()?);
                         }
+                        Field::Output => {
+                            if output.is_some() {
+                                return Err(A::Error::duplicate_field("output"));
+                            }
+                            output = Some(map.next_value()?);
+                        }
+                        Field::Location => {
+                            if location.is_some() {
+                                return Err(A::Error::duplicate_field("location"));
+                            }
+                            location = Some(map.next_value()?);
+                        }
+                        Field::Suite => {
+                            if suite.is_some() {
+                                return Err(A::Error::duplicate_field("suite"));
+                            }
+                            suite = Some(map.next_value()?);
+                        }
                     }
                 }
 
                 Ok(Trial {
                     name: name.ok_or_else(|| A::Error::missing_field("name"))?,
                     outcome: outcome.ok_or_else(|| A::Error::missing_field("outcome"))?,
+                    output: output.ok_or_else(|| A::Error::missing_field("output"))?,
+                    // The location and suite are optional, so they may be omitted.
+                    location: location.unwrap_or(None),
+                    suite: suite.unwrap_or(None),
                 })
             }
         }
 
-        const FIELDS: &[&str] = &["name", "outcome"];
+        const FIELDS: &[&str] = &["name", "outcome", "output", "location", "suite"];
 
         deserializer.deserialize_struct("Trial", FIELDS, TrialVisitor)
     }
@@ -360,8 +465,9 @@ impl<'de> Deserialize<'de> for Trial<'de, &'de str> {
 
 #[cfg(test)]
 mod tests {
-    use super::{Outcome, Trial};
-    use alloc::{borrow::ToOwned, vec};
+    use super::{Environment, Outcome, Trial};
+    use crate::Location;
+    use alloc::{borrow::ToOwned, format, vec};
     use claims::{assert_err_eq, assert_ok_eq};
     use serde::{de::Error as _, Deserialize, Serialize};
     use serde_assert::{de, Deserializer, Serializer, Token, Tokens};
@@ -524,13 +630,127 @@ mod tests {
             Trial {
                 name: "foo",
                 outcome: Outcome::<&str>::Passed,
+                output: "bar\n",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 7,
+                }),
+                suite: None,
             }
             .serialize(&serializer),
             Tokens(vec![
                 Token::Struct {
                     name: "Trial",
+                    len: 5
+                },
+                Token::Field("name"),
+                Token::Str("foo".to_owned()),
+                Token::Field("outcome"),
+                Token::UnitVariant {
+                    name: "Outcome",
+                    variant_index: 0,
+                    variant: "Passed"
+                },
+                Token::Field("output"),
+                Token::Str("bar\n".to_owned()),
+                Token::Field("location"),
+                Token::Some,
+                Token::Struct {
+                    name: "Location",
                     len: 2
                 },
+                Token::Field("file"),
+                Token::Str("src/lib.rs".to_owned()),
+                Token::Field("line"),
+                Token::U32(7),
+                Token::StructEnd,
+                Token::Field("suite"),
+                Token::None,
+                Token::StructEnd
+            ])
+        );
+
+        let mut deserializer = Deserializer::builder().tokens(tokens).build();
+        assert_ok_eq!(
+            Trial::deserialize(&mut deserializer),
+            Trial {
+                name: "foo",
+                outcome: Outcome::<&str>::Passed,
+                output: "bar\n",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 7,
+                }),
+                suite: None,
+            }
+        );
+    }
+
+    #[test]
+    fn serialize_deserialize_trial_no_location() {
+        let serializer = Serializer::builder().build();
+        let tokens = assert_ok_eq!(
+            Trial {
+                name: "foo",
+                outcome: Outcome::<&str>::Passed,
+                output: "",
+                location: None,
+                suite: None,
+            }
+            .serialize(&serializer),
+            Tokens(vec![
+                Token::Struct {
+                    name: "Trial",
+                    len: 5
+                },
+                Token::Field("name"),
+                Token::Str("foo".to_owned()),
+                Token::Field("outcome"),
+                Token::UnitVariant {
+                    name: "Outcome",
+                    variant_index: 0,
+                    variant: "Passed"
+                },
+                Token::Field("output"),
+                Token::Str("".to_owned()),
+                Token::Field("location"),
+                Token::None,
+                Token::Field("suite"),
+                Token::None,
+                Token::StructEnd
+            ])
+        );
+
+        let mut deserializer = Deserializer::builder().tokens(tokens).build();
+        assert_ok_eq!(
+            Trial::deserialize(&mut deserializer),
+            Trial {
+                name: "foo",
+                outcome: Outcome::<&str>::Passed,
+                output: "",
+                location: None,
+                suite: None,
+            }
+        );
+    }
+
+    #[test]
+    fn serialize_deserialize_trial_suite() {
+        let serializer = Serializer::builder().build();
+        let tokens = assert_ok_eq!(
+            Trial {
+                name: "foo",
+                outcome: Outcome::<&str>::Passed,
+                output: "",
+                location: None,
+                suite: Some("slow"),
+            }
+            .serialize(&serializer),
+            Tokens(vec![
+                Token::Struct {
+                    name: "Trial",
+                    len: 5
+                },
                 Token::Field("name"),
                 Token::Str("foo".to_owned()),
                 Token::Field("outcome"),
@@ -539,6 +759,13 @@ mod tests {
                     variant_index: 0,
                     variant: "Passed"
                 },
+                Token::Field("output"),
+                Token::Str("".to_owned()),
+                Token::Field("location"),
+                Token::None,
+                Token::Field("suite"),
+                Token::Some,
+                Token::Str("slow".to_owned()),
                 Token::StructEnd
             ])
         );
@@ -549,6 +776,9 @@ mod tests {
             Trial {
                 name: "foo",
                 outcome: Outcome::<&str>::Passed,
+                output: "",
+                location: None,
+                suite: Some("slow"),
             }
         );
     }
@@ -559,8 +789,10 @@ mod tests {
             .tokens(Tokens(vec![
                 Token::Struct {
                     name: "Trial",
-                    len: 2,
+                    len: 3,
                 },
+                Token::Field("output"),
+                Token::Str("".to_owned()),
                 Token::Field("outcome"),
                 Token::UnitVariant {
                     name: "Outcome",
@@ -577,6 +809,9 @@ mod tests {
             Trial {
                 name: "foo",
                 outcome: Outcome::<&str>::Passed,
+                output: "",
+                location: None,
+                suite: None,
             }
         );
     }
@@ -595,7 +830,10 @@ mod tests {
             .build();
         assert_err_eq!(
             Trial::deserialize(&mut deserializer),
-            de::Error::unknown_field("unknown", &["name", "outcome"])
+            de::Error::unknown_field(
+                "unknown",
+                &["name", "outcome", "output", "location", "suite"]
+            )
         );
     }
 
@@ -605,7 +843,7 @@ mod tests {
             .tokens(Tokens(vec![
                 Token::Struct {
                     name: "Trial",
-                    len: 1,
+                    len: 2,
                 },
                 Token::Field("outcome"),
                 Token::UnitVariant {
@@ -613,6 +851,8 @@ mod tests {
                     variant_index: 0,
                     variant: "Passed",
                 },
+                Token::Field("output"),
+                Token::Str("".to_owned()),
                 Token::StructEnd,
             ]))
             .build();
@@ -628,10 +868,12 @@ mod tests {
             .tokens(Tokens(vec![
                 Token::Struct {
                     name: "Trial",
-                    len: 1,
+                    len: 2,
                 },
                 Token::Field("name"),
                 Token::Str("foo".to_owned()),
+                Token::Field("output"),
+                Token::Str("".to_owned()),
                 Token::StructEnd,
             ]))
             .build();
@@ -641,6 +883,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn deserialize_trial_missing_field_output() {
+        let mut deserializer = Deserializer::builder()
+            .tokens(Tokens(vec![
+                Token::Struct {
+                    name: "Trial",
+                    len: 2,
+                },
+                Token::Field("name"),
+                Token::Str("foo".to_owned()),
+                Token::Field("outcome"),
+                Token::UnitVariant {
+                    name: "Outcome",
+                    variant_index: 0,
+                    variant: "Passed",
+                },
+                Token::StructEnd,
+            ]))
+            .build();
+        assert_err_eq!(
+            Trial::deserialize(&mut deserializer),
+            de::Error::missing_field("output")
+        );
+    }
+
     #[test]
     fn deserialize_trial_duplicate_field_name() {
         let mut deserializer = Deserializer::builder()
@@ -698,4 +965,95 @@ mod tests {
             de::Error::duplicate_field("outcome")
         );
     }
+
+    #[test]
+    fn deserialize_trial_duplicate_field_output() {
+        let mut deserializer = Deserializer::builder()
+            .tokens(Tokens(vec![
+                Token::Struct {
+                    name: "Trial",
+                    len: 3,
+                },
+                Token::Field("output"),
+                Token::Str("foo".to_owned()),
+                Token::Field("name"),
+                Token::Str("bar".to_owned()),
+                Token::Field("output"),
+                Token::Str("baz".to_owned()),
+                Token::StructEnd,
+            ]))
+            .build();
+        assert_err_eq!(
+            Trial::deserialize(&mut deserializer),
+            de::Error::duplicate_field("output")
+        );
+    }
+
+    #[test]
+    fn deserialize_trial_duplicate_field_location() {
+        let mut deserializer = Deserializer::builder()
+            .tokens(Tokens(vec![
+                Token::Struct {
+                    name: "Trial",
+                    len: 3,
+                },
+                Token::Field("location"),
+                Token::None,
+                Token::Field("name"),
+                Token::Str("foo".to_owned()),
+                Token::Field("location"),
+                Token::None,
+                Token::StructEnd,
+            ]))
+            .build();
+        assert_err_eq!(
+            Trial::deserialize(&mut deserializer),
+            de::Error::duplicate_field("location")
+        );
+    }
+
+    #[test]
+    fn deserialize_trial_duplicate_field_suite() {
+        let mut deserializer = Deserializer::builder()
+            .tokens(Tokens(vec![
+                Token::Struct {
+                    name: "Trial",
+                    len: 3,
+                },
+                Token::Field("suite"),
+                Token::None,
+                Token::Field("name"),
+                Token::Str("foo".to_owned()),
+                Token::Field("suite"),
+                Token::None,
+                Token::StructEnd,
+            ]))
+            .build();
+        assert_err_eq!(
+            Trial::deserialize(&mut deserializer),
+            de::Error::duplicate_field("suite")
+        );
+    }
+
+    #[test]
+    fn environment_display() {
+        assert_eq!(format!("{}", Environment::Mgba), "mGBA");
+        assert_eq!(
+            format!("{}", Environment::HardwareOrUnknown),
+            "hardware or unknown"
+        );
+    }
+
+    #[test]
+    fn environment_from_byte() {
+        assert_eq!(
+            Environment::from_byte(Environment::HardwareOrUnknown as u8),
+            Some(Environment::HardwareOrUnknown)
+        );
+        assert_eq!(
+            Environment::from_byte(Environment::Mgba as u8),
+            Some(Environment::Mgba)
+        );
+        assert_eq!(Environment::from_byte(2), None);
+    }
 }
@@ -0,0 +1,169 @@
+//! Truncation of long failure messages.
+//!
+//! Failure messages are formatted directly into the reporter, so a single large message, such as
+//! an `assert_eq!` failure comparing large arrays, can use up most of the available SRAM. Messages
+//! are therefore cut off after a configurable number of bytes.
+
+use core::{
+    fmt,
+    fmt::{Display, Formatter, Write},
+};
+
+/// The number of bytes of a failure message that are kept if no other limit has been configured.
+pub(crate) const DEFAULT_MESSAGE_LIMIT: usize = 1024;
+
+/// Writes through to another writer until a limit is reached, after which writes are only
+/// counted.
+struct Truncator<'a, 'b> {
+    formatter: &'a mut Formatter<'b>,
+    /// The number of bytes that can still be written through.
+    remaining: usize,
+    /// The total number of bytes written, including those that were not written through.
+    total: usize,
+    /// Whether any bytes have been cut off.
+    truncated: bool,
+}
+
+impl Write for Truncator<'_, '_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.total += s.len();
+        if self.truncated {
+            return Ok(());
+        }
+        if s.len() <= self.remaining {
+            self.remaining -= s.len();
+            return self.formatter.write_str(s);
+        }
+
+        // Only write complete characters, so that the message remains valid UTF-8.
+        let mut len = self.remaining;
+        while !s.is_char_boundary(len) {
+            len -= 1;
+        }
+        self.remaining = 0;
+        self.truncated = true;
+        self.formatter.write_str(&s[..len])
+    }
+}
+
+/// Displays at most `limit` bytes of a value.
+///
+/// If the value is cut off, a note containing its full length is appended. The remainder of the
+/// value is still formatted in order to determine that length, so formatting is never interrupted.
+pub(crate) struct Truncated<T> {
+    pub(crate) value: T,
+    pub(crate) limit: usize,
+}
+
+impl<T> Display for Truncated<T>
+where
+    T: Display,
+{
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        let mut truncator = Truncator {
+            formatter,
+            remaining: self.limit,
+            total: 0,
+            truncated: false,
+        };
+        write!(truncator, "{}", self.value)?;
+        if truncator.truncated {
+            write!(
+                truncator.formatter,
+                "... (message truncated, {} bytes total)",
+                truncator.total
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Truncated;
+    use alloc::format;
+
+    #[test]
+    fn shorter_than_limit() {
+        assert_eq!(
+            format!(
+                "{}",
+                Truncated {
+                    value: "foo",
+                    limit: 4
+                }
+            ),
+            "foo"
+        );
+    }
+
+    #[test]
+    fn exact_limit() {
+        assert_eq!(
+            format!(
+                "{}",
+                Truncated {
+                    value: "foob",
+                    limit: 4
+                }
+            ),
+            "foob"
+        );
+    }
+
+    #[test]
+    fn one_over_limit() {
+        assert_eq!(
+            format!(
+                "{}",
+                Truncated {
+                    value: "fooba",
+                    limit: 4
+                }
+            ),
+            "foob... (message truncated, 5 bytes total)"
+        );
+    }
+
+    #[test]
+    fn across_arguments() {
+        assert_eq!(
+            format!(
+                "{}",
+                Truncated {
+                    value: format_args!("{}{}{}", "foo", "bar", "baz"),
+                    limit: 4
+                }
+            ),
+            "foob... (message truncated, 9 bytes total)"
+        );
+    }
+
+    #[test]
+    fn char_boundary() {
+        assert_eq!(
+            format!(
+                "{}",
+                Truncated {
+                    value: "ab\u{e9}\u{e9}",
+                    limit: 3
+                }
+            ),
+            "ab... (message truncated, 6 bytes total)"
+        );
+    }
+
+    #[test]
+    fn zero_limit() {
+        assert_eq!(
+            format!(
+                "{}",
+                Truncated {
+                    value: "foo",
+                    limit: 0
+                }
+            ),
+            "... (message truncated, 3 bytes total)"
+        );
+    }
+}
@@ -0,0 +1,101 @@
+//! Detection of video memory writes outside of vertical blank.
+//!
+//! Writing to VRAM, palette RAM, or OAM while the screen is being drawn can cause visual glitches.
+//! These helpers check the current scanline when video memory is written, failing the test if the
+//! write happens while a visible scanline is being drawn.
+//!
+//! ```no_run
+//! use gba_test::vram_guard;
+//!
+//! # fn wait_for_vblank() {}
+//! wait_for_vblank();
+//! // SAFETY: This is a valid address in VRAM.
+//! unsafe {
+//!     vram_guard::checked_write(0x0600_0000 as *mut u16, 0x7FFF);
+//! }
+//! ```
+
+use voladdress::{Safe, Unsafe, VolAddress};
+
+/// Display control.
+const DISPCNT: VolAddress<u16, Safe, Unsafe> = unsafe { VolAddress::new(0x0400_0000) };
+/// Display status.
+const DISPSTAT: VolAddress<u16, Safe, Unsafe> = unsafe { VolAddress::new(0x0400_0004) };
+/// The scanline currently being drawn.
+const VCOUNT: VolAddress<u16, Safe, Unsafe> = unsafe { VolAddress::new(0x0400_0006) };
+
+/// Display control flag for forced blank.
+const DISPCNT_FORCED_BLANK: u16 = 0b0000_0000_1000_0000;
+/// Display status flag indicating vertical blank.
+const DISPSTAT_VBLANK: u16 = 0b0000_0000_0000_0001;
+
+/// Returns the scanline currently being drawn.
+///
+/// Scanlines `0` through `159` are visible, while `160` through `227` are within vertical blank.
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(all(feature = "runner", target = "thumbv4t-none-eabi")))
+)]
+pub fn scanline() -> u16 {
+    VCOUNT.read() & 0xFF
+}
+
+/// Returns whether video memory can currently be written without visual glitches.
+///
+/// This is the case during vertical blank, or while the display is in forced blank.
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(all(feature = "runner", target = "thumbv4t-none-eabi")))
+)]
+pub fn in_vblank() -> bool {
+    DISPCNT.read() & DISPCNT_FORCED_BLANK != 0 || DISPSTAT.read() & DISPSTAT_VBLANK != 0
+}
+
+/// Writes `value` to video memory at `address`, panicking if not within vertical blank.
+///
+/// # Panics
+/// Panics if the write occurs while a visible scanline is being drawn. The write is not performed
+/// in that case.
+///
+/// # Safety
+/// `address` must be valid for volatile writes of `T`.
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(all(feature = "runner", target = "thumbv4t-none-eabi")))
+)]
+#[track_caller]
+pub unsafe fn checked_write<T>(address: *mut T, value: T) {
+    if !in_vblank() {
+        panic!("VRAM write at scanline {} outside vblank", scanline());
+    }
+    // SAFETY: Guaranteed by the caller.
+    unsafe { address.write_volatile(value) }
+}
+
+/// Asserts that the display is currently within vertical blank.
+///
+/// This is useful for checking that code writing to video memory is only ever called during
+/// vertical blank. Forced blank is also accepted.
+///
+/// ```no_run
+/// use gba_test::assert_in_vblank;
+///
+/// # fn wait_for_vblank() {}
+/// wait_for_vblank();
+/// assert_in_vblank!();
+/// ```
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(all(feature = "runner", target = "thumbv4t-none-eabi")))
+)]
+#[macro_export]
+macro_rules! assert_in_vblank {
+    () => {
+        if !$crate::vram_guard::in_vblank() {
+            ::core::panic!(
+                "assertion failed: in vblank (at scanline {})",
+                $crate::vram_guard::scanline()
+            );
+        }
+    };
+}
@@ -0,0 +1,256 @@
+//! A watchdog that fails tests that run for too long.
+//!
+//! While a test is running, timer 1 is configured to overflow once per frame, and an interrupt
+//! handler counts the overflows. If the test is still running once its frame budget is exhausted,
+//! the handler abandons the test and hands control back to the runner, which records the test as
+//! failed and continues with the next one.
+//!
+//! Any interrupt handler in place before the watchdog is started is restored once it is stopped,
+//! so that a handler installed before running the tests, such as a music driver's, is serviced
+//! again between tests and after the run. While a test is running, only the watchdog's handler is
+//! called.
+//!
+//! The overflows counted while a test is running are also exposed to the test itself through
+//! [`frames()`] and [`wait_frames()`]. Timer 1 overflows exactly once per frame, but is not
+//! synchronized with the display, so the count is not aligned with vertical blank.
+//!
+//! The watchdog relies on interrupts being delivered. It can't fire while a test has interrupts
+//! disabled, whether through `IME`, `IE`, or the CPU's interrupt disable flag, or while a test has
+//! replaced the interrupt handler with its own.
+
+use core::ptr;
+use voladdress::{Safe, VolAddress};
+
+/// The address of the interrupt handler called by the BIOS.
+const IRQ_HANDLER: VolAddress<usize, Safe, Safe> = unsafe { VolAddress::new(0x0300_7FFC) };
+/// Interrupt enable.
+const IE: VolAddress<u16, Safe, Safe> = unsafe { VolAddress::new(0x0400_0200) };
+/// Interrupt request flags.
+const IF: VolAddress<u16, Safe, Safe> = unsafe { VolAddress::new(0x0400_0202) };
+/// Interrupt master enable.
+const IME: VolAddress<u16, Safe, Safe> = unsafe { VolAddress::new(0x0400_0208) };
+/// Timer 1 counter/reload.
+const TM1CNT_L: VolAddress<u16, Safe, Safe> = unsafe { VolAddress::new(0x0400_0104) };
+/// Timer 1 control.
+const TM1CNT_H: VolAddress<u16, Safe, Safe> = unsafe { VolAddress::new(0x0400_0106) };
+
+/// Interrupt flag for timer 1 overflowing.
+const INTERRUPT_TIMER_1: u16 = 0b0000_0000_0001_0000;
+/// Timer control flag for incrementing every 64 cycles.
+const TIMER_PRESCALER_64: u16 = 0b0000_0000_0000_0001;
+/// Timer control flag for requesting an interrupt on overflow.
+const TIMER_IRQ: u16 = 0b0000_0000_0100_0000;
+/// Timer control flag for starting the timer.
+const TIMER_ENABLE: u16 = 0b0000_0000_1000_0000;
+
+/// The number of timer ticks in a single frame, when incrementing every 64 cycles.
+const TICKS_PER_FRAME: u16 = 4389;
+
+/// The number of frames a test may run for if no other timeout has been configured.
+///
+/// This is roughly 30 seconds.
+pub(crate) const DEFAULT_TIMEOUT: u32 = 1800;
+
+/// The number of frames remaining before the current test times out.
+static mut REMAINING: u32 = 0;
+/// The number of frames the current test was given when the watchdog was started.
+///
+/// This is `0` when the watchdog is not running.
+static mut BUDGET: u32 = 0;
+/// The total number of frames counted while the watchdog was running.
+static mut ELAPSED: u32 = 0;
+/// The interrupt handler in place when the watchdog was started.
+static mut PREVIOUS_IRQ_HANDLER: usize = 0;
+
+extern "C" {
+    /// The watchdog's interrupt handler, defined below.
+    fn __gba_test_watchdog_irq();
+}
+
+// The interrupt handler is called by the BIOS in IRQ mode, so it must be ARM code.
+//
+// When the timeout is reached, the handler does not return to the BIOS. Instead, it resets the
+// IRQ and Supervisor stacks to the BIOS defaults and switches back to System mode with interrupts
+// enabled, continuing on top of the test's stack in the same way the panic handler does. Resetting
+// the stacks discards the registers the BIOS pushed to the IRQ stack, along with anything left on
+// the Supervisor stack by a BIOS call the test was interrupted in, such as the `IntrWait` made by
+// `wait_frames()`. Otherwise, every timeout within a BIOS call would leak part of the small
+// Supervisor stack, until it overflowed into the IRQ stack.
+core::arch::global_asm!(
+    ".pushsection .text.__gba_test_watchdog_irq, \"ax\", %progbits",
+    ".arm",
+    ".align 2",
+    ".global __gba_test_watchdog_irq",
+    "__gba_test_watchdog_irq:",
+    // Acknowledge all raised interrupts, including for the BIOS's `IntrWait`.
+    "mov r0, #0x04000000",
+    "add r0, r0, #0x200",
+    "ldrh r1, [r0]",
+    "ldrh r2, [r0, #2]",
+    "and r1, r1, r2",
+    "strh r1, [r0, #2]",
+    "ldr r2, =0x03007FF8",
+    "ldrh r3, [r2]",
+    "orr r3, r3, r1",
+    "strh r3, [r2]",
+    // Count down a frame if timer 1 overflowed.
+    "tst r1, #0x10",
+    "bxeq lr",
+    "ldr r2, ={remaining}",
+    "ldr r3, [r2]",
+    "subs r3, r3, #1",
+    "str r3, [r2]",
+    "bxne lr",
+    // The timeout has been reached. Stop the timer.
+    "mov r1, #0",
+    "mov r2, #0x04000000",
+    "add r2, r2, #0x100",
+    "strh r1, [r2, #6]",
+    // Reset the IRQ stack, discarding `r0-r3`, `r12`, and `lr`, pushed by the BIOS.
+    "ldr sp, =0x03007FA0",
+    // Reset the Supervisor stack, with interrupts disabled.
+    "mov r0, #0xd3",
+    "msr cpsr_c, r0",
+    "ldr sp, =0x03007FE0",
+    // Switch to System mode with interrupts enabled.
+    "mov r0, #0x1f",
+    "msr cpsr_c, r0",
+    "ldr r0, ={expired}",
+    "bx r0",
+    ".ltorg",
+    ".popsection",
+    remaining = sym REMAINING,
+    expired = sym crate::runner::watchdog_expired,
+);
+
+/// Starts the watchdog, failing the current test if it runs for longer than `frames` frames.
+///
+/// If `frames` is `0`, the watchdog is not started.
+pub(crate) fn arm(frames: u32) {
+    if frames == 0 {
+        return;
+    }
+    // SAFETY: `REMAINING` is only accessed by the interrupt handler while the timer is running,
+    // and `BUDGET` is only ever accessed on the main thread.
+    unsafe {
+        *ptr::addr_of_mut!(REMAINING) = frames;
+        BUDGET = frames;
+        PREVIOUS_IRQ_HANDLER = IRQ_HANDLER.read();
+    }
+    TM1CNT_H.write(0);
+    TM1CNT_L.write(0u16.wrapping_sub(TICKS_PER_FRAME));
+    IRQ_HANDLER.write(__gba_test_watchdog_irq as unsafe extern "C" fn() as usize);
+    IF.write(INTERRUPT_TIMER_1);
+    IE.write(IE.read() | INTERRUPT_TIMER_1);
+    IME.write(1);
+    TM1CNT_H.write(TIMER_ENABLE | TIMER_IRQ | TIMER_PRESCALER_64);
+}
+
+/// Stops the watchdog, adding the frames counted since it was started to the total.
+///
+/// The interrupt handler in place when the watchdog was started is restored, if there was one.
+///
+/// Returns the number of whole frames counted since the watchdog was started, or `0` if it was
+/// not running.
+pub(crate) fn disarm() -> u32 {
+    TM1CNT_H.write(0);
+    IE.write(IE.read() & !INTERRUPT_TIMER_1);
+    // SAFETY: The timer is stopped, so `REMAINING` is no longer accessed by the interrupt
+    // handler. `BUDGET`, `ELAPSED`, and `PREVIOUS_IRQ_HANDLER` are only ever accessed on the main
+    // thread.
+    unsafe {
+        if BUDGET == 0 {
+            return 0;
+        }
+        // Without a handler, an interrupt still enabled by the test would jump to address `0`,
+        // so the watchdog's handler is left in place.
+        if PREVIOUS_IRQ_HANDLER != 0 {
+            IRQ_HANDLER.write(PREVIOUS_IRQ_HANDLER);
+        }
+        let frames = BUDGET - *ptr::addr_of!(REMAINING);
+        ELAPSED += frames;
+        BUDGET = 0;
+        frames
+    }
+}
+
+/// Returns the number of frames elapsed since the current test started.
+///
+/// Frames are counted by the watchdog's timer, which is restarted at the beginning of each test,
+/// so the count is relative to the start of the current test. The count increases by exactly one
+/// per frame, but is not aligned with vertical blank. This is useful for asserting on code that
+/// depends on the passage of frames, such as animations.
+///
+/// This returns `0` if no test is running.
+///
+/// ```no_run
+/// gba_test::wait_frames(3);
+/// assert!(gba_test::frames() >= 3);
+/// ```
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(all(feature = "runner", target = "thumbv4t-none-eabi")))
+)]
+pub fn frames() -> u32 {
+    // SAFETY: `BUDGET` is only ever accessed on the main thread. `REMAINING` is only written by
+    // the interrupt handler while the timer is running, and a single aligned word is read
+    // atomically.
+    unsafe {
+        if BUDGET == 0 {
+            return 0;
+        }
+        BUDGET - ptr::read_volatile(ptr::addr_of!(REMAINING))
+    }
+}
+
+/// Waits for `frames` frames to elapse, halting the CPU in between.
+///
+/// Once this returns, [`frames()`] has increased by exactly `frames`. If the test has masked
+/// timer 1's interrupt or disabled interrupts using `IME`, they are enabled again before waiting,
+/// leaving the other interrupts untouched. If the test's timeout is reached while waiting, the
+/// test fails as usual.
+///
+/// # Panics
+/// Panics if no test is running, or if the test has stopped timer 1 or replaced the interrupt
+/// handler, as frames would then never be counted.
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(all(feature = "runner", target = "thumbv4t-none-eabi")))
+)]
+pub fn wait_frames(frames: u32) {
+    // SAFETY: `BUDGET` is only ever accessed on the main thread.
+    if unsafe { BUDGET } == 0 {
+        panic!("`wait_frames()` must only be called while a test is running");
+    }
+    for _ in 0..frames {
+        // Waiting without the watchdog's timer and handler would halt forever.
+        if TM1CNT_H.read() & TIMER_ENABLE == 0 {
+            panic!("`wait_frames()` can't count frames, as timer 1 has been stopped");
+        }
+        if IRQ_HANDLER.read() != __gba_test_watchdog_irq as unsafe extern "C" fn() as usize {
+            panic!(
+                "`wait_frames()` can't count frames, as the interrupt handler has been replaced"
+            );
+        }
+        IE.write(IE.read() | INTERRUPT_TIMER_1);
+        IME.write(1);
+        // SAFETY: The BIOS `IntrWait` function only halts until the next timer 1 interrupt, which
+        // the watchdog's handler acknowledges to the BIOS.
+        unsafe {
+            core::arch::asm!(
+                "swi #0x04",
+                inlateout("r0") 1u32 => _,
+                inlateout("r1") INTERRUPT_TIMER_1 as u32 => _,
+                lateout("r2") _,
+                lateout("r3") _,
+                lateout("r12") _,
+            );
+        }
+    }
+}
+
+/// Returns the total number of whole frames counted while the watchdog was running.
+pub(crate) fn elapsed() -> u32 {
+    // SAFETY: `ELAPSED` is only ever accessed on the main thread.
+    unsafe { ELAPSED }
+}
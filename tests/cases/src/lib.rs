@@ -0,0 +1,24 @@
+//! Defines parameterized tests, some cases of which fail.
+
+#![no_std]
+#![no_main]
+#![feature(custom_test_frameworks)]
+#![test_runner(gba_test::runner)]
+#![reexport_test_harness_main = "test_harness"]
+
+gba_test::harness_main!();
+
+#[cfg(test)]
+mod tests {
+    use gba_test::test;
+
+    #[test(cases(1, 2, 3))]
+    fn doubles(x: u32) {
+        assert_eq!(x + x, x * 2);
+    }
+
+    #[test(cases(2, 3))]
+    fn even(x: u32) {
+        assert!(x % 2 == 0, "{} is odd", x);
+    }
+}
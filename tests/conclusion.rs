@@ -1,53 +1,39 @@
-#![cfg(all(feature = "postcard", feature = "alloc"))]
+#![cfg(feature = "std")]
 
 use cargo_metadata::Message;
-use gba_test::{Outcome, Trial};
+use gba_test::{
+    elf::TestMeta, read_environment, read_seed, read_trials, Environment, ExitCode, Location,
+    Outcome, Trial, TrialOwned, Truncation,
+};
 use std::{
     env, fs,
     path::PathBuf,
     process::{Command, Stdio},
 };
 
-#[test]
-fn pass() {
-    // Build and run the test.
-    let mut command = Command::new("cargo")
-        .args([
-            "test",
-            #[cfg(not(debug_assertions))]
-            "--release",
-            "--message-format=json-render-diagnostics",
-        ])
-        .stdout(Stdio::piped())
-        .current_dir("tests/pass")
-        .spawn()
-        .expect("failed to build test");
+/// Builds and runs the test crate at `tests/<name>`, returning the contents of its save file.
+///
+/// The returned data is guaranteed to be a complete serialization of the test results.
+fn run(name: &str) -> Vec<u8> {
+    let rom = rom(name);
 
-    // Find the executable name.
-    let reader = std::io::BufReader::new(command.stdout.as_mut().unwrap());
-    let mut executable_name = None;
-    for message in Message::parse_stream(reader) {
-        match message.unwrap() {
-            Message::CompilerArtifact(artifact) => {
-                if let Some(executable) = artifact.executable {
-                    executable_name = Some(executable);
-                }
-            }
-            Message::BuildFinished(_) => {
-                break;
-            }
-            _ => (), // Unknown message
-        }
-    }
+    // Run the test.
+    let mut command = Command::new("mgba")
+        .args(["-C", "savegamePath=."])
+        .arg(&rom)
+        .current_dir(format!("tests/{}", name))
+        .spawn()
+        .expect("failed to run test");
 
     // Produce the save file name.
     let save_file = {
-        let mut save_file = PathBuf::from(executable_name.expect("unable to find executable name"));
+        let mut save_file = rom;
         save_file.set_extension("sav");
         env::current_dir()
             .expect("unable to find current directory")
             .join(format!(
-                "tests/pass/{}",
+                "tests/{}/{}",
+                name,
                 save_file
                     .file_name()
                     .expect("unable to obtain save file name")
@@ -56,24 +42,24 @@ fn pass() {
             ))
     };
 
+    // Wait for the results to be completely written. The save file may exist before the results
+    // header has been written, so a file without the header is waited on as well.
+    let mut timeout = 0;
+    let mut header_missing = false;
     let output = loop {
         if let Ok(output) = fs::read(&save_file) {
-            break output;
-        }
-        std::thread::sleep(std::time::Duration::from_secs(1));
-    };
-
-    let mut timeout = 0;
-    let trials: Vec<Trial<&str>> = loop {
-        if let Ok(result) = postcard::from_bytes::<Result<_, &str>>(&output) {
-            match result {
-                Ok(trials) => break trials,
-                _ => continue,
+            match read_trials(&output).1 {
+                Some(Truncation::Incomplete) => header_missing = false,
+                Some(Truncation::UnrecognizedFormat) => header_missing = true,
+                _ => break output,
             }
         }
         std::thread::sleep(std::time::Duration::from_secs(1));
         timeout += 1;
         if timeout >= 30 {
+            if header_missing {
+                panic!("the save file does not begin with the results header");
+            }
             panic!("did not execute successfully");
         }
     };
@@ -87,183 +73,236 @@ fn pass() {
         command.kill();
     }
 
-    // Compare the output with the expected output.
-    assert_eq!(
-        trials,
-        vec![Trial {
-            name: "it_works",
-            outcome: Outcome::Passed,
-        }],
-    );
+    output
 }
 
-#[test]
-fn ignore() {
-    // Build and run the test.
-    let mut command = Command::new("cargo")
+/// Reads the results from the contents of a save file, requiring that every test's result is
+/// present.
+fn complete_trials(output: &[u8]) -> Vec<TrialOwned> {
+    let (trials, truncation) = read_trials(output);
+    assert_eq!(truncation, None);
+    trials.into_iter().map(TrialOwned::from).collect()
+}
+
+/// Builds the test crate at `tests/<name>` without running it, returning the path to its executable.
+fn build(name: &str) -> PathBuf {
+    let output = Command::new("cargo")
         .args([
             "test",
+            "--no-run",
             #[cfg(not(debug_assertions))]
             "--release",
             "--message-format=json-render-diagnostics",
         ])
-        .stdout(Stdio::piped())
-        .current_dir("tests/ignore")
-        .spawn()
+        .current_dir(format!("tests/{}", name))
+        .output()
         .expect("failed to build test");
 
     // Find the executable name.
-    let reader = std::io::BufReader::new(command.stdout.as_mut().unwrap());
     let mut executable_name = None;
-    for message in Message::parse_stream(reader) {
-        match message.unwrap() {
-            Message::CompilerArtifact(artifact) => {
-                if let Some(executable) = artifact.executable {
-                    executable_name = Some(executable);
-                }
+    for message in Message::parse_stream(output.stdout.as_slice()) {
+        if let Message::CompilerArtifact(artifact) = message.unwrap() {
+            if let Some(executable) = artifact.executable {
+                executable_name = Some(executable);
             }
-            Message::BuildFinished(_) => {
-                break;
-            }
-            _ => (), // Unknown message
         }
     }
+    executable_name
+        .expect("unable to find executable name")
+        .into()
+}
 
-    // Produce the save file name.
-    let save_file = {
-        let mut save_file = PathBuf::from(executable_name.expect("unable to find executable name"));
-        save_file.set_extension("sav");
-        env::current_dir()
-            .expect("unable to find current directory")
-            .join(format!(
-                "tests/ignore/{}",
-                save_file
-                    .file_name()
-                    .expect("unable to obtain save file name")
-                    .to_str()
-                    .expect("unable to convert save file name to string")
-            ))
+/// Returns the size of the section called `name` in an ELF file, or `0` if there is no such
+/// section.
+fn section_size(elf: &[u8], name: &str) -> u32 {
+    let read_u16 = |offset: usize| u16::from_le_bytes([elf[offset], elf[offset + 1]]) as usize;
+    let read_u32 = |offset: usize| {
+        u32::from_le_bytes([
+            elf[offset],
+            elf[offset + 1],
+            elf[offset + 2],
+            elf[offset + 3],
+        ])
     };
+    let section_headers = read_u32(0x20) as usize;
+    let section_header_size = read_u16(0x2E);
+    let section_count = read_u16(0x30);
+    let names = section_headers + read_u16(0x32) * section_header_size;
+    let names = &elf[read_u32(names + 16) as usize..];
 
-    let output = loop {
-        if let Ok(output) = fs::read(&save_file) {
-            break output;
-        }
-        std::thread::sleep(std::time::Duration::from_secs(1));
-    };
+    (0..section_count)
+        .map(|index| section_headers + index * section_header_size)
+        .find(|&header| {
+            names[read_u32(header) as usize..]
+                .split(|&byte| byte == 0)
+                .next()
+                == Some(name.as_bytes())
+        })
+        .map(|header| read_u32(header + 20))
+        .unwrap_or(0)
+}
+
+/// Builds the test crate at `tests/<name>` and converts it into a ROM, returning the path to the
+/// ROM.
+fn rom(name: &str) -> PathBuf {
+    let executable = build(name);
+    let elf = fs::read(&executable).expect("failed to read executable");
+    let rom = gba_test::rom::from_elf(&elf).expect("failed to convert executable into a ROM");
+    let path = executable.with_extension("gba");
+    fs::write(&path, rom).expect("failed to write ROM");
+    path
+}
+
+/// Builds the test crate at `tests/<name>` and runs it using `mgba-rom-test`, returning the
+/// emulator's exit code and the lines it logged.
+fn emulate(name: &str) -> (i32, String) {
+    let rom = rom(name);
 
+    // Run the test, exiting on the runner's final `swi #0x03` with the code in `r0`. Only errors,
+    // warnings, and informational messages are logged.
+    let mut command = Command::new("mgba-rom-test")
+        .args(["-S", "0x03", "-R", "r0", "-l", "15"])
+        .arg(rom)
+        .current_dir(format!("tests/{}", name))
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("failed to run test");
+
+    // Wait for the emulator to exit.
     let mut timeout = 0;
-    let trials: Vec<Trial<&str>> = loop {
-        if let Ok(result) = postcard::from_bytes::<Result<_, &str>>(&output) {
-            match result {
-                Ok(trials) => break trials,
-                _ => continue,
-            }
+    loop {
+        if command
+            .try_wait()
+            .expect("unable to wait for test")
+            .is_some()
+        {
+            let output = command
+                .wait_with_output()
+                .expect("unable to read emulator output");
+            return (
+                output
+                    .status
+                    .code()
+                    .expect("emulator exited without an exit code"),
+                String::from_utf8_lossy(&output.stdout).into_owned(),
+            );
         }
         std::thread::sleep(std::time::Duration::from_secs(1));
         timeout += 1;
         if timeout >= 30 {
+            // Best-effort cleanup.
+            #[allow(unused_must_use)]
+            {
+                command.kill();
+            }
             panic!("did not execute successfully");
         }
-    };
-
-    // Clean up.
-    fs::remove_file(save_file).expect("could not delete save file");
-    // It's fine for this not to succeed, as the test has already been completed. This cleanup is
-    // best-effort only.
-    #[allow(unused_must_use)]
-    {
-        command.kill();
     }
+}
+
+/// Builds the test crate at `tests/<name>` and runs it using `mgba-rom-test`, returning the
+/// emulator's exit code.
+fn exit_code(name: &str) -> i32 {
+    emulate(name).0
+}
+
+/// Builds the test crate at `tests/<name>` and runs it using `mgba-rom-test`, returning the
+/// summary line logged once all tests have been run, without its duration.
+fn summary(name: &str) -> String {
+    let (_, log) = emulate(name);
+    let line = log
+        .lines()
+        .find_map(|line| {
+            line.split_once("gba_test: ")
+                .map(|(_, summary)| summary)
+                .filter(|summary| summary.starts_with("result="))
+        })
+        .expect("no summary line was logged");
+    let (summary, duration) = line
+        .rsplit_once("; duration_frames=")
+        .expect("summary line has no duration");
+    duration
+        .trim()
+        .parse::<u32>()
+        .expect("duration is not a number");
+    summary.to_owned()
+}
+
+#[test]
+fn pass() {
+    let output = run("pass");
+    let trials = complete_trials(&output);
 
     // Compare the output with the expected output.
     assert_eq!(
         trials,
         vec![Trial {
             name: "it_works",
-            outcome: Outcome::Ignored,
+            outcome: Outcome::Passed,
+            output: "",
+            location: Some(Location {
+                file: "src/lib.rs",
+                line: 21,
+            }),
+            suite: None,
         }],
     );
 }
 
 #[test]
-fn fail() {
-    // Build and run the test.
-    let mut command = Command::new("cargo")
-        .args([
-            "test",
-            #[cfg(not(debug_assertions))]
-            "--release",
-            "--message-format=json-render-diagnostics",
-        ])
-        .stdout(Stdio::piped())
-        .current_dir("tests/fail")
-        .spawn()
-        .expect("failed to build test");
+fn framing() {
+    let output = run("pass");
 
-    // Find the executable name.
-    let reader = std::io::BufReader::new(command.stdout.as_mut().unwrap());
-    let mut executable_name = None;
-    for message in Message::parse_stream(reader) {
-        match message.unwrap() {
-            Message::CompilerArtifact(artifact) => {
-                if let Some(executable) = artifact.executable {
-                    executable_name = Some(executable);
-                }
-            }
-            Message::BuildFinished(_) => {
-                break;
-            }
-            _ => (), // Unknown message
-        }
-    }
+    // The results begin with the magic and the version of the format.
+    assert_eq!(&output[..4], b"GBAT");
+    assert_eq!(u16::from_le_bytes([output[4], output[5]]), 2);
+    assert_eq!(read_trials(&output).1, None);
+}
 
-    // Produce the save file name.
-    let save_file = {
-        let mut save_file = PathBuf::from(executable_name.expect("unable to find executable name"));
-        save_file.set_extension("sav");
-        env::current_dir()
-            .expect("unable to find current directory")
-            .join(format!(
-                "tests/fail/{}",
-                save_file
-                    .file_name()
-                    .expect("unable to obtain save file name")
-                    .to_str()
-                    .expect("unable to convert save file name to string")
-            ))
-    };
+#[test]
+fn environment() {
+    let output = run("pass");
 
-    let output = loop {
-        if let Ok(output) = fs::read(&save_file) {
-            break output;
-        }
-        std::thread::sleep(std::time::Duration::from_secs(1));
-    };
+    assert_eq!(read_environment(&output), Some(Environment::Mgba));
+}
 
-    let mut timeout = 0;
-    let trials: Vec<Trial<&str>> = loop {
-        if let Ok(result) = postcard::from_bytes::<Result<_, &str>>(&output) {
-            match result {
-                Ok(trials) => break trials,
-                _ => continue,
-            }
-        }
-        std::thread::sleep(std::time::Duration::from_secs(1));
-        timeout += 1;
-        if timeout >= 30 {
-            panic!("did not execute successfully");
-        }
-    };
+#[test]
+fn ignore() {
+    let output = run("ignore");
+    let trials = complete_trials(&output);
 
-    // Clean up.
-    fs::remove_file(save_file).expect("could not delete save file");
-    // It's fine for this not to succeed, as the test has already been completed. This cleanup is
-    // best-effort only.
-    #[allow(unused_must_use)]
-    {
-        command.kill();
-    }
+    // Compare the output with the expected output.
+    assert_eq!(
+        trials,
+        vec![
+            Trial {
+                name: "it_works",
+                outcome: Outcome::Ignored,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 22,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "with_reason",
+                outcome: Outcome::Ignored,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 29,
+                }),
+                suite: None,
+            },
+        ],
+    );
+}
+
+#[test]
+fn fail() {
+    let output = run("fail");
+    let trials = complete_trials(&output);
 
     // Compare the output with the expected output.
     assert_eq!(
@@ -271,8 +310,2455 @@ fn fail() {
         vec![Trial {
                 name: "it_works",
                 outcome: Outcome::Failed {
-                    message: "panicked at 'assertion failed: `(left == right)`\n  left: `4`,\n right: `5`', src/lib.rs:28:9",
+                    message: "panicked at 'assertion failed: `(left == right)`\n  left: `4`,\n right: `5`', src/lib.rs:23:9",
                 },
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 21,
+                }),
+                suite: None,
             }],
     );
 }
+
+#[test]
+fn empty() {
+    let output = run("empty");
+    let trials = complete_trials(&output);
+
+    // Compare the output with the expected output.
+    assert_eq!(trials, Vec::<TrialOwned>::new());
+}
+
+#[test]
+fn vram_clear() {
+    let output = run("vram_clear");
+    let trials = complete_trials(&output);
+
+    // Compare the output with the expected output.
+    assert_eq!(
+        trials,
+        vec![
+            Trial {
+                name: "a_write_sentinel",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 20,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "b_sentinel_cleared",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 26,
+                }),
+                suite: None,
+            }
+        ],
+    );
+}
+
+#[test]
+fn shuffle() {
+    let output = run("shuffle");
+    let mut trials = complete_trials(&output);
+    let seed = read_seed(&output);
+
+    // The seed is the default seed stored in the ROM.
+    assert_eq!(seed, Some(0x9E37_79B9));
+    // Every test should have been run exactly once.
+    trials.sort_by(|a, b| a.name.cmp(&b.name));
+    assert_eq!(
+        trials,
+        vec![
+            Trial {
+                name: "a",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 21,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "b",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 26,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "c",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 31,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "d",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 36,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "e",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 41,
+                }),
+                suite: None,
+            },
+        ],
+    );
+}
+
+#[test]
+fn perf() {
+    let output = run("perf");
+    let trials = complete_trials(&output);
+
+    // Compare the output with the expected output.
+    assert_eq!(
+        trials,
+        vec![
+            Trial {
+                name: "assert_cycles_under",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 58,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "busy_loop_in_range",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 41,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "busy_loop_scales",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 50,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "conversions",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 64,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "empty",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 36,
+                }),
+                suite: None,
+            },
+        ],
+    );
+}
+
+#[test]
+fn output() {
+    let output = run("output");
+    let trials = complete_trials(&output);
+
+    // Compare the output with the expected output.
+    assert_eq!(
+        trials,
+        vec![
+            Trial {
+                name: "fails",
+                outcome: Outcome::Failed {
+                    message: "panicked at 'failed', src/lib.rs:18:9",
+                },
+                output: "before failure\n",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 16,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "passes",
+                outcome: Outcome::Passed,
+                output: "foo\n1 + 2 = 3\n",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 22,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "silent",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 28,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "truncated",
+                outcome: Outcome::Passed,
+                // Output is capped at 512 bytes.
+                output: &"0123456789\n".repeat(47)[..512],
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 31,
+                }),
+                suite: None,
+            },
+        ],
+    );
+}
+
+#[test]
+fn duplicate() {
+    let output = run("duplicate");
+    let trials = complete_trials(&output);
+
+    // Compare the output with the expected output.
+    assert_eq!(
+        trials,
+        vec![
+            Trial {
+                name: "same",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 16,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "unique",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 19,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "same#2",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 27,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "same#3",
+                outcome: Outcome::Failed {
+                    message: "panicked at 'failed', src/lib.rs:36:9",
+                },
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 35,
+                }),
+                suite: None,
+            },
+        ],
+    );
+}
+
+#[test]
+fn watchdog() {
+    let output = run("watchdog");
+    let trials = complete_trials(&output);
+
+    // Compare the output with the expected output.
+    assert_eq!(
+        trials,
+        vec![
+            Trial {
+                name: "hangs",
+                outcome: Outcome::Failed {
+                    message: "test timed out after 10 frames",
+                },
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 22,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "passes",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 29,
+                }),
+                suite: None,
+            },
+        ],
+    );
+}
+
+#[test]
+fn watchdog_wait() {
+    let output = run("watchdog_wait");
+    let trials = complete_trials(&output);
+    let names = (1..=8)
+        .map(|attempt| format!("times_out[{}]", attempt))
+        .collect::<Vec<_>>();
+
+    // Compare the output with the expected output.
+    assert_eq!(
+        trials,
+        names
+            .iter()
+            .map(|name| Trial {
+                name,
+                outcome: Outcome::Failed {
+                    message: "test timed out after 5 frames",
+                },
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 25,
+                }),
+                suite: None,
+            })
+            .chain([Trial {
+                name: "waits",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 30,
+                }),
+                suite: None,
+            }])
+            .collect::<Vec<_>>(),
+    );
+}
+
+#[test]
+fn cases() {
+    let output = run("cases");
+    let trials = complete_trials(&output);
+
+    // Compare the output with the expected output.
+    assert_eq!(
+        trials,
+        vec![
+            Trial {
+                name: "doubles[1]",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 16,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "doubles[2]",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 16,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "doubles[3]",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 16,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "even[2]",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 21,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "even[3]",
+                outcome: Outcome::Failed {
+                    message: "panicked at '3 is odd', src/lib.rs:22:9",
+                },
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 21,
+                }),
+                suite: None,
+            },
+        ],
+    );
+}
+
+#[test]
+fn failed_names() {
+    let output = run("failed_names");
+    let trials = complete_trials(&output);
+
+    // Compare the output with the expected output.
+    assert_eq!(
+        trials,
+        vec![
+            Trial {
+                name: "fails",
+                outcome: Outcome::Failed {
+                    message: "panicked at 'failure', src/lib.rs:36:9",
+                },
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 35,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "fails_again",
+                outcome: Outcome::Failed {
+                    message: "panicked at 'assertion failed: `(left == right)`\n  left: `1`,\n right: `2`', src/lib.rs:41:9",
+                },
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 40,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "passes",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 45,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "recorded",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 48,
+                }),
+                suite: None,
+            },
+        ],
+    );
+}
+
+#[test]
+fn failure_formatter() {
+    let output = run("failure_formatter");
+    let trials = complete_trials(&output);
+
+    // Compare the output with the expected output.
+    assert_eq!(
+        trials,
+        vec![
+            Trial {
+                name: "custom",
+                outcome: Outcome::Failed {
+                    message: "custom failure message at line 44",
+                },
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 43,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "expected_mismatch",
+                outcome: Outcome::Failed {
+                    message: "panic message: \"custom failure message at line 50\", expected substring: \"foo\"",
+                },
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 49,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "fallback",
+                outcome: Outcome::Failed {
+                    message: "panicked at 'baz', src/lib.rs:59:9",
+                },
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 54,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "passes",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 63,
+                }),
+                suite: None,
+            },
+        ],
+    );
+}
+
+#[test]
+fn format_error() {
+    let output = run("format_error");
+    let trials = complete_trials(&output);
+
+    // Compare the output with the expected output.
+    assert_eq!(
+        trials,
+        vec![
+            Trial {
+                name: "fails",
+                outcome: Outcome::Failed {
+                    message: "panicked at 'formatted', src/lib.rs:30:9",
+                },
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 29,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "passes",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 34,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "unformattable",
+                outcome: Outcome::Failed {
+                    message: "<failure message could not be formatted>",
+                },
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 37,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "unformattable_after",
+                outcome: Outcome::Failed {
+                    message: "panicked at 'formatted after', src/lib.rs:43:9",
+                },
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 42,
+                }),
+                suite: None,
+            },
+        ],
+    );
+}
+
+#[test]
+fn frames() {
+    let output = run("frames");
+    let trials = complete_trials(&output);
+
+    // Compare the output with the expected output.
+    assert_eq!(
+        trials,
+        vec![
+            Trial {
+                name: "advances",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 61,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "interrupts_disabled",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 68,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "masked_interrupt",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 76,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "relative_to_test",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 84,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "replaced_handler",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 91,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "stopped_timer",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 98,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "wait_none",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 104,
+                }),
+                suite: None,
+            },
+        ],
+    );
+}
+
+#[test]
+fn framework_panic() {
+    let output = run("framework_panic");
+    let trials = complete_trials(&output);
+
+    // Compare the output with the expected output.
+    assert_eq!(
+        trials,
+        vec![
+            Trial {
+                name: "a_passes",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 44,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "faulty",
+                outcome: Outcome::Failed {
+                    message: "framework error: panicked at 'unable to determine frame budget', src/lib.rs:32:9",
+                },
+                output: "",
+                location: None,
+                suite: None,
+            },
+            Trial {
+                name: "c_passes",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 54,
+                }),
+                suite: None,
+            },
+        ],
+    );
+}
+
+#[test]
+fn global_setup() {
+    let output = run("global_setup");
+    let trials = complete_trials(&output);
+
+    // Compare the output with the expected output.
+    assert_eq!(
+        trials,
+        vec![
+            Trial {
+                name: "first",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 29,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "panics",
+                outcome: Outcome::Failed {
+                    message: "panicked at 'failed', src/lib.rs:36:9",
+                },
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 35,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "second",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 40,
+                }),
+                suite: None,
+            },
+        ],
+    );
+}
+
+#[test]
+fn global_setup_panic() {
+    let output = run("global_setup_panic");
+    let (trials, truncation) = read_trials(&output);
+
+    // Compare the output with the expected output.
+    assert_eq!(trials, vec![]);
+    assert_eq!(
+        truncation,
+        Some(Truncation::Aborted {
+            message: "global setup failed: panicked at 'calibration failed', src/lib.rs:11:5"
+        })
+    );
+}
+
+#[test]
+fn golden() {
+    let output = run("golden");
+    let trials = complete_trials(&output);
+
+    // Compare the output with the expected output.
+    assert_eq!(
+        trials,
+        vec![
+            Trial {
+                name: "matches",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 33,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "mismatch",
+                outcome: Outcome::Failed {
+                    message: "panicked at 'memory does not match golden file \"golden/gradient.bin\": 3 of 256 bytes differ from golden, first at offset 0x40\ndifferences by section:\n      0      0      0      0\n      1      0      0      0\n      0      0      0      0\n      0      0      0      2', src/lib.rs:48:13",
+                },
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 41,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "perturbed_buffer",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 53,
+                }),
+                suite: None,
+            },
+        ],
+    );
+}
+
+#[test]
+fn io_diff() {
+    let output = run("io_diff");
+    let trials = complete_trials(&output);
+
+    // Compare the output with the expected output.
+    assert_eq!(
+        trials,
+        vec![
+            Trial {
+                name: "changes_registers",
+                outcome: Outcome::Failed {
+                    message: "panicked at 'foo', src/lib.rs:40:9\n--- changed registers ---\nDISPCNT 0080→0403, IE 0000→0001",
+                },
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 37,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "passes_with_changes",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 44,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "unchanged",
+                outcome: Outcome::Failed {
+                    message: "panicked at 'bar', src/lib.rs:50:9",
+                },
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 49,
+                }),
+                suite: None,
+            },
+        ],
+    );
+}
+
+#[test]
+fn isa() {
+    let output = run("isa");
+    let trials = complete_trials(&output);
+
+    // Compare the output with the expected output.
+    assert_eq!(
+        trials,
+        vec![
+            Trial {
+                name: "arm",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 33,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "cases[1]::arm",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 43,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "cases[1]::thumb",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 43,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "cases[2]::arm",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 43,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "cases[2]::thumb",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 43,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "function_pointer::arm",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 38,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "function_pointer::thumb",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 38,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "thumb",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 48,
+                }),
+                suite: None,
+            },
+        ],
+    );
+}
+
+#[test]
+fn list_only() {
+    let output = run("list_only");
+    let trials = complete_trials(&output);
+
+    // Compare the output with the expected output.
+    assert_eq!(
+        trials,
+        vec![
+            Trial {
+                name: "fails",
+                outcome: Outcome::Ignored,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 28,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "ignored",
+                outcome: Outcome::Ignored,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 34,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "passes",
+                outcome: Outcome::Ignored,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 37,
+                }),
+                suite: None,
+            },
+        ],
+    );
+}
+
+#[test]
+fn skip() {
+    let output = run("skip");
+    let trials = complete_trials(&output);
+
+    // Compare the output with the expected output.
+    assert_eq!(
+        trials,
+        vec![
+            Trial {
+                name: "formatted",
+                outcome: Outcome::Ignored,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 16,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "not_skipped",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 21,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "should_err",
+                outcome: Outcome::Ignored,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 29,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "should_panic",
+                outcome: Outcome::Ignored,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 35,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "skipped",
+                outcome: Outcome::Ignored,
+                output: "probing for rumble cart\n",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 40,
+                }),
+                suite: None,
+            },
+        ],
+    );
+}
+
+#[test]
+fn requires() {
+    let output = run("requires");
+    let trials = complete_trials(&output);
+
+    // Compare the output with the expected output.
+    assert_eq!(
+        trials,
+        vec![
+            Trial {
+                name: "link_cable",
+                outcome: Outcome::Ignored,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 16,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "rtc",
+                outcome: Outcome::Ignored,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 21,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "rtc_should_panic",
+                outcome: Outcome::Ignored,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 27,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "sram",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 30,
+                }),
+                suite: None,
+            },
+        ],
+    );
+}
+
+#[test]
+fn log_assertions() {
+    let output = run("log_assertions");
+    let trials = complete_trials(&output);
+
+    // Compare the output with the expected output.
+    assert_eq!(
+        trials,
+        vec![
+            Trial {
+                name: "cleared",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 17,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "level_mismatch",
+                outcome: Outcome::Failed {
+                    message: "panicked at 'no WARN record containing \"battery\" was logged; captured records:\nINFO log_assertions::tests: low battery', src/lib.rs:24:9\n--- captured log ---\nINFO low battery",
+                },
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 22,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "matched",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 28,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "not_matched",
+                outcome: Outcome::Failed {
+                    message: "panicked at 'no WARN record containing \"battery\" was logged; captured records:\nINFO log_assertions::tests: starting\nWARN log_assertions::tests: low memory', src/lib.rs:38:9\n--- captured log ---\nINFO starting\nWARN low memory",
+                },
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 35,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "nothing_logged",
+                outcome: Outcome::Failed {
+                    message: "panicked at 'no ERROR record containing \"failure\" was logged; no records were captured', src/lib.rs:43:9",
+                },
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 42,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "overflow",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 47,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "truncated",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 65,
+                }),
+                suite: None,
+            },
+        ],
+    );
+}
+
+#[test]
+fn log_capture() {
+    let output = run("log_capture");
+    let trials = complete_trials(&output);
+    let long_line = format!(
+        "panicked at 'failure', src/lib.rs:31:9\n--- captured log ---\nINFO {}",
+        "x".repeat(1018)
+    );
+    let recent_lines = format!(
+        "panicked at 'failure', src/lib.rs:44:9\n--- captured log ---\n{}",
+        (27..100)
+            .map(|index| format!("INFO line {:03}", index))
+            .collect::<Vec<_>>()
+            .join("\n")
+    );
+
+    // Compare the output with the expected output.
+    assert_eq!(
+        trials,
+        vec![
+            Trial {
+                name: "captured",
+                outcome: Outcome::Failed {
+                    message: "panicked at 'failure', src/lib.rs:20:9\n--- captured log ---\nINFO first\nWARN second\nTRACE third 3",
+                },
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 16,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "cleared",
+                outcome: Outcome::Failed {
+                    message: "panicked at 'failure', src/lib.rs:25:9",
+                },
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 24,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "long_line",
+                outcome: Outcome::Failed {
+                    message: &long_line,
+                },
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 29,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "passes",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 35,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "recent_lines",
+                outcome: Outcome::Failed {
+                    message: &recent_lines,
+                },
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 40,
+                }),
+                suite: None,
+            },
+        ],
+    );
+}
+
+#[test]
+fn max_frames() {
+    let output = run("max_frames");
+    let trials = complete_trials(&output);
+
+    // Compare the output with the expected output.
+    assert_eq!(
+        trials,
+        vec![
+            Trial {
+                name: "over_budget",
+                outcome: Outcome::Failed {
+                    message: "took 9 frames, budget 5",
+                },
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 40,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "should_panic_over_budget",
+                outcome: Outcome::Failed {
+                    message: "took 9 frames, budget 5",
+                },
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 46,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "should_panic_within_budget",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 53,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "unlimited",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 59,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "within_budget",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 64,
+                }),
+                suite: None,
+            },
+        ],
+    );
+}
+
+#[test]
+fn message_limit() {
+    let output = run("message_limit");
+    let trials = complete_trials(&output);
+
+    // Compare the output with the expected output.
+    assert_eq!(
+        trials,
+        vec![
+            Trial {
+                name: "long_message",
+                outcome: Outcome::Failed {
+                    message: "panicked at 'this message is longer than the con... (message truncated, 79 bytes total)",
+                },
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 22,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "short_message",
+                outcome: Outcome::Failed {
+                    message: "panicked at 'short', src/lib.rs:28:9",
+                },
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 27,
+                }),
+                suite: None,
+            },
+        ],
+    );
+}
+
+#[test]
+fn order() {
+    let output = run("order");
+    let trials = complete_trials(&output);
+
+    // The tests are run in order of their paths, regardless of the order they are declared in,
+    // while the cases of a test keep the order they are given in.
+    assert_eq!(
+        trials,
+        vec![
+            Trial {
+                name: "only",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 40,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "alpha",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 34,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "first",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 25,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "second",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 22,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "cases[3]",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 29,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "cases[1]",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 29,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "cases[2]",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 29,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "zeta",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 16,
+                }),
+                suite: None,
+            },
+        ],
+    );
+}
+
+#[test]
+fn preserve_audio() {
+    let output = run("preserve_audio");
+    let trials = complete_trials(&output);
+
+    // Compare the output with the expected output.
+    assert_eq!(
+        trials,
+        vec![
+            Trial {
+                name: "a_start_playback",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 28,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "b_playback_preserved",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 38,
+                }),
+                suite: None,
+            },
+        ],
+    );
+}
+
+#[test]
+fn progress() {
+    let output = run("progress");
+    let trials = complete_trials(&output);
+
+    // Compare the output with the expected output.
+    assert_eq!(
+        trials,
+        vec![
+            Trial {
+                name: "first",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 30,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "ignored",
+                outcome: Outcome::Ignored,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 44,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "second",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 47,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "third",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 54,
+                }),
+                suite: None,
+            },
+        ],
+    );
+}
+
+#[test]
+fn quick() {
+    let output = run("quick");
+    let trials = complete_trials(&output);
+
+    // Compare the output with the expected output.
+    assert_eq!(
+        trials,
+        vec![
+            Trial {
+                name: "deterministic",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 16,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "property_fails",
+                outcome: Outcome::Failed {
+                    message: "panicked at 'property failed on iteration 2 of 100 (seed: 0x00000001)\n  x = 146\n  y = Some(false)', src/lib.rs:38:9",
+                },
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 37,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "property_holds",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 42,
+                }),
+                suite: None,
+            },
+        ],
+    );
+}
+
+#[test]
+fn repeat() {
+    let output = run("repeat");
+    let trials = complete_trials(&output);
+
+    // Compare the output with the expected output.
+    assert_eq!(
+        trials,
+        vec![
+            Trial {
+                name: "always_fails",
+                outcome: Outcome::Failed {
+                    message: "failed on iteration 1 of 3: panicked at 'failed', src/lib.rs:27:9",
+                },
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 26,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "flaky",
+                outcome: Outcome::Failed {
+                    message: "failed on iteration 2 of 3: panicked at 'flaked', src/lib.rs:37:9",
+                },
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 31,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "passes",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 41,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "runs_every_iteration",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 49,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "should_panic",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 58,
+                }),
+                suite: None,
+            },
+        ],
+    );
+}
+
+#[test]
+fn should_err() {
+    let output = run("should_err");
+    let trials = complete_trials(&output);
+
+    // Compare the output with the expected output.
+    assert_eq!(
+        trials,
+        vec![
+            Trial {
+                name: "err",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 20,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "expected_constant",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 38,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "expected_debug",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 50,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "expected_literal",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 32,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "expected_mismatch",
+                outcome: Outcome::Failed {
+                    message: "error: \"bar\", expected substring: \"foo\"",
+                },
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 44,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "ok",
+                outcome: Outcome::Failed {
+                    message: "expected Err, got Ok",
+                },
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 26,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "panics",
+                outcome: Outcome::Failed {
+                    message: "panicked at 'foo', src/lib.rs:57:9",
+                },
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 56,
+                }),
+                suite: None,
+            },
+        ],
+    );
+}
+
+#[test]
+fn should_panic() {
+    let output = run("should_panic");
+    let trials = complete_trials(&output);
+
+    // Compare the output with the expected output.
+    assert_eq!(
+        trials,
+        vec![
+            Trial {
+                name: "does_not_panic",
+                outcome: Outcome::Failed {
+                    message: "test did not panic as expected",
+                },
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 25,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "expected_constant",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 35,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "expected_formatted",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 47,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "expected_formatted_mismatch",
+                outcome: Outcome::Failed {
+                    message: "panic message: \"panicked at 'fobar', src/lib.rs:54:9\", expected substring: \"foo\"",
+                },
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 53,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "expected_literal",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 29,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "expected_mismatch",
+                outcome: Outcome::Failed {
+                    message: "panic message: \"panicked at 'bar', src/lib.rs:42:9\", expected substring: \"foo\"",
+                },
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 41,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "panics",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 19,
+                }),
+                suite: None,
+            },
+        ],
+    );
+}
+
+#[test]
+fn should_panic_location() {
+    let output = run("should_panic_location");
+    let trials = complete_trials(&output);
+
+    // Compare the output with the expected output.
+    assert_eq!(
+        trials,
+        vec![
+            Trial {
+                name: "does_not_panic",
+                outcome: Outcome::Failed {
+                    message: "test did not panic as expected",
+                },
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 39,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "expected_and_location",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 43,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "expected_match_location_mismatch",
+                outcome: Outcome::Failed {
+                    message: "panic location: \"src/lib.rs:56:9\", expected file substring: \"dma\"",
+                },
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 55,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "expected_mismatch_location_match",
+                outcome: Outcome::Failed {
+                    message: "panic message: \"panicked at 'foo', src/dma.rs:4:5\", expected substring: \"bar\"",
+                },
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 49,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "location",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 21,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "location_constant",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 27,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "location_mismatch",
+                outcome: Outcome::Failed {
+                    message: "panic location: \"src/lib.rs:34:9\", expected file substring: \"src/dma.rs\"",
+                },
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 33,
+                }),
+                suite: None,
+            },
+        ],
+    );
+}
+
+#[test]
+fn suite() {
+    let output = run("suite");
+    let trials = complete_trials(&output);
+
+    // Compare the output with the expected output.
+    assert_eq!(
+        trials,
+        vec![
+            Trial {
+                name: "cases[1]",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 27,
+                }),
+                suite: Some("slow"),
+            },
+            Trial {
+                name: "cases[2]",
+                outcome: Outcome::Failed {
+                    message: "panicked at 'assertion failed: `(left == right)`\n  left: `2`,\n right: `1`', src/lib.rs:28:9",
+                },
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 27,
+                }),
+                suite: Some("slow"),
+            },
+            Trial {
+                name: "default",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 16,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "hardware_only",
+                outcome: Outcome::Failed {
+                    message: "panicked at 'foo', src/lib.rs:23:9",
+                },
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 22,
+                }),
+                suite: Some("hardware-only"),
+            },
+            Trial {
+                name: "slow",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 19,
+                }),
+                suite: Some("slow"),
+            },
+        ],
+    );
+}
+
+#[test]
+fn termination() {
+    let output = run("termination");
+    let trials = complete_trials(&output);
+
+    // Compare the output with the expected output.
+    assert_eq!(
+        trials,
+        vec![
+            Trial {
+                name: "cases[0]",
+                outcome: Outcome::Failed {
+                    message: "returned None",
+                },
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 53,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "cases[1]",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 53,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "err",
+                outcome: Outcome::Failed {
+                    message: "Error: \"bad\"",
+                },
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 16,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "failure",
+                outcome: Outcome::Failed {
+                    message: "precondition not met",
+                },
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 21,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "failure_err",
+                outcome: Outcome::Failed {
+                    message: "Error: precondition not met",
+                },
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 26,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "nested",
+                outcome: Outcome::Failed {
+                    message: "returned None",
+                },
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 31,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "none",
+                outcome: Outcome::Failed {
+                    message: "returned None",
+                },
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 36,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "ok",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 41,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "question_mark",
+                outcome: Outcome::Failed {
+                    message: "returned None",
+                },
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 46,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "some",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 58,
+                }),
+                suite: None,
+            },
+        ],
+    );
+}
+
+#[test]
+fn unregistered() {
+    let output = run("unregistered");
+    let trials = complete_trials(&output);
+
+    // Compare the output with the expected output.
+    assert_eq!(trials, Vec::<TrialOwned>::new());
+}
+
+#[test]
+fn vram_guard() {
+    let output = run("vram_guard");
+    let trials = complete_trials(&output);
+
+    // Compare the output with the expected output.
+    assert_eq!(
+        trials,
+        vec![
+            Trial {
+                name: "assert_in_vblank",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 65,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "assert_in_vblank_fails",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 73,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "write_in_forced_blank",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 56,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "write_in_vblank",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 37,
+                }),
+                suite: None,
+            },
+            Trial {
+                name: "write_outside_vblank",
+                outcome: Outcome::Passed,
+                output: "",
+                location: Some(Location {
+                    file: "src/lib.rs",
+                    line: 47,
+                }),
+                suite: None,
+            },
+        ],
+    );
+}
+
+#[test]
+fn exit_code_pass() {
+    assert_eq!(exit_code("pass"), ExitCode::Passed as i32);
+}
+
+#[test]
+fn exit_code_fail() {
+    assert_eq!(exit_code("fail"), ExitCode::Failed as i32);
+}
+
+#[test]
+fn exit_code_empty() {
+    assert_eq!(exit_code("empty"), ExitCode::NoTests as i32);
+}
+
+#[test]
+fn exit_code_unregistered() {
+    assert_eq!(exit_code("unregistered"), ExitCode::NoTests as i32);
+}
+
+#[test]
+fn exit_code_list_only() {
+    assert_eq!(exit_code("list_only"), ExitCode::Passed as i32);
+}
+
+#[test]
+fn exit_code_skip() {
+    assert_eq!(exit_code("skip"), ExitCode::Passed as i32);
+}
+
+#[test]
+fn exit_code_watchdog() {
+    assert_eq!(exit_code("watchdog"), ExitCode::TimedOut as i32);
+}
+
+#[test]
+fn exit_code_global_setup_panic() {
+    assert_eq!(
+        exit_code("global_setup_panic"),
+        ExitCode::FrameworkPanic as i32
+    );
+}
+
+#[test]
+fn summary_pass() {
+    assert_eq!(
+        summary("pass"),
+        "result=ok; passed=1; failed=0; ignored=0; total=1"
+    );
+}
+
+#[test]
+fn summary_fail() {
+    assert_eq!(
+        summary("fail"),
+        "result=failed; passed=0; failed=1; ignored=0; total=1"
+    );
+}
+
+#[test]
+fn summary_ignore() {
+    assert_eq!(
+        summary("ignore"),
+        "result=ok; passed=0; failed=0; ignored=2; total=2"
+    );
+}
+
+#[test]
+fn summary_unregistered() {
+    assert_eq!(
+        summary("unregistered"),
+        "result=ok; passed=0; failed=0; ignored=0; total=0"
+    );
+}
+
+#[test]
+fn summary_list_only() {
+    assert_eq!(
+        summary("list_only"),
+        "result=ok; passed=0; failed=0; ignored=3; total=3"
+    );
+}
+
+#[test]
+fn summary_skip() {
+    assert_eq!(
+        summary("skip"),
+        "result=ok; passed=1; failed=0; ignored=4; total=5"
+    );
+}
+
+#[test]
+fn summary_requires() {
+    assert_eq!(
+        summary("requires"),
+        "result=ok; passed=1; failed=0; ignored=3; total=4"
+    );
+}
+
+#[test]
+fn no_tests_hint() {
+    let (_, log) = emulate("unregistered");
+    for line in [
+        "gba_test: no tests were found; check that the crate root contains:",
+        "gba_test:     #![feature(custom_test_frameworks)]",
+        "gba_test:     #![test_runner(gba_test::runner)]",
+        "gba_test:     #![reexport_test_harness_main = \"test_harness\"]",
+    ] {
+        assert!(log.contains(line), "missing {:?} in log:\n{}", line, log);
+    }
+}
+
+#[test]
+fn list_only_log() {
+    let (_, log) = emulate("list_only");
+    for line in [
+        "gba_test: listed fails at src/lib.rs:28",
+        "gba_test: listed ignored at src/lib.rs:34",
+        "gba_test: listed passes at src/lib.rs:37",
+    ] {
+        assert!(log.contains(line), "missing {:?} in log:\n{}", line, log);
+    }
+}
+
+#[test]
+fn list_only_elf() {
+    let elf = fs::read(build("list_only")).expect("failed to read executable");
+    let mut tests = gba_test::elf::list_tests(&elf).expect("failed to list tests");
+    tests.sort_by(|a, b| a.name.cmp(&b.name));
+
+    assert_eq!(
+        tests,
+        vec![
+            TestMeta {
+                name: "fails".into(),
+                path: "list_only::tests::fails".into(),
+                ignore: false,
+                should_panic: false,
+                should_err: false,
+            },
+            TestMeta {
+                name: "ignored".into(),
+                path: "list_only::tests::ignored".into(),
+                ignore: true,
+                should_panic: false,
+                should_err: false,
+            },
+            TestMeta {
+                name: "passes".into(),
+                path: "list_only::tests::passes".into(),
+                ignore: false,
+                should_panic: false,
+                should_err: false,
+            },
+        ]
+    );
+}
+
+#[test]
+fn log_assertions_log() {
+    let (_, log) = emulate("log_assertions");
+    for line in ["low battery", "low memory", "record 19"] {
+        assert!(log.contains(line), "missing {:?} in log:\n{}", line, log);
+    }
+}
+
+#[test]
+fn skip_log() {
+    let (_, log) = emulate("skip");
+    for line in [
+        "gba_test: skipped formatted: no rumble cart detected",
+        "gba_test: skipped should_err: no rumble cart detected",
+        "gba_test: skipped should_panic: no rumble cart detected",
+        "gba_test: skipped skipped: no rumble cart detected",
+    ] {
+        assert!(log.contains(line), "missing {:?} in log:\n{}", line, log);
+    }
+}
+
+#[test]
+fn requires_log() {
+    let (_, log) = emulate("requires");
+    for line in [
+        "gba_test: skipped link_cable: missing capability: link_cable",
+        "gba_test: skipped rtc: missing capability: rtc",
+        "gba_test: skipped rtc_should_panic: missing capability: rtc",
+    ] {
+        assert!(log.contains(line), "missing {:?} in log:\n{}", line, log);
+    }
+    assert!(!log.contains("missing capability: sram"), "log:\n{}", log);
+}
+
+#[test]
+fn size_per_test() {
+    let size = |name| {
+        let elf = fs::read(build(name)).expect("failed to read executable");
+        (section_size(&elf, ".rodata"), section_size(&elf, ".text"))
+    };
+    let (one_rodata, one_text) = size("size_one");
+    let (many_rodata, many_text) = size("size_many");
+    let rodata = (many_rodata - one_rodata) / 32;
+    let text = (many_text - one_text) / 32;
+
+    // Each test adds its `Test` const, its entry in the list of tests, its name and path, and its
+    // function, which should come to well under 128 bytes.
+    assert!(
+        rodata + text <= 128,
+        "each test takes {} bytes of `.rodata` and {} bytes of `.text`",
+        rodata,
+        text
+    );
+}
@@ -0,0 +1,38 @@
+//! Defines multiple tests sharing the same name.
+
+#![no_std]
+#![no_main]
+#![feature(custom_test_frameworks)]
+#![test_runner(gba_test::runner)]
+#![reexport_test_harness_main = "test_harness"]
+
+gba_test::harness_main!();
+
+#[cfg(test)]
+mod a {
+    use gba_test::test;
+
+    #[test]
+    fn same() {}
+
+    #[test]
+    fn unique() {}
+}
+
+#[cfg(test)]
+mod b {
+    use gba_test::test;
+
+    #[test]
+    fn same() {}
+}
+
+#[cfg(test)]
+mod c {
+    use gba_test::test;
+
+    #[test]
+    fn same() {
+        panic!("failed");
+    }
+}
@@ -0,0 +1,23 @@
+//! Defines no tests, as the only test is configured out.
+
+#![no_std]
+#![no_main]
+#![feature(custom_test_frameworks)]
+#![test_runner(gba_test::runner)]
+#![reexport_test_harness_main = "test_harness"]
+
+pub fn add(left: usize, right: usize) -> usize {
+    left + right
+}
+
+gba_test::harness_main!();
+
+#[cfg(test)]
+mod tests {
+    #[cfg(any())]
+    #[gba_test::test]
+    fn it_works() {
+        let result = super::add(2, 2);
+        assert_eq!(result, 4);
+    }
+}
@@ -10,12 +10,7 @@ pub fn add(left: usize, right: usize) -> usize {
     left + right
 }
 
-#[cfg(test)]
-#[no_mangle]
-pub fn main() {
-    test_harness();
-    loop {}
-}
+gba_test::harness_main!();
 
 #[cfg(test)]
 mod tests {
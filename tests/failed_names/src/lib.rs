@@ -0,0 +1,54 @@
+//! Defines failing tests, followed by a test checking that their names were recorded, while
+//! EWRAM is cleared before each test.
+
+#![no_std]
+#![no_main]
+#![feature(custom_test_frameworks)]
+#![test_runner(gba_test::runner)]
+#![reexport_test_harness_main = "test_harness"]
+
+extern "C" {
+    /// The list of failed test names maintained by the runner.
+    static GBA_TEST_FAILED_NAMES: [u8; 2048];
+}
+
+/// Returns the contents of the list of failed test names.
+pub fn failed_names() -> [u8; 2048] {
+    unsafe { core::ptr::read_volatile(core::ptr::addr_of!(GBA_TEST_FAILED_NAMES)) }
+}
+
+#[cfg(test)]
+#[no_mangle]
+pub fn main() {
+    // Clearing EWRAM before each test must leave the list intact.
+    gba_test::set_register_ram_reset(gba_test::RegisterRamReset::default().ewram());
+    test_harness();
+    loop {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::failed_names;
+    use gba_test::test;
+
+    #[test]
+    fn fails() {
+        panic!("failure");
+    }
+
+    #[test]
+    fn fails_again() {
+        assert_eq!(1, 2);
+    }
+
+    #[test]
+    fn passes() {}
+
+    #[test]
+    fn recorded() {
+        let names = failed_names();
+        assert_eq!(&names[..4], b"GBTF");
+        assert_eq!(u16::from_le_bytes([names[4], names[5]]), 18);
+        assert_eq!(&names[6..24], b"fails\nfails_again\n");
+    }
+}
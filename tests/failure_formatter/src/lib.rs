@@ -0,0 +1,64 @@
+//! Defines tests whose failure messages are written by a custom formatter.
+
+#![no_std]
+#![no_main]
+#![feature(custom_test_frameworks)]
+#![test_runner(gba_test::runner)]
+#![reexport_test_harness_main = "test_harness"]
+
+#[cfg(test)]
+use core::{fmt, fmt::Write, panic::PanicInfo, ptr};
+
+/// Whether the formatter should fail, falling back to the default message.
+#[cfg(test)]
+static mut FAIL_FORMATTING: bool = false;
+
+#[cfg(test)]
+fn format(info: &PanicInfo, writer: &mut dyn Write) -> fmt::Result {
+    // SAFETY: Only ever accessed on the main thread.
+    if unsafe { *ptr::addr_of!(FAIL_FORMATTING) } {
+        return Err(fmt::Error);
+    }
+    write!(
+        writer,
+        "custom failure message at line {}",
+        info.location().map_or(0, |location| location.line())
+    )
+}
+
+#[cfg(test)]
+#[no_mangle]
+pub fn main() {
+    gba_test::set_failure_formatter(format);
+    test_harness();
+    loop {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FAIL_FORMATTING;
+    use gba_test::test;
+
+    #[test]
+    fn custom() {
+        panic!("foo");
+    }
+
+    #[test]
+    #[should_panic(expected = "foo")]
+    fn expected_mismatch() {
+        panic!("bar");
+    }
+
+    #[test]
+    fn fallback() {
+        // SAFETY: Only ever accessed on the main thread.
+        unsafe {
+            FAIL_FORMATTING = true;
+        }
+        panic!("baz");
+    }
+
+    #[test]
+    fn passes() {}
+}
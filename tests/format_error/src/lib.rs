@@ -0,0 +1,45 @@
+//! Defines tests panicking with messages that fail partway through formatting.
+
+#![no_std]
+#![no_main]
+#![feature(custom_test_frameworks)]
+#![test_runner(gba_test::runner)]
+#![reexport_test_harness_main = "test_harness"]
+
+use core::{fmt, fmt::Display};
+
+/// A value that writes part of itself before failing to format.
+pub struct Unformattable;
+
+impl Display for Unformattable {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("partially written")?;
+        Err(fmt::Error)
+    }
+}
+
+gba_test::harness_main!();
+
+#[cfg(test)]
+mod tests {
+    use super::Unformattable;
+    use gba_test::test;
+
+    #[test]
+    fn fails() {
+        panic!("formatted");
+    }
+
+    #[test]
+    fn passes() {}
+
+    #[test]
+    fn unformattable() {
+        panic!("{}", Unformattable);
+    }
+
+    #[test]
+    fn unformattable_after() {
+        panic!("formatted after");
+    }
+}
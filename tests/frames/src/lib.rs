@@ -0,0 +1,109 @@
+//! Defines tests that count the frames elapsed while they run.
+
+#![no_std]
+#![no_main]
+#![feature(custom_test_frameworks)]
+#![test_runner(gba_test::runner)]
+#![reexport_test_harness_main = "test_harness"]
+
+use core::ptr;
+
+/// The address of the interrupt handler called by the BIOS.
+const IRQ_HANDLER: *mut usize = 0x0300_7FFC as *mut usize;
+/// Timer 1 control.
+const TM1CNT_H: *mut u16 = 0x0400_0106 as *mut u16;
+/// Interrupt enable.
+const IE: *mut u16 = 0x0400_0200 as *mut u16;
+/// Interrupt master enable.
+const IME: *mut u16 = 0x0400_0208 as *mut u16;
+
+/// An interrupt handler that does nothing.
+extern "C" fn ignore_interrupts() {}
+
+/// Masks all interrupts.
+pub fn mask_interrupts() {
+    unsafe {
+        ptr::write_volatile(IE, 0);
+    }
+}
+
+/// Disables interrupts using the master enable.
+pub fn disable_interrupts() {
+    unsafe {
+        ptr::write_volatile(IME, 0);
+    }
+}
+
+/// Stops timer 1.
+pub fn stop_timer() {
+    unsafe {
+        ptr::write_volatile(TM1CNT_H, 0);
+    }
+}
+
+/// Replaces the interrupt handler with one that does nothing.
+///
+/// Interrupts are disabled first, so that the replacement is never called.
+pub fn replace_handler() {
+    disable_interrupts();
+    unsafe {
+        ptr::write_volatile(IRQ_HANDLER, ignore_interrupts as extern "C" fn() as usize);
+    }
+}
+
+gba_test::harness_main!();
+
+#[cfg(test)]
+mod tests {
+    use gba_test::{frames, test, wait_frames};
+
+    #[test]
+    fn advances() {
+        let start = frames();
+        wait_frames(3);
+        assert_eq!(frames() - start, 3);
+    }
+
+    #[test]
+    fn interrupts_disabled() {
+        crate::disable_interrupts();
+        let start = frames();
+        wait_frames(2);
+        assert_eq!(frames() - start, 2);
+    }
+
+    #[test]
+    fn masked_interrupt() {
+        crate::mask_interrupts();
+        let start = frames();
+        wait_frames(2);
+        assert_eq!(frames() - start, 2);
+    }
+
+    #[test]
+    fn relative_to_test() {
+        // The previous tests waited for frames, but the count restarts for each test.
+        assert_eq!(frames(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "the interrupt handler has been replaced")]
+    fn replaced_handler() {
+        crate::replace_handler();
+        wait_frames(1);
+    }
+
+    #[test]
+    #[should_panic(expected = "timer 1 has been stopped")]
+    fn stopped_timer() {
+        crate::stop_timer();
+        wait_frames(1);
+    }
+
+    #[test]
+    fn wait_none() {
+        let start = frames();
+        wait_frames(0);
+        assert_eq!(frames(), start);
+    }
+}
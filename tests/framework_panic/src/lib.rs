@@ -0,0 +1,55 @@
+//! Defines a test case whose implementation panics within the runner, between passing tests.
+
+#![no_std]
+#![no_main]
+#![feature(custom_test_frameworks)]
+#![test_runner(gba_test::runner)]
+#![reexport_test_harness_main = "test_harness"]
+
+use gba_test::{Ignore, TestCase};
+
+/// A test case that panics when the runner queries its frame budget.
+///
+/// This holds the path of the module the test case is defined in.
+pub struct Faulty(pub &'static str);
+
+impl TestCase for Faulty {
+    fn name(&self) -> &str {
+        "faulty"
+    }
+
+    fn module_path(&self) -> &str {
+        self.0
+    }
+
+    fn run(&self) {}
+
+    fn ignore(&self) -> Ignore {
+        Ignore::No
+    }
+
+    fn max_frames(&self) -> Option<u32> {
+        panic!("unable to determine frame budget");
+    }
+}
+
+gba_test::harness_main!();
+
+#[cfg(test)]
+mod tests {
+    use super::Faulty;
+    use gba_test::test;
+
+    #[test]
+    fn a_passes() {}
+
+    mod b_faulty {
+        use super::Faulty;
+
+        #[test_case]
+        const TEST: Faulty = Faulty(module_path!());
+    }
+
+    #[test]
+    fn c_passes() {}
+}
@@ -0,0 +1,44 @@
+//! Defines tests that depend on state written by a global setup function.
+
+#![no_std]
+#![no_main]
+#![feature(custom_test_frameworks)]
+#![test_runner(gba_test::runner)]
+#![reexport_test_harness_main = "test_harness"]
+
+#[cfg(test)]
+static mut SETUP_RUNS: u32 = 0;
+
+#[cfg(test)]
+fn setup() {
+    // SAFETY: Only ever accessed on the main thread.
+    unsafe {
+        SETUP_RUNS += 1;
+    }
+}
+
+gba_test::harness_main!(once = setup);
+
+#[cfg(test)]
+mod tests {
+    use super::SETUP_RUNS;
+    use core::ptr;
+    use gba_test::test;
+
+    #[test]
+    fn first() {
+        // SAFETY: Only ever accessed on the main thread.
+        assert_eq!(unsafe { *ptr::addr_of!(SETUP_RUNS) }, 1);
+    }
+
+    #[test]
+    fn panics() {
+        panic!("failed");
+    }
+
+    #[test]
+    fn second() {
+        // SAFETY: Only ever accessed on the main thread.
+        assert_eq!(unsafe { *ptr::addr_of!(SETUP_RUNS) }, 1);
+    }
+}
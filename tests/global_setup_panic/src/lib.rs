@@ -0,0 +1,22 @@
+//! Defines a global setup function that panics, preventing any tests from running.
+
+#![no_std]
+#![no_main]
+#![feature(custom_test_frameworks)]
+#![test_runner(gba_test::runner)]
+#![reexport_test_harness_main = "test_harness"]
+
+#[cfg(test)]
+fn setup() {
+    panic!("calibration failed");
+}
+
+gba_test::harness_main!(once = setup);
+
+#[cfg(test)]
+mod tests {
+    use gba_test::test;
+
+    #[test]
+    fn never_run() {}
+}
@@ -0,0 +1,64 @@
+//! Defines tests comparing memory against golden files.
+
+#![no_std]
+#![no_main]
+#![feature(custom_test_frameworks)]
+#![test_runner(gba_test::runner)]
+#![reexport_test_harness_main = "test_harness"]
+
+use core::ptr;
+
+/// The start of VRAM.
+pub const VRAM: *mut u16 = 0x0600_0000 as *mut u16;
+
+/// Draws a gradient matching `golden/gradient.bin` to VRAM.
+///
+/// VRAM does not support 8-bit writes, so each pair of bytes is written as a halfword.
+pub fn draw_gradient() {
+    for index in 0..128 {
+        let low = (index * 2) as u16;
+        unsafe { ptr::write_volatile(VRAM.add(index), ((low + 1) << 8) | low) }
+    }
+}
+
+gba_test::harness_main!();
+
+#[cfg(test)]
+mod tests {
+    use super::{draw_gradient, VRAM};
+    use core::ptr;
+    use gba_test::{assert_matches_golden, golden, include_aligned_bytes, test};
+
+    #[test]
+    fn matches() {
+        draw_gradient();
+        unsafe {
+            assert_matches_golden!(VRAM as *const u8, "golden/gradient.bin");
+        }
+    }
+
+    #[test]
+    fn mismatch() {
+        draw_gradient();
+        unsafe {
+            ptr::write_volatile(VRAM.add(32), 0x41FF);
+            ptr::write_volatile(VRAM.add(127), 0x0000);
+        }
+        unsafe {
+            assert_matches_golden!(VRAM as *const u8, "golden/gradient.bin");
+        }
+    }
+
+    #[test]
+    fn perturbed_buffer() {
+        let golden = include_aligned_bytes!("golden/gradient.bin");
+        let mut buffer = [0u8; 256];
+        buffer.copy_from_slice(golden);
+        buffer[200] ^= 0x80;
+
+        let mismatch = unsafe { golden::compare(buffer.as_ptr(), golden) }.unwrap_err();
+        assert_eq!(mismatch.differing_bytes, 1);
+        assert_eq!(mismatch.first_offset, 200);
+        assert_eq!(mismatch.sections[3][0], 1);
+    }
+}
@@ -1,4 +1,4 @@
-//! Defines an ignored test.
+//! Defines ignored tests.
 
 #![no_std]
 #![no_main]
@@ -10,12 +10,7 @@ pub fn add(left: usize, right: usize) -> usize {
     left + right
 }
 
-#[cfg(test)]
-#[no_mangle]
-pub fn main() {
-    test_harness();
-    loop {}
-}
+gba_test::harness_main!();
 
 #[cfg(test)]
 mod tests {
@@ -28,4 +23,10 @@ mod tests {
         let result = add(2, 2);
         assert_eq!(result, 4);
     }
+
+    #[test]
+    #[ignore = "not yet supported"]
+    fn with_reason() {
+        panic!("should not be run");
+    }
 }
@@ -0,0 +1,52 @@
+//! Defines tests that change IO registers before failing.
+
+#![no_std]
+#![no_main]
+#![feature(custom_test_frameworks)]
+#![test_runner(gba_test::runner)]
+#![reexport_test_harness_main = "test_harness"]
+
+use core::ptr;
+
+/// Display control.
+const DISPCNT: *mut u16 = 0x0400_0000 as *mut u16;
+/// Interrupt enable.
+const IE: *mut u16 = 0x0400_0200 as *mut u16;
+
+/// Switches to mode 3 with background 2 enabled.
+pub fn enable_mode_3() {
+    unsafe {
+        ptr::write_volatile(DISPCNT, 0x0403);
+    }
+}
+
+/// Enables the V-blank interrupt.
+pub fn enable_vblank_interrupt() {
+    unsafe {
+        ptr::write_volatile(IE, ptr::read_volatile(IE) | 0x0001);
+    }
+}
+
+gba_test::harness_main!();
+
+#[cfg(test)]
+mod tests {
+    use gba_test::test;
+
+    #[test]
+    fn changes_registers() {
+        crate::enable_mode_3();
+        crate::enable_vblank_interrupt();
+        panic!("foo");
+    }
+
+    #[test]
+    fn passes_with_changes() {
+        crate::enable_mode_3();
+    }
+
+    #[test]
+    fn unchanged() {
+        panic!("bar");
+    }
+}
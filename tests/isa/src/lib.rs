@@ -0,0 +1,51 @@
+//! Defines tests that are run in specific instruction sets.
+
+#![no_std]
+#![no_main]
+#![feature(custom_test_frameworks)]
+#![test_runner(gba_test::runner)]
+#![reexport_test_harness_main = "test_harness"]
+
+use core::hint::black_box;
+
+/// Doubles a value.
+///
+/// This is never inlined, so that it is called using a branch that may switch instruction sets.
+#[inline(never)]
+pub fn double(x: u32) -> u32 {
+    x * 2
+}
+
+/// Calls a function through a pointer, which requires switching to the instruction set the
+/// function was compiled for.
+pub fn call_through_pointer(x: u32) -> u32 {
+    let function: fn(u32) -> u32 = black_box(double);
+    function(x)
+}
+
+gba_test::harness_main!();
+
+#[cfg(test)]
+mod tests {
+    use gba_test::test;
+
+    #[test(isa = "arm")]
+    fn arm() {
+        assert_eq!(crate::double(21), 42);
+    }
+
+    #[test(isa = "both")]
+    fn function_pointer() {
+        assert_eq!(crate::call_through_pointer(21), 42);
+    }
+
+    #[test(isa = "both", cases(1, 2))]
+    fn cases(x: u32) -> Option<()> {
+        (crate::call_through_pointer(x) == x * 2).then_some(())
+    }
+
+    #[test(isa = "thumb")]
+    fn thumb() {
+        assert_eq!(crate::double(21), 42);
+    }
+}
@@ -0,0 +1,38 @@
+//! Defines tests that are listed without being run.
+
+#![no_std]
+#![no_main]
+#![feature(custom_test_frameworks)]
+#![test_runner(gba_test::runner)]
+#![reexport_test_harness_main = "test_harness"]
+
+/// Fails if run, as the global setup is skipped when listing tests.
+pub fn setup() {
+    panic!("global setup was run");
+}
+
+#[cfg(test)]
+#[no_mangle]
+pub fn main() {
+    gba_test::set_list_only(true);
+    gba_test::set_global_setup(setup);
+    test_harness();
+    loop {}
+}
+
+#[cfg(test)]
+mod tests {
+    use gba_test::test;
+
+    #[test]
+    fn fails() {
+        panic!("test was run");
+    }
+
+    #[test]
+    #[ignore]
+    fn ignored() {}
+
+    #[test]
+    fn passes() {}
+}
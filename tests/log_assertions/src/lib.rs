@@ -0,0 +1,71 @@
+//! Defines tests that make assertions about the messages they log.
+
+#![no_std]
+#![no_main]
+#![feature(custom_test_frameworks)]
+#![test_runner(gba_test::runner)]
+#![reexport_test_harness_main = "test_harness"]
+
+gba_test::harness_main!();
+
+#[cfg(test)]
+mod tests {
+    use gba_test::{assert_logged, test};
+    use log::Level;
+
+    #[test]
+    fn cleared() {
+        assert_eq!(gba_test::logs().count(), 0);
+    }
+
+    #[test]
+    fn level_mismatch() {
+        log::info!("low battery");
+        assert_logged!(Level::Warn, "battery");
+    }
+
+    #[test]
+    fn matched() {
+        log::info!("starting");
+        log::warn!("low battery");
+        assert_logged!(Level::Warn, "battery");
+    }
+
+    #[test]
+    fn not_matched() {
+        log::info!("starting");
+        log::warn!("low memory");
+        assert_logged!(Level::Warn, "battery");
+    }
+
+    #[test]
+    fn nothing_logged() {
+        assert_logged!(Level::Error, "failure");
+    }
+
+    #[test]
+    fn overflow() {
+        for index in 0..20 {
+            log::info!("record {}", index);
+        }
+        let mut records = gba_test::logs();
+        for index in 4..20 {
+            let record = records.next().unwrap();
+            assert_eq!(record.level(), Level::Info);
+            assert_eq!(record.target(), "log_assertions::tests");
+            assert_eq!(
+                record.message().strip_prefix("record ").map(str::parse),
+                Some(Ok(index))
+            );
+        }
+        assert!(records.next().is_none());
+    }
+
+    #[test]
+    fn truncated() {
+        log::warn!("{:x<100}", "");
+        let record = gba_test::logs().next().unwrap();
+        assert_eq!(record.message().len(), 64);
+        assert_logged!(Level::Warn, "xxxx");
+    }
+}
@@ -0,0 +1,46 @@
+//! Defines tests that log messages, checking that they are captured in failure messages.
+
+#![no_std]
+#![no_main]
+#![feature(custom_test_frameworks)]
+#![test_runner(gba_test::runner)]
+#![reexport_test_harness_main = "test_harness"]
+
+gba_test::harness_main!();
+
+#[cfg(test)]
+mod tests {
+    use gba_test::test;
+
+    #[test]
+    fn captured() {
+        log::info!("first");
+        log::warn!("second");
+        log::trace!("third {}", 3);
+        panic!("failure");
+    }
+
+    #[test]
+    fn cleared() {
+        panic!("failure");
+    }
+
+    #[test]
+    fn long_line() {
+        log::info!("{:x<2000}", "");
+        panic!("failure");
+    }
+
+    #[test]
+    fn passes() {
+        log::info!("not reported");
+    }
+
+    #[test]
+    fn recent_lines() {
+        for index in 0..100 {
+            log::info!("line {:03}", index);
+        }
+        panic!("failure");
+    }
+}
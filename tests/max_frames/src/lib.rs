@@ -0,0 +1,67 @@
+//! Defines tests with frame budgets, busy-waiting for a known number of frames.
+
+#![no_std]
+#![no_main]
+#![feature(custom_test_frameworks)]
+#![test_runner(gba_test::runner)]
+#![reexport_test_harness_main = "test_harness"]
+
+use core::ptr;
+
+/// Timer 0 counter/reload.
+const TM0CNT_L: *mut u16 = 0x0400_0100 as *mut u16;
+/// Timer 0 control.
+const TM0CNT_H: *mut u16 = 0x0400_0102 as *mut u16;
+
+/// The number of timer ticks in a single frame, when incrementing every 64 cycles.
+const TICKS_PER_FRAME: u32 = 4389;
+
+/// Busy-waits for the given number of half frames, using timer 0.
+pub fn wait_half_frames(half_frames: u32) {
+    let ticks = (TICKS_PER_FRAME * half_frames / 2) as u16;
+    unsafe {
+        ptr::write_volatile(TM0CNT_H, 0);
+        ptr::write_volatile(TM0CNT_L, 0);
+        // Start the timer, incrementing every 64 cycles.
+        ptr::write_volatile(TM0CNT_H, 0b1000_0001);
+        while ptr::read_volatile(TM0CNT_L) < ticks {}
+        ptr::write_volatile(TM0CNT_H, 0);
+    }
+}
+
+gba_test::harness_main!();
+
+#[cfg(test)]
+mod tests {
+    use super::wait_half_frames;
+    use gba_test::test;
+
+    #[test(max_frames = 5)]
+    fn over_budget() {
+        wait_half_frames(19);
+    }
+
+    #[test(max_frames = 5)]
+    #[should_panic]
+    fn should_panic_over_budget() {
+        wait_half_frames(19);
+        panic!("expected");
+    }
+
+    #[test(max_frames = 5)]
+    #[should_panic]
+    fn should_panic_within_budget() {
+        wait_half_frames(5);
+        panic!("expected");
+    }
+
+    #[test]
+    fn unlimited() {
+        wait_half_frames(19);
+    }
+
+    #[test(max_frames = 5)]
+    fn within_budget() {
+        wait_half_frames(5);
+    }
+}
@@ -0,0 +1,30 @@
+//! Defines tests that fail with messages longer than the configured limit.
+
+#![no_std]
+#![no_main]
+#![feature(custom_test_frameworks)]
+#![test_runner(gba_test::runner)]
+#![reexport_test_harness_main = "test_harness"]
+
+#[cfg(test)]
+#[no_mangle]
+pub fn main() {
+    gba_test::set_message_limit(48);
+    test_harness();
+    loop {}
+}
+
+#[cfg(test)]
+mod tests {
+    use gba_test::test;
+
+    #[test]
+    fn long_message() {
+        panic!("this message is longer than the configured limit");
+    }
+
+    #[test]
+    fn short_message() {
+        panic!("short");
+    }
+}
@@ -0,0 +1,42 @@
+//! Defines tests in a different order than they are run in.
+
+#![no_std]
+#![no_main]
+#![feature(custom_test_frameworks)]
+#![test_runner(gba_test::runner)]
+#![reexport_test_harness_main = "test_harness"]
+
+gba_test::harness_main!();
+
+#[cfg(test)]
+mod tests {
+    use gba_test::test;
+
+    #[test]
+    fn zeta() {}
+
+    mod b {
+        use gba_test::test;
+
+        #[test]
+        fn second() {}
+
+        #[test]
+        fn first() {}
+    }
+
+    #[test(cases(3, 1, 2))]
+    fn cases(x: u32) {
+        assert!(x > 0);
+    }
+
+    #[test]
+    fn alpha() {}
+
+    mod a {
+        use gba_test::test;
+
+        #[test]
+        fn only() {}
+    }
+}
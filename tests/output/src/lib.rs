@@ -0,0 +1,36 @@
+//! Defines tests that write captured output.
+
+#![no_std]
+#![no_main]
+#![feature(custom_test_frameworks)]
+#![test_runner(gba_test::runner)]
+#![reexport_test_harness_main = "test_harness"]
+
+gba_test::harness_main!();
+
+#[cfg(test)]
+mod tests {
+    use gba_test::{output, test};
+
+    #[test]
+    fn fails() {
+        output!("before failure");
+        panic!("failed");
+    }
+
+    #[test]
+    fn passes() {
+        output!("foo");
+        output!("{} + {} = {}", 1, 2, 1 + 2);
+    }
+
+    #[test]
+    fn silent() {}
+
+    #[test]
+    fn truncated() {
+        for _ in 0..100 {
+            output!("0123456789");
+        }
+    }
+}
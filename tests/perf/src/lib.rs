@@ -0,0 +1,69 @@
+//! Defines tests measuring code performance.
+
+#![no_std]
+#![no_main]
+#![feature(custom_test_frameworks)]
+#![test_runner(gba_test::runner)]
+#![reexport_test_harness_main = "test_harness"]
+
+/// Spins for the given number of iterations.
+///
+/// This is written in assembly so that the number of cycles per iteration does not depend on
+/// compiler optimizations.
+pub fn busy_loop(iterations: u32) {
+    if iterations == 0 {
+        return;
+    }
+    unsafe {
+        core::arch::asm!(
+            "1:",
+            "subs {0}, {0}, #1",
+            "bne 1b",
+            inout(reg) iterations => _,
+            options(nomem, nostack),
+        );
+    }
+}
+
+gba_test::harness_main!();
+
+#[cfg(test)]
+mod tests {
+    use super::busy_loop;
+    use gba_test::{assert_cycles_under, perf, perf::Cycles, test};
+
+    #[test]
+    fn empty() {
+        assert!(perf::measure(|| {}) < Cycles(16));
+    }
+
+    #[test]
+    fn busy_loop_in_range() {
+        // Each iteration takes a handful of cycles, depending on the wait states of the memory the
+        // code is run from.
+        let cycles = perf::measure(|| busy_loop(1000));
+        assert!(cycles > Cycles(2_000), "{}", cycles);
+        assert!(cycles < Cycles(30_000), "{}", cycles);
+    }
+
+    #[test]
+    fn busy_loop_scales() {
+        let short = perf::measure(|| busy_loop(1000)).get();
+        let long = perf::measure(|| busy_loop(2000)).get();
+        assert!(long > short * 3 / 2, "{} vs {}", short, long);
+        assert!(long < short * 5 / 2, "{} vs {}", short, long);
+    }
+
+    #[test]
+    fn assert_cycles_under() {
+        assert_cycles_under!(busy_loop(10), Cycles::FRAME);
+        assert_cycles_under!(busy_loop(10), 10_000);
+    }
+
+    #[test]
+    fn conversions() {
+        assert_eq!(Cycles::FRAME.as_scanlines(), 228);
+        assert_eq!(Cycles(1 << 24).as_micros(), 1_000_000);
+        assert_eq!(Cycles::SCANLINE.as_micros(), 73);
+    }
+}
@@ -0,0 +1,48 @@
+//! Defines tests that depend on audio-related registers surviving between tests.
+
+#![no_std]
+#![no_main]
+#![feature(custom_test_frameworks)]
+#![test_runner(gba_test::runner)]
+#![reexport_test_harness_main = "test_harness"]
+
+/// Master sound enable.
+pub const SOUNDCNT_X: *mut u16 = 0x0400_0084 as *mut u16;
+/// Timer 0 control.
+pub const TM0CNT_H: *mut u16 = 0x0400_0102 as *mut u16;
+/// Interrupt enable.
+pub const IE: *mut u16 = 0x0400_0200 as *mut u16;
+
+/// The interrupt flag for timer 0 overflowing, as used by a music driver.
+pub const INTERRUPT_TIMER_0: u16 = 0b0000_0000_0000_1000;
+
+gba_test::harness_main!();
+
+#[cfg(test)]
+mod tests {
+    use super::{IE, INTERRUPT_TIMER_0, SOUNDCNT_X, TM0CNT_H};
+    use core::ptr;
+    use gba_test::test;
+
+    #[test]
+    fn a_start_playback() {
+        unsafe {
+            ptr::write_volatile(SOUNDCNT_X, 0x0080);
+            // The timer is started without requesting interrupts, since no handler is installed.
+            ptr::write_volatile(TM0CNT_H, 0x0080);
+            ptr::write_volatile(IE, ptr::read_volatile(IE) | INTERRUPT_TIMER_0);
+        }
+    }
+
+    #[test]
+    fn b_playback_preserved() {
+        unsafe {
+            assert_eq!(ptr::read_volatile(SOUNDCNT_X) & 0x0080, 0x0080);
+            assert_eq!(ptr::read_volatile(TM0CNT_H), 0x0080);
+            assert_eq!(
+                ptr::read_volatile(IE) & INTERRUPT_TIMER_0,
+                INTERRUPT_TIMER_0
+            );
+        }
+    }
+}
@@ -0,0 +1,65 @@
+//! Defines tests that check the progress of the test run.
+
+#![no_std]
+#![no_main]
+#![feature(custom_test_frameworks)]
+#![test_runner(gba_test::runner)]
+#![reexport_test_harness_main = "test_harness"]
+
+gba_test::harness_main!();
+
+#[cfg(test)]
+mod tests {
+    use core::ptr;
+    use gba_test::{progress, test, Progress};
+
+    /// The index reported to the previous test.
+    static mut PREVIOUS_INDEX: Option<usize> = None;
+
+    /// Asserts that the current index is greater than that of the previous test.
+    fn assert_increasing(progress: Progress) {
+        // SAFETY: Only ever accessed on the main thread.
+        let previous = unsafe { &mut *ptr::addr_of_mut!(PREVIOUS_INDEX) };
+        if let Some(previous) = *previous {
+            assert!(progress.current_index > previous);
+        }
+        *previous = Some(progress.current_index);
+    }
+
+    #[test]
+    fn first() {
+        let progress = progress().unwrap();
+        assert_eq!(
+            progress,
+            Progress {
+                current_index: 0,
+                total: 4,
+            }
+        );
+        assert_increasing(progress);
+    }
+
+    #[test]
+    #[ignore]
+    fn ignored() {}
+
+    #[test]
+    fn second() {
+        let progress = progress().unwrap();
+        assert_eq!(progress.total, 4);
+        assert_increasing(progress);
+    }
+
+    #[test]
+    fn third() {
+        let progress = progress().unwrap();
+        assert_eq!(
+            progress,
+            Progress {
+                current_index: 3,
+                total: 4,
+            }
+        );
+        assert_increasing(progress);
+    }
+}
@@ -0,0 +1,47 @@
+//! Defines tests checking properties against generated inputs.
+
+#![no_std]
+#![no_main]
+#![feature(custom_test_frameworks)]
+#![test_runner(gba_test::runner)]
+#![reexport_test_harness_main = "test_harness"]
+
+gba_test::harness_main!();
+
+#[cfg(test)]
+mod tests {
+    use gba_test::{check, test};
+
+    #[test]
+    fn deterministic() {
+        let mut first = [(0u32, 0u16); 10];
+        let mut second = [(0u32, 0u16); 10];
+
+        let mut index = 0;
+        check!(iterations = 10, |x: u32, y: u16| {
+            first[index] = (x, y);
+            index += 1;
+            true
+        });
+        let mut index = 0;
+        check!(iterations = 10, |x: u32, y: u16| {
+            second[index] = (x, y);
+            index += 1;
+            true
+        });
+
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn property_fails() {
+        check!(seed = 1, |x: u8, y: Option<bool>| x < 128 || y.is_none());
+    }
+
+    #[test]
+    fn property_holds() {
+        check!(|values: [u8; 4]| -> bool {
+            values.iter().map(|&value| value as u32).sum::<u32>() <= 1020
+        });
+    }
+}
@@ -0,0 +1,61 @@
+//! Defines tests that are each run multiple times, including one that only fails sometimes.
+
+#![no_std]
+#![no_main]
+#![feature(custom_test_frameworks)]
+#![test_runner(gba_test::runner)]
+#![reexport_test_harness_main = "test_harness"]
+
+#[cfg(test)]
+#[no_mangle]
+pub fn main() {
+    gba_test::set_repeat_count(3);
+    test_harness();
+    loop {}
+}
+
+#[cfg(test)]
+mod tests {
+    use core::ptr;
+    use gba_test::test;
+
+    static mut FLAKY_RUNS: u32 = 0;
+    static mut PASSES_RUNS: u32 = 0;
+
+    #[test]
+    fn always_fails() {
+        panic!("failed");
+    }
+
+    #[test]
+    fn flaky() {
+        // SAFETY: Only ever accessed on the main thread.
+        let runs = unsafe {
+            *ptr::addr_of_mut!(FLAKY_RUNS) += 1;
+            *ptr::addr_of!(FLAKY_RUNS)
+        };
+        assert!(runs != 2, "flaked");
+    }
+
+    #[test]
+    fn passes() {
+        // SAFETY: Only ever accessed on the main thread.
+        unsafe {
+            *ptr::addr_of_mut!(PASSES_RUNS) += 1;
+        }
+    }
+
+    #[test]
+    fn runs_every_iteration() {
+        // Tests are run in order, so every iteration of `passes` has been run.
+        //
+        // SAFETY: Only ever accessed on the main thread.
+        assert_eq!(unsafe { *ptr::addr_of!(PASSES_RUNS) }, 3);
+    }
+
+    #[test]
+    #[should_panic]
+    fn should_panic() {
+        panic!("expected");
+    }
+}
@@ -0,0 +1,40 @@
+//! Defines tests that require hardware, only some of which is present under mGBA.
+
+#![no_std]
+#![no_main]
+#![feature(custom_test_frameworks)]
+#![test_runner(gba_test::runner)]
+#![reexport_test_harness_main = "test_harness"]
+
+gba_test::harness_main!();
+
+#[cfg(test)]
+mod tests {
+    use gba_test::test;
+
+    #[test(requires = "link_cable")]
+    fn link_cable() {
+        panic!("test was run without a link cable");
+    }
+
+    #[test(requires = "rtc")]
+    fn rtc() {
+        panic!("test was run without a real-time clock");
+    }
+
+    #[test(requires = "rtc")]
+    #[should_panic]
+    fn rtc_should_panic() {}
+
+    #[test(requires = "sram")]
+    fn sram() {
+        // SAFETY: SRAM is present, as the test results are written to it.
+        unsafe {
+            let byte = 0x0E00_FFFF as *mut u8;
+            let original = byte.read_volatile();
+            byte.write_volatile(0x5A);
+            assert_eq!(byte.read_volatile(), 0x5A);
+            byte.write_volatile(original);
+        }
+    }
+}
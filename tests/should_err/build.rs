@@ -0,0 +1,16 @@
+fn main() {
+    let out_dir = std::env::var("OUT_DIR").unwrap();
+    let out_dir_file = format!("{}/{}", out_dir, "rsrt0.o");
+
+    let as_output = std::process::Command::new("arm-none-eabi-as")
+        .args(&["-o", out_dir_file.as_str()])
+        .arg("-mthumb-interwork")
+        .arg("-mcpu=arm7tdmi")
+        .arg("src/rsrt0.s")
+        .output()
+        .expect("failed to run arm-none-eabi-as");
+    if !as_output.status.success() {
+        panic!("{}", String::from_utf8_lossy(&as_output.stderr));
+    }
+    println!("cargo:rustc-link-search={}", out_dir);
+}
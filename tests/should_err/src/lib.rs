@@ -0,0 +1,59 @@
+//! Defines tests that are expected to return an `Err`.
+
+#![no_std]
+#![no_main]
+#![feature(custom_test_frameworks)]
+#![test_runner(gba_test::runner)]
+#![reexport_test_harness_main = "test_harness"]
+
+pub const MESSAGE: &str = "foo";
+
+gba_test::harness_main!();
+
+#[cfg(test)]
+mod tests {
+    use core::num::TryFromIntError;
+    use gba_test::test;
+
+    #[test]
+    #[should_err]
+    fn err() -> Result<(), &'static str> {
+        Err("foo")
+    }
+
+    #[test]
+    #[should_err]
+    fn ok() -> Result<(), &'static str> {
+        Ok(())
+    }
+
+    #[test]
+    #[should_err(expected = "foo")]
+    fn expected_literal() -> Result<(), &'static str> {
+        Err("foo bar")
+    }
+
+    #[test]
+    #[should_err = crate::MESSAGE]
+    fn expected_constant() -> Result<(), &'static str> {
+        Err("bar foo")
+    }
+
+    #[test]
+    #[should_err(expected = "foo")]
+    fn expected_mismatch() -> Result<(), &'static str> {
+        Err("bar")
+    }
+
+    #[test]
+    #[should_err(expected = "TryFromIntError")]
+    fn expected_debug() -> Result<u8, TryFromIntError> {
+        u8::try_from(256u32)
+    }
+
+    #[test]
+    #[should_err]
+    fn panics() -> Result<(), &'static str> {
+        panic!("foo");
+    }
+}
@@ -0,0 +1,56 @@
+//! Defines tests that are expected to panic.
+
+#![no_std]
+#![no_main]
+#![feature(custom_test_frameworks)]
+#![test_runner(gba_test::runner)]
+#![reexport_test_harness_main = "test_harness"]
+
+pub const MESSAGE: &str = "foo";
+
+gba_test::harness_main!();
+
+#[cfg(test)]
+mod tests {
+    use gba_test::test;
+
+    #[test]
+    #[should_panic]
+    fn panics() {
+        panic!("foo");
+    }
+
+    #[test]
+    #[should_panic]
+    fn does_not_panic() {}
+
+    #[test]
+    #[should_panic(expected = "foo")]
+    fn expected_literal() {
+        panic!("foo bar");
+    }
+
+    #[test]
+    #[should_panic(expected = crate::MESSAGE)]
+    fn expected_constant() {
+        panic!("bar foo");
+    }
+
+    #[test]
+    #[should_panic(expected = "foo")]
+    fn expected_mismatch() {
+        panic!("bar");
+    }
+
+    #[test]
+    #[should_panic(expected = "o b")]
+    fn expected_formatted() {
+        panic!("{} {}", "foo", "bar");
+    }
+
+    #[test]
+    #[should_panic(expected = "foo")]
+    fn expected_formatted_mismatch() {
+        panic!("{}{}", "fo", "bar");
+    }
+}
@@ -0,0 +1,5 @@
+//! A module panicking with the same message as the crate root, from a different file.
+
+pub fn transfer() {
+    panic!("foo");
+}
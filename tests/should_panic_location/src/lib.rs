@@ -0,0 +1,58 @@
+//! Defines tests that are expected to panic at a specific location.
+
+#![no_std]
+#![no_main]
+#![feature(custom_test_frameworks)]
+#![test_runner(gba_test::runner)]
+#![reexport_test_harness_main = "test_harness"]
+
+pub mod dma;
+
+pub const FILE: &str = "src/dma.rs";
+
+gba_test::harness_main!();
+
+#[cfg(test)]
+mod tests {
+    use gba_test::test;
+
+    #[test]
+    #[should_panic(location = "src/lib.rs")]
+    fn location() {
+        panic!("foo");
+    }
+
+    #[test]
+    #[should_panic(location = crate::FILE)]
+    fn location_constant() {
+        crate::dma::transfer();
+    }
+
+    #[test]
+    #[should_panic(location = "src/dma.rs")]
+    fn location_mismatch() {
+        panic!("foo");
+    }
+
+    #[test]
+    #[should_panic(location = "src/dma.rs")]
+    fn does_not_panic() {}
+
+    #[test]
+    #[should_panic(expected = "foo", location = "dma")]
+    fn expected_and_location() {
+        crate::dma::transfer();
+    }
+
+    #[test]
+    #[should_panic(expected = "bar", location = "dma")]
+    fn expected_mismatch_location_match() {
+        crate::dma::transfer();
+    }
+
+    #[test]
+    #[should_panic(expected = "foo", location = "dma")]
+    fn expected_match_location_mismatch() {
+        panic!("foo");
+    }
+}
@@ -0,0 +1,44 @@
+//! Defines multiple tests to be run in a shuffled order.
+
+#![no_std]
+#![no_main]
+#![feature(custom_test_frameworks)]
+#![test_runner(gba_test::runner)]
+#![reexport_test_harness_main = "test_harness"]
+
+pub fn add(left: usize, right: usize) -> usize {
+    left + right
+}
+
+gba_test::harness_main!();
+
+#[cfg(test)]
+mod tests {
+    use super::add;
+    use gba_test::test;
+
+    #[test]
+    fn a() {
+        assert_eq!(add(1, 0), 1);
+    }
+
+    #[test]
+    fn b() {
+        assert_eq!(add(1, 1), 2);
+    }
+
+    #[test]
+    fn c() {
+        assert_eq!(add(1, 2), 3);
+    }
+
+    #[test]
+    fn d() {
+        assert_eq!(add(1, 3), 4);
+    }
+
+    #[test]
+    fn e() {
+        assert_eq!(add(1, 4), 5);
+    }
+}
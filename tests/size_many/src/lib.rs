@@ -0,0 +1,113 @@
+//! Defines many empty tests, for measuring the ROM space taken by each test.
+
+#![no_std]
+#![no_main]
+#![feature(custom_test_frameworks)]
+#![test_runner(gba_test::runner)]
+#![reexport_test_harness_main = "test_harness"]
+
+gba_test::harness_main!();
+
+#[cfg(test)]
+mod tests {
+    use gba_test::test;
+
+    #[test]
+    fn test_00() {}
+
+    #[test]
+    fn test_01() {}
+
+    #[test]
+    fn test_02() {}
+
+    #[test]
+    fn test_03() {}
+
+    #[test]
+    fn test_04() {}
+
+    #[test]
+    fn test_05() {}
+
+    #[test]
+    fn test_06() {}
+
+    #[test]
+    fn test_07() {}
+
+    #[test]
+    fn test_08() {}
+
+    #[test]
+    fn test_09() {}
+
+    #[test]
+    fn test_10() {}
+
+    #[test]
+    fn test_11() {}
+
+    #[test]
+    fn test_12() {}
+
+    #[test]
+    fn test_13() {}
+
+    #[test]
+    fn test_14() {}
+
+    #[test]
+    fn test_15() {}
+
+    #[test]
+    fn test_16() {}
+
+    #[test]
+    fn test_17() {}
+
+    #[test]
+    fn test_18() {}
+
+    #[test]
+    fn test_19() {}
+
+    #[test]
+    fn test_20() {}
+
+    #[test]
+    fn test_21() {}
+
+    #[test]
+    fn test_22() {}
+
+    #[test]
+    fn test_23() {}
+
+    #[test]
+    fn test_24() {}
+
+    #[test]
+    fn test_25() {}
+
+    #[test]
+    fn test_26() {}
+
+    #[test]
+    fn test_27() {}
+
+    #[test]
+    fn test_28() {}
+
+    #[test]
+    fn test_29() {}
+
+    #[test]
+    fn test_30() {}
+
+    #[test]
+    fn test_31() {}
+
+    #[test]
+    fn test_32() {}
+}
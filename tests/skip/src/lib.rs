@@ -0,0 +1,44 @@
+//! Defines tests that skip themselves while running.
+
+#![no_std]
+#![no_main]
+#![feature(custom_test_frameworks)]
+#![test_runner(gba_test::runner)]
+#![reexport_test_harness_main = "test_harness"]
+
+gba_test::harness_main!();
+
+#[cfg(test)]
+mod tests {
+    use gba_test::{output, skip, test};
+
+    #[test]
+    fn formatted() {
+        skip!("no {} detected", "rumble cart");
+    }
+
+    #[test]
+    fn not_skipped() {
+        if false {
+            skip!("unreachable");
+        }
+    }
+
+    #[test]
+    #[should_err]
+    fn should_err() -> Result<(), &'static str> {
+        skip!("no rumble cart detected");
+    }
+
+    #[test]
+    #[should_panic]
+    fn should_panic() {
+        skip!("no rumble cart detected");
+    }
+
+    #[test]
+    fn skipped() {
+        output!("probing for rumble cart");
+        skip!("no rumble cart detected");
+    }
+}
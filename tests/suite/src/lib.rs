@@ -0,0 +1,30 @@
+//! Defines tests grouped into suites.
+
+#![no_std]
+#![no_main]
+#![feature(custom_test_frameworks)]
+#![test_runner(gba_test::runner)]
+#![reexport_test_harness_main = "test_harness"]
+
+gba_test::harness_main!();
+
+#[cfg(test)]
+mod tests {
+    use gba_test::test;
+
+    #[test]
+    fn default() {}
+
+    #[test(suite = "slow")]
+    fn slow() {}
+
+    #[test(suite = "hardware-only")]
+    fn hardware_only() {
+        panic!("foo");
+    }
+
+    #[test(cases(1, 2), suite = "slow")]
+    fn cases(x: u32) {
+        assert_eq!(x, 1);
+    }
+}
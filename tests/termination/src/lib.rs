@@ -0,0 +1,61 @@
+//! Defines tests returning values that pass or fail them.
+
+#![no_std]
+#![no_main]
+#![feature(custom_test_frameworks)]
+#![test_runner(gba_test::runner)]
+#![reexport_test_harness_main = "test_harness"]
+
+gba_test::harness_main!();
+
+#[cfg(test)]
+mod tests {
+    use gba_test::{test, Failure};
+
+    #[test]
+    fn err() -> Result<(), &'static str> {
+        Err("bad")
+    }
+
+    #[test]
+    fn failure() -> Failure {
+        Failure("precondition not met")
+    }
+
+    #[test]
+    fn failure_err() -> Result<(), Failure> {
+        Err(Failure("precondition not met"))
+    }
+
+    #[test]
+    fn nested() -> Result<Option<()>, ()> {
+        Ok(None)
+    }
+
+    #[test]
+    fn none() -> Option<()> {
+        None
+    }
+
+    #[test]
+    fn ok() -> Result<(), &'static str> {
+        Ok(())
+    }
+
+    #[test]
+    fn question_mark() -> Option<()> {
+        let value: Option<u32> = None;
+        value?;
+        Some(())
+    }
+
+    #[test(cases(0, 1))]
+    fn cases(x: u32) -> Option<()> {
+        (x > 0).then_some(())
+    }
+
+    #[test]
+    fn some() -> Option<()> {
+        Some(())
+    }
+}
@@ -0,0 +1,23 @@
+//! Defines a test that is missing its `#[gba_test::test]` attribute, so no tests are registered.
+
+#![no_std]
+#![no_main]
+#![feature(custom_test_frameworks)]
+#![test_runner(gba_test::runner)]
+#![reexport_test_harness_main = "test_harness"]
+
+#[cfg(test)]
+#[no_mangle]
+pub fn main() {
+    gba_test::assert_harness_configured!();
+    test_harness();
+    loop {}
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(dead_code)]
+    fn forgotten() {
+        assert_eq!(2 + 2, 4);
+    }
+}
@@ -0,0 +1,29 @@
+//! Defines tests verifying that VRAM is cleared between tests.
+
+#![no_std]
+#![no_main]
+#![feature(custom_test_frameworks)]
+#![test_runner(gba_test::runner)]
+#![reexport_test_harness_main = "test_harness"]
+
+gba_test::harness_main!();
+
+#[cfg(test)]
+mod tests {
+    use core::ptr;
+    use gba_test::test;
+
+    /// An arbitrary location within VRAM.
+    const VRAM: *mut u16 = 0x0600_0100 as *mut u16;
+
+    #[test]
+    fn a_write_sentinel() {
+        unsafe { ptr::write_volatile(VRAM, 0xBEEF) };
+        assert_eq!(unsafe { ptr::read_volatile(VRAM) }, 0xBEEF);
+    }
+
+    #[test]
+    fn b_sentinel_cleared() {
+        assert_eq!(unsafe { ptr::read_volatile(VRAM) }, 0);
+    }
+}
@@ -0,0 +1,78 @@
+//! Defines tests writing to video memory during and outside of vertical blank.
+
+#![no_std]
+#![no_main]
+#![feature(custom_test_frameworks)]
+#![test_runner(gba_test::runner)]
+#![reexport_test_harness_main = "test_harness"]
+
+use core::ptr;
+
+/// Display control.
+const DISPCNT: *mut u16 = 0x0400_0000 as *mut u16;
+/// The scanline currently being drawn.
+const VCOUNT: *const u16 = 0x0400_0006 as *const u16;
+
+/// The first palette entry.
+pub const PALETTE: *mut u16 = 0x0500_0000 as *mut u16;
+
+/// Enables or disables forced blank.
+pub fn set_forced_blank(enabled: bool) {
+    unsafe { ptr::write_volatile(DISPCNT, if enabled { 0x0080 } else { 0x0000 }) }
+}
+
+/// Waits until the given scanline is being drawn.
+pub fn wait_for_scanline(scanline: u16) {
+    while unsafe { ptr::read_volatile(VCOUNT) } != scanline {}
+}
+
+gba_test::harness_main!();
+
+#[cfg(test)]
+mod tests {
+    use super::{set_forced_blank, wait_for_scanline, PALETTE};
+    use gba_test::{assert_in_vblank, test, vram_guard};
+
+    #[test]
+    fn write_in_vblank() {
+        set_forced_blank(false);
+        wait_for_scanline(160);
+        unsafe {
+            vram_guard::checked_write(PALETTE, 0x7FFF);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "VRAM write at scanline 10 outside vblank")]
+    fn write_outside_vblank() {
+        set_forced_blank(false);
+        wait_for_scanline(10);
+        unsafe {
+            vram_guard::checked_write(PALETTE, 0x7FFF);
+        }
+    }
+
+    #[test]
+    fn write_in_forced_blank() {
+        set_forced_blank(true);
+        wait_for_scanline(10);
+        unsafe {
+            vram_guard::checked_write(PALETTE, 0x7FFF);
+        }
+    }
+
+    #[test]
+    fn assert_in_vblank() {
+        set_forced_blank(false);
+        wait_for_scanline(200);
+        assert_in_vblank!();
+    }
+
+    #[test]
+    #[should_panic(expected = "in vblank (at scanline 10)")]
+    fn assert_in_vblank_fails() {
+        set_forced_blank(false);
+        wait_for_scanline(10);
+        assert_in_vblank!();
+    }
+}
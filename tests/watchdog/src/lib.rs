@@ -0,0 +1,30 @@
+//! Defines a test that never finishes, followed by a test that passes.
+
+#![no_std]
+#![no_main]
+#![feature(custom_test_frameworks)]
+#![test_runner(gba_test::runner)]
+#![reexport_test_harness_main = "test_harness"]
+
+#[cfg(test)]
+#[no_mangle]
+pub fn main() {
+    gba_test::set_default_timeout(10);
+    test_harness();
+    loop {}
+}
+
+#[cfg(test)]
+mod tests {
+    use gba_test::test;
+
+    #[test]
+    fn hangs() {
+        loop {
+            core::hint::spin_loop();
+        }
+    }
+
+    #[test]
+    fn passes() {}
+}
@@ -0,0 +1,34 @@
+//! Defines tests that repeatedly time out while waiting for frames, followed by a test that waits
+//! for frames and passes.
+
+#![no_std]
+#![no_main]
+#![feature(custom_test_frameworks)]
+#![test_runner(gba_test::runner)]
+#![reexport_test_harness_main = "test_harness"]
+
+#[cfg(test)]
+#[no_mangle]
+pub fn main() {
+    gba_test::set_default_timeout(5);
+    test_harness();
+    loop {}
+}
+
+#[cfg(test)]
+mod tests {
+    use gba_test::{frames, test, wait_frames};
+
+    // Each timeout interrupts the BIOS call made by `wait_frames()`, which must not leave anything
+    // behind on the BIOS's stacks.
+    #[test(cases(1, 2, 3, 4, 5, 6, 7, 8))]
+    fn times_out(_attempt: u32) {
+        wait_frames(10);
+    }
+
+    #[test]
+    fn waits() {
+        wait_frames(2);
+        assert_eq!(frames(), 2);
+    }
+}